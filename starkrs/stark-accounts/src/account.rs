@@ -0,0 +1,905 @@
+use crate::call::{encode_calls, Call};
+use crate::nonce::NonceManager;
+use crate::signer::Signer;
+use crate::simulation::Simulation;
+use stark_core::types::contract::SierraClass;
+use stark_core::types::fee::{FeeEstimate, SimulationFlag, SimulationFlagForEstimateFee};
+use stark_core::types::request::{BlockNumber, BlockTag};
+use stark_core::types::transaction::{
+    BroadcastedDeclareTransaction, BroadcastedDeclareTransactionV3, BroadcastedInvokeTransaction,
+    BroadcastedInvokeTransactionV1, BroadcastedInvokeTransactionV3, BroadcastedTransaction,
+    DataAvailabilityMode, ResourceBounds, ResourceBoundsMapping,
+};
+use stark_core::Felt;
+use stark_provider::pending::PendingTransaction;
+use stark_provider::rpc::ProviderError;
+use stark_provider::Provider;
+use std::fmt;
+use std::sync::Arc;
+
+pub const ETH_TOKEN_ADDRESS: &str =
+    "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+pub const STRK_TOKEN_ADDRESS: &str =
+    "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d";
+
+/// The token used to pay for a transaction. ETH pays through V1 transactions with a `max_fee`,
+/// STRK through V3 transactions with resource bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FeeToken {
+    #[default]
+    Eth,
+    Strk,
+}
+
+impl FeeToken {
+    pub fn address(&self) -> Felt {
+        match self {
+            FeeToken::Eth => Felt::from_hex(ETH_TOKEN_ADDRESS).unwrap(),
+            FeeToken::Strk => Felt::from_hex(STRK_TOKEN_ADDRESS).unwrap(),
+        }
+    }
+}
+
+/// Safety margins applied to a fee estimate when the caller does not set the fee explicitly,
+/// in basis points: `15_000` scales a value by 1.5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeMultipliers {
+    /// Applied to `overall_fee` to derive a V1 `max_fee`.
+    pub overall_fee: u32,
+    /// Applied to the estimated gas amounts to derive V3 `max_amount`s.
+    pub gas_amount: u32,
+    /// Applied to the estimated gas prices to derive V3 `max_price_per_unit`s.
+    pub gas_price: u32,
+}
+
+impl Default for FeeMultipliers {
+    fn default() -> Self {
+        Self {
+            overall_fee: 15_000,
+            gas_amount: 15_000,
+            gas_price: 15_000,
+        }
+    }
+}
+
+impl FeeMultipliers {
+    pub fn max_fee(&self, estimate: &FeeEstimate) -> Felt {
+        Felt::from(scale(estimate.overall_fee, self.overall_fee))
+    }
+
+    pub fn resource_bounds(&self, estimate: &FeeEstimate) -> ResourceBoundsMapping {
+        let bounds = |amount: u64, price: u128| ResourceBounds {
+            max_amount: u64::try_from(scale(amount.into(), self.gas_amount)).unwrap_or(u64::MAX),
+            max_price_per_unit: scale(price, self.gas_price),
+        };
+        ResourceBoundsMapping {
+            l1_gas: bounds(estimate.l1_gas_consumed, estimate.l1_gas_price),
+            l2_gas: bounds(estimate.l2_gas_consumed, estimate.l2_gas_price),
+            l1_data_gas: Some(bounds(
+                estimate.l1_data_gas_consumed,
+                estimate.l1_data_gas_price,
+            )),
+        }
+    }
+}
+
+/// `value * basis_points / 10_000`, rounded up and saturating at `u128::MAX`.
+fn scale(value: u128, basis_points: u32) -> u128 {
+    let basis_points = u128::from(basis_points);
+    let (whole, rest) = (value / 10_000, value % 10_000);
+    whole
+        .checked_mul(basis_points)
+        .and_then(|scaled| scaled.checked_add((rest * basis_points).div_ceil(10_000)))
+        .unwrap_or(u128::MAX)
+}
+
+#[derive(Debug)]
+pub enum AccountError<S> {
+    Signing(S),
+    Provider(ProviderError),
+    InvalidResponse(serde_json::Error),
+}
+
+impl<S: fmt::Display> fmt::Display for AccountError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountError::Signing(err) => write!(f, "signing failed: {err}"),
+            AccountError::Provider(err) => write!(f, "provider error: {err}"),
+            AccountError::InvalidResponse(err) => write!(f, "invalid node response: {err}"),
+        }
+    }
+}
+
+impl<S: fmt::Debug + fmt::Display> std::error::Error for AccountError<S> {}
+
+/// An account controlled by a single signing key.
+pub struct SingleOwnerAccount<P, S> {
+    provider: Arc<P>,
+    signer: S,
+    address: Felt,
+    chain_id: Felt,
+    fee_token: FeeToken,
+    fee_multipliers: FeeMultipliers,
+    nonce_manager: Option<Arc<NonceManager<P>>>,
+}
+
+impl<P: Provider, S: Signer + Sync> SingleOwnerAccount<P, S> {
+    pub fn new(provider: Arc<P>, signer: S, address: Felt, chain_id: Felt) -> Self {
+        Self {
+            provider,
+            signer,
+            address,
+            chain_id,
+            fee_token: FeeToken::default(),
+            fee_multipliers: FeeMultipliers::default(),
+            nonce_manager: None,
+        }
+    }
+
+    /// Takes nonces from `nonce_manager` instead of querying the node for every execution.
+    pub fn with_nonce_manager(mut self, nonce_manager: Arc<NonceManager<P>>) -> Self {
+        self.nonce_manager = Some(nonce_manager);
+        self
+    }
+
+    /// Sets the default fee token for executions built from this account.
+    pub fn with_fee_token(mut self, fee_token: FeeToken) -> Self {
+        self.fee_token = fee_token;
+        self
+    }
+
+    pub fn with_fee_multipliers(mut self, fee_multipliers: FeeMultipliers) -> Self {
+        self.fee_multipliers = fee_multipliers;
+        self
+    }
+
+    pub fn address(&self) -> Felt {
+        self.address
+    }
+
+    pub fn chain_id(&self) -> Felt {
+        self.chain_id
+    }
+
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub fn nonce_manager(&self) -> Option<&Arc<NonceManager<P>>> {
+        self.nonce_manager.as_ref()
+    }
+
+    pub fn execute(&self, calls: Vec<Call>) -> Execution<'_, P, S> {
+        Execution {
+            account: self,
+            calls,
+            nonce: None,
+            fee_token: self.fee_token,
+            max_fee: None,
+            resource_bounds: None,
+            tip: 0,
+            fee_multipliers: self.fee_multipliers,
+            skip_validate: false,
+        }
+    }
+
+    /// Declares a Sierra class whose CASM hashes to `compiled_class_hash`. See [`Declaration`].
+    pub fn declare(
+        &self,
+        contract_class: SierraClass,
+        compiled_class_hash: Felt,
+    ) -> Declaration<'_, P, S> {
+        Declaration {
+            account: self,
+            contract_class,
+            compiled_class_hash,
+            nonce: None,
+            resource_bounds: None,
+            tip: 0,
+            fee_multipliers: self.fee_multipliers,
+        }
+    }
+
+    /// Simulates executing `calls` with the account's defaults. See [`Execution::simulate`].
+    pub async fn simulate(&self, calls: Vec<Call>) -> Result<Simulation, AccountError<S::Error>> {
+        self.execute(calls).simulate().await
+    }
+
+    pub async fn get_nonce(&self) -> Result<Felt, AccountError<S::Error>> {
+        self.provider
+            .stark_getnonce(self.address, BlockNumber::BlockTag(BlockTag::Pending))
+            .await
+            .map_err(AccountError::Provider)
+    }
+
+    /// Reports the outcome of submitting a transaction with a managed `nonce` to the nonce
+    /// manager, if one is attached.
+    async fn report_submission(&self, nonce: Felt, result: Result<Felt, &ProviderError>) {
+        if let Some(manager) = &self.nonce_manager {
+            match result {
+                Ok(transaction_hash) => manager.submitted(nonce, transaction_hash).await,
+                Err(err) => manager.failed(nonce, err).await,
+            }
+        }
+    }
+}
+
+/// An invoke transaction being built from a set of calls.
+pub struct Execution<'a, P, S> {
+    account: &'a SingleOwnerAccount<P, S>,
+    calls: Vec<Call>,
+    nonce: Option<Felt>,
+    fee_token: FeeToken,
+    max_fee: Option<Felt>,
+    resource_bounds: Option<ResourceBoundsMapping>,
+    tip: u64,
+    fee_multipliers: FeeMultipliers,
+    skip_validate: bool,
+}
+
+impl<'a, P: Provider, S: Signer + Sync> Execution<'a, P, S> {
+    pub fn nonce(mut self, nonce: Felt) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn fee_token(mut self, fee_token: FeeToken) -> Self {
+        self.fee_token = fee_token;
+        self
+    }
+
+    /// Sets the `max_fee` of an ETH (V1) execution instead of estimating it.
+    pub fn max_fee(mut self, max_fee: Felt) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    /// Sets the resource bounds of a STRK (V3) execution instead of estimating them.
+    pub fn resource_bounds(mut self, resource_bounds: ResourceBoundsMapping) -> Self {
+        self.resource_bounds = Some(resource_bounds);
+        self
+    }
+
+    pub fn tip(mut self, tip: u64) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn fee_multipliers(mut self, fee_multipliers: FeeMultipliers) -> Self {
+        self.fee_multipliers = fee_multipliers;
+        self
+    }
+
+    /// Estimates and simulates without running `__validate__`, so both are run over an unsigned
+    /// transaction. The results exclude the cost of validation.
+    pub fn skip_validate(mut self, skip_validate: bool) -> Self {
+        self.skip_validate = skip_validate;
+        self
+    }
+
+    /// Estimates over a query-version transaction, so the signature sent along cannot be
+    /// replayed as a real transaction.
+    pub async fn estimate_fee(&self) -> Result<FeeEstimate, AccountError<S::Error>> {
+        let nonce = self.peek_nonce().await?;
+        self.estimate_fee_with_nonce(nonce).await
+    }
+
+    /// Runs the transaction against the pending block without submitting it, reporting its
+    /// trace, fee, events and balance changes.
+    ///
+    /// Unless a `max_fee` or resource bounds were set, the fee is left at zero and not charged.
+    /// The nonce is only peeked, never reserved. Like [`Execution::estimate_fee`], this sends a
+    /// query-version transaction.
+    pub async fn simulate(&self) -> Result<Simulation, AccountError<S::Error>> {
+        let nonce = self.peek_nonce().await?;
+        let (transaction, charge_fee) = match self.fee_token {
+            FeeToken::Eth => (
+                self.build_v1(nonce, self.max_fee.unwrap_or(Felt::ZERO), true),
+                self.max_fee.is_some(),
+            ),
+            FeeToken::Strk => (
+                self.build_v3(nonce, self.resource_bounds.unwrap_or_default(), true),
+                self.resource_bounds.is_some(),
+            ),
+        };
+        let mut simulation_flags = Vec::new();
+        if !charge_fee {
+            simulation_flags.push(SimulationFlag::SkipFeeCharge);
+        }
+        let transaction = if self.skip_validate {
+            simulation_flags.push(SimulationFlag::SkipValidate);
+            transaction
+        } else {
+            self.sign(transaction).await?
+        };
+        let mut simulated = self
+            .account
+            .provider
+            .simulate_transactions(
+                BlockNumber::BlockTag(BlockTag::Pending),
+                &[BroadcastedTransaction::Invoke(transaction)],
+                &simulation_flags,
+            )
+            .await
+            .map_err(AccountError::Provider)?;
+        let simulated = simulated.pop().ok_or_else(|| {
+            AccountError::InvalidResponse(serde::de::Error::custom("empty simulation list"))
+        })?;
+        Ok(Simulation::from(simulated))
+    }
+
+    /// Builds and signs the transaction, estimating the fee if it was not set explicitly.
+    ///
+    /// With a nonce manager attached, the nonce is reserved here and released again if
+    /// preparation fails. Once the transaction is submitted, report it back through
+    /// [`NonceManager::submitted`], or pass the error to [`NonceManager::failed`] if submission
+    /// failed; [`Execution::send`] does all of this.
+    pub async fn prepare(&self) -> Result<BroadcastedInvokeTransaction, AccountError<S::Error>> {
+        match (self.nonce, &self.account.nonce_manager) {
+            (Some(nonce), _) => self.prepare_with_nonce(nonce).await,
+            (None, Some(manager)) => {
+                let nonce = manager.next().await.map_err(AccountError::Provider)?;
+                let result = self.prepare_with_nonce(nonce).await;
+                if result.is_err() {
+                    manager.release(nonce).await;
+                }
+                result
+            }
+            (None, None) => {
+                let nonce = self.account.get_nonce().await?;
+                self.prepare_with_nonce(nonce).await
+            }
+        }
+    }
+
+    /// Prepares the transaction and submits it. Await the returned [`PendingTransaction`] for
+    /// its receipt.
+    pub async fn send(&self) -> Result<PendingTransaction<'a, P>, AccountError<S::Error>> {
+        let transaction = self.prepare().await?;
+        let nonce = match &transaction {
+            BroadcastedInvokeTransaction::V1(tx) => tx.nonce,
+            BroadcastedInvokeTransaction::V3(tx) => tx.nonce,
+        };
+        let result = self
+            .account
+            .provider
+            .add_invoke_transaction(transaction)
+            .await;
+        if self.nonce.is_none() {
+            self.account
+                .report_submission(nonce, result.as_ref().map(|result| result.transaction_hash))
+                .await;
+        }
+        let result = result.map_err(AccountError::Provider)?;
+        Ok(PendingTransaction::new(
+            self.account.provider.as_ref(),
+            result.transaction_hash,
+        ))
+    }
+
+    async fn peek_nonce(&self) -> Result<Felt, AccountError<S::Error>> {
+        match (self.nonce, &self.account.nonce_manager) {
+            (Some(nonce), _) => Ok(nonce),
+            (None, Some(manager)) => manager.peek().await.map_err(AccountError::Provider),
+            (None, None) => self.account.get_nonce().await,
+        }
+    }
+
+    async fn prepare_with_nonce(
+        &self,
+        nonce: Felt,
+    ) -> Result<BroadcastedInvokeTransaction, AccountError<S::Error>> {
+        match self.fee_token {
+            FeeToken::Eth => {
+                let max_fee = match self.max_fee {
+                    Some(max_fee) => max_fee,
+                    None => {
+                        let estimate = self.estimate_fee_with_nonce(nonce).await?;
+                        self.fee_multipliers.max_fee(&estimate)
+                    }
+                };
+                self.sign(self.build_v1(nonce, max_fee, false)).await
+            }
+            FeeToken::Strk => {
+                let resource_bounds = match self.resource_bounds {
+                    Some(resource_bounds) => resource_bounds,
+                    None => {
+                        let estimate = self.estimate_fee_with_nonce(nonce).await?;
+                        self.fee_multipliers.resource_bounds(&estimate)
+                    }
+                };
+                self.sign(self.build_v3(nonce, resource_bounds, false))
+                    .await
+            }
+        }
+    }
+
+    async fn estimate_fee_with_nonce(
+        &self,
+        nonce: Felt,
+    ) -> Result<FeeEstimate, AccountError<S::Error>> {
+        let transaction = match self.fee_token {
+            FeeToken::Eth => self.build_v1(nonce, Felt::ZERO, true),
+            FeeToken::Strk => self.build_v3(nonce, ResourceBoundsMapping::default(), true),
+        };
+        let (transaction, simulation_flags) = if self.skip_validate {
+            (
+                transaction,
+                vec![SimulationFlagForEstimateFee::SkipValidate],
+            )
+        } else {
+            (self.sign(transaction).await?, vec![])
+        };
+        let mut estimates = self
+            .account
+            .provider
+            .estimate_fee(
+                &[BroadcastedTransaction::Invoke(transaction)],
+                &simulation_flags,
+                BlockNumber::BlockTag(BlockTag::Pending),
+            )
+            .await
+            .map_err(AccountError::Provider)?;
+        estimates.pop().ok_or_else(|| {
+            AccountError::InvalidResponse(serde::de::Error::custom("empty fee estimate list"))
+        })
+    }
+
+    /// Builds an unsigned V1 transaction. A query transaction carries the query version and can
+    /// only be estimated or simulated.
+    fn build_v1(&self, nonce: Felt, max_fee: Felt, is_query: bool) -> BroadcastedInvokeTransaction {
+        BroadcastedInvokeTransaction::V1(BroadcastedInvokeTransactionV1 {
+            sender_address: self.account.address,
+            calldata: encode_calls(&self.calls),
+            max_fee,
+            signature: vec![],
+            nonce,
+            is_query,
+        })
+    }
+
+    fn build_v3(
+        &self,
+        nonce: Felt,
+        resource_bounds: ResourceBoundsMapping,
+        is_query: bool,
+    ) -> BroadcastedInvokeTransaction {
+        BroadcastedInvokeTransaction::V3(BroadcastedInvokeTransactionV3 {
+            sender_address: self.account.address,
+            calldata: encode_calls(&self.calls),
+            signature: vec![],
+            nonce,
+            resource_bounds,
+            tip: self.tip,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            is_query,
+        })
+    }
+
+    async fn sign(
+        &self,
+        mut transaction: BroadcastedInvokeTransaction,
+    ) -> Result<BroadcastedInvokeTransaction, AccountError<S::Error>> {
+        let hash = transaction.transaction_hash(self.account.chain_id);
+        let signature = self
+            .account
+            .signer
+            .sign_hash(&hash)
+            .await
+            .map_err(AccountError::Signing)?;
+        match &mut transaction {
+            BroadcastedInvokeTransaction::V1(tx) => tx.signature = vec![signature.r, signature.s],
+            BroadcastedInvokeTransaction::V3(tx) => tx.signature = vec![signature.r, signature.s],
+        }
+        Ok(transaction)
+    }
+}
+
+/// A declare transaction being built for a Sierra class. Sierra classes can only be declared
+/// through V3 transactions, so declarations always pay in STRK.
+pub struct Declaration<'a, P, S> {
+    account: &'a SingleOwnerAccount<P, S>,
+    contract_class: SierraClass,
+    compiled_class_hash: Felt,
+    nonce: Option<Felt>,
+    resource_bounds: Option<ResourceBoundsMapping>,
+    tip: u64,
+    fee_multipliers: FeeMultipliers,
+}
+
+impl<'a, P: Provider, S: Signer + Sync> Declaration<'a, P, S> {
+    pub fn nonce(mut self, nonce: Felt) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the resource bounds instead of estimating them.
+    pub fn resource_bounds(mut self, resource_bounds: ResourceBoundsMapping) -> Self {
+        self.resource_bounds = Some(resource_bounds);
+        self
+    }
+
+    pub fn tip(mut self, tip: u64) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    pub fn fee_multipliers(mut self, fee_multipliers: FeeMultipliers) -> Self {
+        self.fee_multipliers = fee_multipliers;
+        self
+    }
+
+    /// The hash the class will be declared under.
+    pub fn class_hash(&self) -> Felt {
+        self.contract_class.class_hash()
+    }
+
+    /// Estimates over a signed query-version transaction.
+    pub async fn estimate_fee(&self) -> Result<FeeEstimate, AccountError<S::Error>> {
+        let nonce = match (self.nonce, &self.account.nonce_manager) {
+            (Some(nonce), _) => nonce,
+            (None, Some(manager)) => manager.peek().await.map_err(AccountError::Provider)?,
+            (None, None) => self.account.get_nonce().await?,
+        };
+        self.estimate_fee_with_nonce(nonce).await
+    }
+
+    /// Builds and signs the transaction, estimating the fee if it was not set explicitly. Nonces
+    /// from a nonce manager are handled as in [`Execution::prepare`].
+    pub async fn prepare(&self) -> Result<BroadcastedDeclareTransaction, AccountError<S::Error>> {
+        match (self.nonce, &self.account.nonce_manager) {
+            (Some(nonce), _) => self.prepare_with_nonce(nonce).await,
+            (None, Some(manager)) => {
+                let nonce = manager.next().await.map_err(AccountError::Provider)?;
+                let result = self.prepare_with_nonce(nonce).await;
+                if result.is_err() {
+                    manager.release(nonce).await;
+                }
+                result
+            }
+            (None, None) => {
+                let nonce = self.account.get_nonce().await?;
+                self.prepare_with_nonce(nonce).await
+            }
+        }
+    }
+
+    /// Prepares the transaction and submits it. Await the returned [`PendingTransaction`] for
+    /// its receipt.
+    pub async fn send(&self) -> Result<PendingTransaction<'a, P>, AccountError<S::Error>> {
+        let transaction = self.prepare().await?;
+        let nonce = match &transaction {
+            BroadcastedDeclareTransaction::V1(tx) => tx.nonce,
+            BroadcastedDeclareTransaction::V3(tx) => tx.nonce,
+        };
+        let result = self
+            .account
+            .provider
+            .add_declare_transaction(transaction)
+            .await;
+        if self.nonce.is_none() {
+            self.account
+                .report_submission(nonce, result.as_ref().map(|result| result.transaction_hash))
+                .await;
+        }
+        let result = result.map_err(AccountError::Provider)?;
+        Ok(PendingTransaction::new(
+            self.account.provider.as_ref(),
+            result.transaction_hash,
+        ))
+    }
+
+    async fn prepare_with_nonce(
+        &self,
+        nonce: Felt,
+    ) -> Result<BroadcastedDeclareTransaction, AccountError<S::Error>> {
+        let resource_bounds = match self.resource_bounds {
+            Some(resource_bounds) => resource_bounds,
+            None => {
+                let estimate = self.estimate_fee_with_nonce(nonce).await?;
+                self.fee_multipliers.resource_bounds(&estimate)
+            }
+        };
+        self.sign(self.build(nonce, resource_bounds, false)).await
+    }
+
+    async fn estimate_fee_with_nonce(
+        &self,
+        nonce: Felt,
+    ) -> Result<FeeEstimate, AccountError<S::Error>> {
+        let transaction = self
+            .sign(self.build(nonce, ResourceBoundsMapping::default(), true))
+            .await?;
+        let mut estimates = self
+            .account
+            .provider
+            .estimate_fee(
+                &[BroadcastedTransaction::Declare(transaction)],
+                &[],
+                BlockNumber::BlockTag(BlockTag::Pending),
+            )
+            .await
+            .map_err(AccountError::Provider)?;
+        estimates.pop().ok_or_else(|| {
+            AccountError::InvalidResponse(serde::de::Error::custom("empty fee estimate list"))
+        })
+    }
+
+    fn build(
+        &self,
+        nonce: Felt,
+        resource_bounds: ResourceBoundsMapping,
+        is_query: bool,
+    ) -> BroadcastedDeclareTransactionV3 {
+        BroadcastedDeclareTransactionV3 {
+            sender_address: self.account.address,
+            compiled_class_hash: self.compiled_class_hash,
+            signature: vec![],
+            nonce,
+            contract_class: self.contract_class.clone(),
+            resource_bounds,
+            tip: self.tip,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            is_query,
+        }
+    }
+
+    async fn sign(
+        &self,
+        mut transaction: BroadcastedDeclareTransactionV3,
+    ) -> Result<BroadcastedDeclareTransaction, AccountError<S::Error>> {
+        let hash = transaction.transaction_hash(self.account.chain_id);
+        let signature = self
+            .account
+            .signer
+            .sign_hash(&hash)
+            .await
+            .map_err(AccountError::Signing)?;
+        transaction.signature = vec![signature.r, signature.s];
+        Ok(BroadcastedDeclareTransaction::V3(transaction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signer::LocalWallet;
+    use serde_json::json;
+    use stark_core::types::fee::PriceUnit;
+    use stark_provider::rpc::{JsonRpcClient, SubmissionError};
+    use stark_provider::transport::MemoryTransport;
+
+    fn estimate() -> FeeEstimate {
+        FeeEstimate {
+            l1_gas_consumed: 0,
+            l1_gas_price: 30_000_000_000_000,
+            l2_gas_consumed: 1_000_000,
+            l2_gas_price: 8_000_000_000,
+            l1_data_gas_consumed: 192,
+            l1_data_gas_price: 1_000,
+            overall_fee: 8_000_192_000_000_000,
+            unit: PriceUnit::Fri,
+        }
+    }
+
+    #[test]
+    fn resource_bounds_apply_multipliers() {
+        let multipliers = FeeMultipliers {
+            overall_fee: 10_000,
+            gas_amount: 20_000,
+            gas_price: 15_000,
+        };
+        let bounds = multipliers.resource_bounds(&estimate());
+        assert_eq!(bounds.l2_gas.max_amount, 2_000_000);
+        assert_eq!(bounds.l2_gas.max_price_per_unit, 12_000_000_000);
+        assert_eq!(bounds.l1_data_gas.unwrap().max_amount, 384);
+        assert_eq!(bounds.l1_gas.max_price_per_unit, 45_000_000_000_000);
+    }
+
+    #[test]
+    fn max_fee_does_not_overflow_u64() {
+        let estimate = FeeEstimate {
+            overall_fee: 30_000_000_000_000_000_000,
+            unit: PriceUnit::Wei,
+            ..estimate()
+        };
+        let max_fee = FeeMultipliers::default().max_fee(&estimate);
+        assert_eq!(max_fee, Felt::from(45_000_000_000_000_000_000u128));
+    }
+
+    #[test]
+    fn multipliers_keep_full_precision() {
+        // 2^53 + 1 wei is not representable as an f64.
+        let estimate = FeeEstimate {
+            overall_fee: (1 << 53) + 1,
+            ..estimate()
+        };
+        let multipliers = FeeMultipliers {
+            overall_fee: 10_000,
+            ..FeeMultipliers::default()
+        };
+        assert_eq!(
+            multipliers.max_fee(&estimate),
+            Felt::from((1u128 << 53) + 1)
+        );
+        assert_eq!(scale(3, 15_000), 5);
+        assert_eq!(scale(u128::MAX, 20_000), u128::MAX);
+    }
+
+    #[tokio::test]
+    async fn send_reports_the_nonce_back() {
+        let mut submissions = 0;
+        let provider = Arc::new(JsonRpcClient::new(MemoryTransport::with_handler(
+            move |request| {
+                if request["method"] == "starknet_getNonce" {
+                    // Another sender used nonces 7 and 8 behind the manager's back.
+                    return json!({"jsonrpc": "2.0", "id": request["id"], "result": "0x9"});
+                }
+                assert_eq!(request["method"], "starknet_addInvokeTransaction");
+                assert_eq!(request["params"][0]["max_fee"], "0x3e8");
+                assert_eq!(request["params"][0]["version"], "0x1");
+                let expected_nonce = if submissions == 0 { "0x7" } else { "0x9" };
+                assert_eq!(request["params"][0]["nonce"], expected_nonce);
+                submissions += 1;
+                if submissions == 1 {
+                    json!({"jsonrpc": "2.0", "id": request["id"], "error": {"code": 52, "message": "Invalid transaction nonce"}})
+                } else {
+                    json!({"jsonrpc": "2.0", "id": request["id"], "result": {"transaction_hash": "0xabc"}})
+                }
+            },
+        )));
+        let nonce_manager = Arc::new(NonceManager::with_initial_nonce(
+            provider.clone(),
+            Felt::TWO,
+            Felt::from(7u64),
+        ));
+        let account = SingleOwnerAccount::new(
+            provider,
+            LocalWallet::from_private_key(Felt::from(0x1234u64)),
+            Felt::TWO,
+            Felt::from_short_string("SN_SEPOLIA").unwrap(),
+        )
+        .with_nonce_manager(nonce_manager.clone());
+
+        let err = match account
+            .execute(vec![])
+            .max_fee(Felt::from(1000u64))
+            .send()
+            .await
+        {
+            Err(AccountError::Provider(err)) => err,
+            _ => panic!("the first submission is rejected"),
+        };
+        assert!(matches!(
+            err,
+            ProviderError::Submission(SubmissionError::InvalidTransactionNonce)
+        ));
+        assert_eq!(nonce_manager.peek().await.unwrap(), Felt::from(9u64));
+
+        let pending = account
+            .execute(vec![])
+            .max_fee(Felt::from(1000u64))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(pending.transaction_hash(), Felt::from(0xabcu64));
+        assert_eq!(nonce_manager.peek().await.unwrap(), Felt::from(10u64));
+    }
+
+    #[tokio::test]
+    async fn simulate_reports_balance_changes() {
+        let transfer = stark_core::utils::hash::get_selector_from_name("Transfer");
+        let provider = Arc::new(JsonRpcClient::new(MemoryTransport::with_handler(
+            move |request| match request["method"].as_str().unwrap() {
+                "starknet_getNonce" => {
+                    json!({"jsonrpc": "2.0", "id": request["id"], "result": "0x3"})
+                }
+                "starknet_simulateTransactions" => {
+                    let params = &request["params"];
+                    assert_eq!(params[0], "pending");
+                    assert_eq!(params[1][0]["nonce"], "0x3");
+                    assert_eq!(
+                        params[1][0]["version"],
+                        "0x100000000000000000000000000000001"
+                    );
+                    assert_eq!(params[1][0]["signature"], json!([]));
+                    assert_eq!(params[2], json!(["SKIP_FEE_CHARGE", "SKIP_VALIDATE"]));
+                    let call = json!({
+                        "contract_address": "0x2",
+                        "entry_point_selector": "0x1",
+                        "calldata": [],
+                        "caller_address": "0x0",
+                        "class_hash": "0x5",
+                        "entry_point_type": "EXTERNAL",
+                        "call_type": "CALL",
+                        "result": [],
+                        "calls": [],
+                        "events": [{"order": 0, "keys": [transfer, "0x2", "0x9"], "data": ["0x64", "0x0"]}],
+                        "messages": []
+                    });
+                    json!({"jsonrpc": "2.0", "id": request["id"], "result": [{
+                        "transaction_trace": {"type": "INVOKE", "execute_invocation": call},
+                        "fee_estimation": {
+                            "l1_gas_consumed": "0x0", "l1_gas_price": "0x1",
+                            "l2_gas_consumed": "0x3e8", "l2_gas_price": "0x2",
+                            "l1_data_gas_consumed": "0x80", "l1_data_gas_price": "0x1",
+                            "overall_fee": "0x850", "unit": "WEI"
+                        }
+                    }]})
+                }
+                method => panic!("unexpected {method}"),
+            },
+        )));
+        let account = SingleOwnerAccount::new(
+            provider,
+            LocalWallet::from_private_key(Felt::from(0x1234u64)),
+            Felt::TWO,
+            Felt::from_short_string("SN_SEPOLIA").unwrap(),
+        );
+
+        let simulation = account
+            .execute(vec![])
+            .skip_validate(true)
+            .simulate()
+            .await
+            .unwrap();
+        assert_eq!(simulation.fee_estimate.overall_fee, 0x850);
+        assert_eq!(simulation.revert_reason(), None);
+        assert_eq!(simulation.events.len(), 1);
+        assert_eq!(simulation.balance_changes.len(), 2);
+        assert_eq!(simulation.balance_changes[0].address, Felt::TWO);
+        assert_eq!(simulation.balance_changes[0].delta, (-100).into());
+    }
+
+    #[tokio::test]
+    async fn declare_signs_a_v3_transaction() {
+        let provider = Arc::new(JsonRpcClient::new(MemoryTransport::with_handler(
+            move |request| {
+                let result = match request["method"].as_str().unwrap() {
+                    "starknet_getNonce" => json!("0x4"),
+                    "starknet_addDeclareTransaction" => {
+                        let tx = &request["params"][0];
+                        assert_eq!(tx["type"], "DECLARE");
+                        assert_eq!(tx["version"], "0x3");
+                        assert_eq!(tx["nonce"], "0x4");
+                        assert_eq!(tx["compiled_class_hash"], "0xcafe");
+                        assert_eq!(tx["resource_bounds"]["l2_gas"]["max_amount"], "0x64");
+                        json!({"transaction_hash": "0xabc", "class_hash": "0x1"})
+                    }
+                    method => panic!("unexpected {method}"),
+                };
+                json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+            },
+        )));
+        let account = SingleOwnerAccount::new(
+            provider,
+            LocalWallet::from_private_key(Felt::from(0x1234u64)),
+            Felt::TWO,
+            Felt::from_short_string("SN_SEPOLIA").unwrap(),
+        );
+        let class = SierraClass {
+            sierra_program: vec![Felt::ONE],
+            contract_class_version: "0.1.0".to_string(),
+            entry_points_by_type: Default::default(),
+            abi: Some("[]".to_string()),
+        };
+        let declaration = account
+            .declare(class.clone(), Felt::from(0xcafeu64))
+            .resource_bounds(ResourceBoundsMapping {
+                l2_gas: ResourceBounds {
+                    max_amount: 100,
+                    max_price_per_unit: 1,
+                },
+                ..Default::default()
+            });
+        assert_eq!(declaration.class_hash(), class.class_hash());
+        let pending = declaration.send().await.unwrap();
+        assert_eq!(pending.transaction_hash(), Felt::from(0xabcu64));
+    }
+}
@@ -0,0 +1,21 @@
+use stark_core::Felt;
+
+/// A single contract call executed through an account's `__execute__` entry point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub to: Felt,
+    pub selector: Felt,
+    pub calldata: Vec<Felt>,
+}
+
+/// Encodes `calls` as the `Array<Call>` calldata expected by Cairo 1 accounts.
+pub fn encode_calls(calls: &[Call]) -> Vec<Felt> {
+    let mut calldata = vec![Felt::from(calls.len())];
+    for call in calls {
+        calldata.push(call.to);
+        calldata.push(call.selector);
+        calldata.push(Felt::from(call.calldata.len()));
+        calldata.extend_from_slice(&call.calldata);
+    }
+    calldata
+}
@@ -0,0 +1,283 @@
+use crate::account::{AccountError, FeeMultipliers, FeeToken};
+use crate::signer::Signer;
+use stark_core::types::fee::FeeEstimate;
+use stark_core::types::request::{BlockNumber, BlockTag};
+use stark_core::types::transaction::{
+    BroadcastedDeployAccountTransaction, BroadcastedDeployAccountTransactionV1,
+    BroadcastedDeployAccountTransactionV3, BroadcastedTransaction, DataAvailabilityMode,
+    ResourceBoundsMapping,
+};
+use stark_core::utils::hash::calculate_contract_address;
+use stark_core::Felt;
+use stark_provider::pending::PendingTransaction;
+use stark_provider::Provider;
+
+/// The class hash of OpenZeppelin's account contract, version 0.8.1, whose constructor takes the
+/// owner's public key.
+pub const OZ_ACCOUNT_CLASS_HASH: &str =
+    "0x05400e90f7e0ae78bd02c77cd75527280470e2fe19c54970dd79dc37a9d3645c";
+
+/// A `DEPLOY_ACCOUNT` transaction deploying an account contract at the address derived from its
+/// class hash, salt and constructor calldata. The account pays for its own deployment, so fund
+/// [`AccountDeployment::address`] before sending.
+pub struct AccountDeployment<'a, P, S> {
+    provider: &'a P,
+    signer: &'a S,
+    chain_id: Felt,
+    class_hash: Felt,
+    salt: Felt,
+    constructor_calldata: Vec<Felt>,
+    nonce: Felt,
+    fee_token: FeeToken,
+    max_fee: Option<Felt>,
+    resource_bounds: Option<ResourceBoundsMapping>,
+    fee_multipliers: FeeMultipliers,
+}
+
+impl<'a, P: Provider, S: Signer + Sync> AccountDeployment<'a, P, S> {
+    pub fn new(
+        provider: &'a P,
+        signer: &'a S,
+        chain_id: Felt,
+        class_hash: Felt,
+        salt: Felt,
+        constructor_calldata: Vec<Felt>,
+    ) -> Self {
+        Self {
+            provider,
+            signer,
+            chain_id,
+            class_hash,
+            salt,
+            constructor_calldata,
+            nonce: Felt::ZERO,
+            fee_token: FeeToken::default(),
+            max_fee: None,
+            resource_bounds: None,
+            fee_multipliers: FeeMultipliers::default(),
+        }
+    }
+
+    /// The address the account will be deployed at.
+    pub fn address(&self) -> Felt {
+        calculate_contract_address(
+            self.salt,
+            self.class_hash,
+            &self.constructor_calldata,
+            Felt::ZERO,
+        )
+    }
+
+    /// Sets the nonce, zero unless a previous deployment attempt was reverted.
+    pub fn nonce(mut self, nonce: Felt) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    pub fn fee_token(mut self, fee_token: FeeToken) -> Self {
+        self.fee_token = fee_token;
+        self
+    }
+
+    /// Sets the `max_fee` of an ETH (V1) deployment instead of estimating it.
+    pub fn max_fee(mut self, max_fee: Felt) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    /// Sets the resource bounds of a STRK (V3) deployment instead of estimating them.
+    pub fn resource_bounds(mut self, resource_bounds: ResourceBoundsMapping) -> Self {
+        self.resource_bounds = Some(resource_bounds);
+        self
+    }
+
+    pub fn fee_multipliers(mut self, fee_multipliers: FeeMultipliers) -> Self {
+        self.fee_multipliers = fee_multipliers;
+        self
+    }
+
+    /// Estimates over a signed query-version transaction.
+    pub async fn estimate_fee(&self) -> Result<FeeEstimate, AccountError<S::Error>> {
+        let transaction = match self.fee_token {
+            FeeToken::Eth => self.build_v1(Felt::ZERO, true),
+            FeeToken::Strk => self.build_v3(ResourceBoundsMapping::default(), true),
+        };
+        let transaction = self.sign(transaction).await?;
+        let mut estimates = self
+            .provider
+            .estimate_fee(
+                &[BroadcastedTransaction::DeployAccount(transaction)],
+                &[],
+                BlockNumber::BlockTag(BlockTag::Pending),
+            )
+            .await
+            .map_err(AccountError::Provider)?;
+        estimates.pop().ok_or_else(|| {
+            AccountError::InvalidResponse(serde::de::Error::custom("empty fee estimate list"))
+        })
+    }
+
+    /// Builds and signs the transaction, estimating the fee if it was not set explicitly.
+    pub async fn prepare(
+        &self,
+    ) -> Result<BroadcastedDeployAccountTransaction, AccountError<S::Error>> {
+        let transaction = match self.fee_token {
+            FeeToken::Eth => {
+                let max_fee = match self.max_fee {
+                    Some(max_fee) => max_fee,
+                    None => self.fee_multipliers.max_fee(&self.estimate_fee().await?),
+                };
+                self.build_v1(max_fee, false)
+            }
+            FeeToken::Strk => {
+                let resource_bounds = match self.resource_bounds {
+                    Some(resource_bounds) => resource_bounds,
+                    None => self
+                        .fee_multipliers
+                        .resource_bounds(&self.estimate_fee().await?),
+                };
+                self.build_v3(resource_bounds, false)
+            }
+        };
+        self.sign(transaction).await
+    }
+
+    /// Prepares the transaction and submits it. Await the returned [`PendingTransaction`] for
+    /// its receipt.
+    pub async fn send(&self) -> Result<PendingTransaction<'a, P>, AccountError<S::Error>> {
+        let transaction = self.prepare().await?;
+        let result = self
+            .provider
+            .add_deploy_account_transaction(transaction)
+            .await
+            .map_err(AccountError::Provider)?;
+        Ok(PendingTransaction::new(
+            self.provider,
+            result.transaction_hash,
+        ))
+    }
+
+    fn build_v1(&self, max_fee: Felt, is_query: bool) -> BroadcastedDeployAccountTransaction {
+        BroadcastedDeployAccountTransaction::V1(BroadcastedDeployAccountTransactionV1 {
+            signature: vec![],
+            nonce: self.nonce,
+            max_fee,
+            contract_address_salt: self.salt,
+            constructor_calldata: self.constructor_calldata.clone(),
+            class_hash: self.class_hash,
+            is_query,
+        })
+    }
+
+    fn build_v3(
+        &self,
+        resource_bounds: ResourceBoundsMapping,
+        is_query: bool,
+    ) -> BroadcastedDeployAccountTransaction {
+        BroadcastedDeployAccountTransaction::V3(BroadcastedDeployAccountTransactionV3 {
+            signature: vec![],
+            nonce: self.nonce,
+            contract_address_salt: self.salt,
+            constructor_calldata: self.constructor_calldata.clone(),
+            class_hash: self.class_hash,
+            resource_bounds,
+            tip: 0,
+            paymaster_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            is_query,
+        })
+    }
+
+    async fn sign(
+        &self,
+        mut transaction: BroadcastedDeployAccountTransaction,
+    ) -> Result<BroadcastedDeployAccountTransaction, AccountError<S::Error>> {
+        let hash = transaction.transaction_hash(self.chain_id);
+        let signature = self
+            .signer
+            .sign_hash(&hash)
+            .await
+            .map_err(AccountError::Signing)?;
+        match &mut transaction {
+            BroadcastedDeployAccountTransaction::V1(tx) => {
+                tx.signature = vec![signature.r, signature.s]
+            }
+            BroadcastedDeployAccountTransaction::V3(tx) => {
+                tx.signature = vec![signature.r, signature.s]
+            }
+        }
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signer::{verify_signature, LocalWallet};
+    use serde_json::json;
+    use stark_provider::rpc::JsonRpcClient;
+    use stark_provider::transport::MemoryTransport;
+
+    const OZ_ACCOUNT_CLASS: &str =
+        "0x05400e90f7e0ae78bd02c77cd75527280470e2fe19c54970dd79dc37a9d3645c";
+
+    #[tokio::test]
+    async fn send_estimates_signs_and_submits() {
+        let wallet = LocalWallet::from_private_key(Felt::from(0x1234u64));
+        let public_key = wallet.public_key();
+        let provider = JsonRpcClient::new(MemoryTransport::with_handler(move |request| {
+            let result = match request["method"].as_str().unwrap() {
+                "starknet_estimateFee" => {
+                    let tx = &request["params"][0][0];
+                    assert_eq!(tx["type"], "DEPLOY_ACCOUNT");
+                    assert_eq!(tx["version"], "0x100000000000000000000000000000001");
+                    assert_eq!(tx["signature"].as_array().unwrap().len(), 2);
+                    json!([{
+                        "l1_gas_consumed": "0x0", "l1_gas_price": "0x1",
+                        "l2_gas_consumed": "0x0", "l2_gas_price": "0x1",
+                        "l1_data_gas_consumed": "0x0", "l1_data_gas_price": "0x1",
+                        "overall_fee": "0x3e8", "unit": "WEI"
+                    }])
+                }
+                "starknet_addDeployAccountTransaction" => {
+                    let tx = &request["params"][0];
+                    assert_eq!(tx["version"], "0x1");
+                    assert_eq!(tx["max_fee"], "0x5dc");
+                    assert_eq!(tx["nonce"], "0x0");
+                    assert_eq!(tx["constructor_calldata"], json!([public_key]));
+                    json!({"transaction_hash": "0xabc", "contract_address": "0x1"})
+                }
+                method => panic!("unexpected {method}"),
+            };
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        }));
+        let chain_id = Felt::from_short_string("SN_SEPOLIA").unwrap();
+        let deployment = AccountDeployment::new(
+            &provider,
+            &wallet,
+            chain_id,
+            Felt::from_hex(OZ_ACCOUNT_CLASS).unwrap(),
+            Felt::from(0x99u64),
+            vec![public_key],
+        );
+
+        let prepared = deployment.prepare().await.unwrap();
+        assert_eq!(prepared.contract_address(), deployment.address());
+        let signature = match &prepared {
+            BroadcastedDeployAccountTransaction::V1(tx) => tx.signature.clone(),
+            BroadcastedDeployAccountTransaction::V3(_) => panic!("ETH pays through V1"),
+        };
+        assert!(verify_signature(
+            &public_key,
+            &prepared.transaction_hash(chain_id),
+            &crate::signer::Signature {
+                r: signature[0],
+                s: signature[1],
+            }
+        ));
+
+        let pending = deployment.send().await.unwrap();
+        assert_eq!(pending.transaction_hash(), Felt::from(0xabcu64));
+    }
+}
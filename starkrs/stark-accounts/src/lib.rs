@@ -0,0 +1,15 @@
+pub mod account;
+pub mod call;
+pub mod deployment;
+pub mod nonce;
+pub mod signer;
+pub mod simulation;
+pub mod udc;
+
+pub use account::*;
+pub use call::Call;
+pub use deployment::AccountDeployment;
+pub use nonce::NonceManager;
+pub use signer::{LocalWallet, Signer};
+pub use simulation::{BalanceChange, Simulation};
+pub use udc::ContractDeployment;
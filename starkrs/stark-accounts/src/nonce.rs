@@ -0,0 +1,430 @@
+use stark_core::types::request::{BlockNumber, BlockTag};
+use stark_core::Felt;
+use stark_provider::rpc::{ProviderError, SubmissionError};
+use stark_provider::Provider;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// `INVALID_TRANSACTION_NONCE`
+const INVALID_TRANSACTION_NONCE: i64 = 52;
+/// `TXN_HASH_NOT_FOUND`
+const TXN_HASH_NOT_FOUND: i64 = 29;
+
+/// Returns `true` if the node rejected a transaction because of its nonce.
+pub fn is_invalid_nonce_error(err: &ProviderError) -> bool {
+    match err {
+        ProviderError::Submission(err) => *err == SubmissionError::InvalidTransactionNonce,
+        ProviderError::Rpc(err) => err.code == INVALID_TRANSACTION_NONCE,
+        _ => false,
+    }
+}
+
+/// Hands out account nonces locally so concurrent senders don't query the node before every
+/// transaction and collide on the same value.
+///
+/// Every nonce returned by [`NonceManager::next`] must eventually be reported back with
+/// [`NonceManager::submitted`], [`NonceManager::failed`] or [`NonceManager::release`]. Released
+/// nonces are handed out again before fresh ones so that the account never leaves a gap behind.
+pub struct NonceManager<P> {
+    provider: Arc<P>,
+    address: Felt,
+    state: Mutex<NonceState>,
+}
+
+#[derive(Debug, Default)]
+struct NonceState {
+    /// The next never-used nonce, `None` until synced with the node.
+    next: Option<u64>,
+    /// Handed out and not reported back yet.
+    in_flight: BTreeSet<u64>,
+    /// Handed out but never accepted; reused before `next`.
+    released: BTreeSet<u64>,
+    /// Submitted transactions by nonce, pruned as the chain nonce advances.
+    submitted: BTreeMap<u64, Felt>,
+}
+
+impl NonceState {
+    fn take(&mut self) -> u64 {
+        let nonce = match self.released.pop_first() {
+            Some(nonce) => nonce,
+            None => {
+                let next = self.next.expect("nonce state must be synced before use");
+                self.next = Some(next + 1);
+                next
+            }
+        };
+        self.in_flight.insert(nonce);
+        nonce
+    }
+
+    fn peek(&self) -> Option<u64> {
+        self.released.first().copied().or(self.next)
+    }
+
+    fn release(&mut self, nonce: u64) {
+        if !self.in_flight.remove(&nonce) {
+            return;
+        }
+        if self.next == Some(nonce + 1) {
+            self.next = Some(nonce);
+        } else {
+            self.released.insert(nonce);
+        }
+    }
+
+    fn submitted(&mut self, nonce: u64, transaction_hash: Felt) {
+        self.in_flight.remove(&nonce);
+        self.submitted.insert(nonce, transaction_hash);
+    }
+
+    /// Realigns with the nonce the node reports and marks every unaccounted nonce in between as
+    /// a gap.
+    fn sync(&mut self, chain_nonce: u64) {
+        self.in_flight.retain(|nonce| *nonce >= chain_nonce);
+        self.released.retain(|nonce| *nonce >= chain_nonce);
+        self.submitted.retain(|nonce, _| *nonce >= chain_nonce);
+
+        let highest_pending = self
+            .in_flight
+            .iter()
+            .chain(self.submitted.keys())
+            .max()
+            .copied();
+        let next = match highest_pending {
+            Some(highest) => highest + 1,
+            None => chain_nonce,
+        };
+        self.next = Some(next);
+        // Released nonces at or above `next` would be handed out again once `next` reaches them.
+        self.released.retain(|nonce| *nonce < next);
+        for nonce in chain_nonce..next {
+            if !self.in_flight.contains(&nonce) && !self.submitted.contains_key(&nonce) {
+                self.released.insert(nonce);
+            }
+        }
+    }
+}
+
+impl<P: Provider> NonceManager<P> {
+    pub fn new(provider: Arc<P>, address: Felt) -> Self {
+        Self {
+            provider,
+            address,
+            state: Mutex::new(NonceState::default()),
+        }
+    }
+
+    /// Starts from a known nonce instead of querying the node on first use.
+    pub fn with_initial_nonce(provider: Arc<P>, address: Felt, nonce: Felt) -> Self {
+        let manager = Self::new(provider, address);
+        manager.state.try_lock().unwrap().next = nonce.to_u64();
+        manager
+    }
+
+    pub fn address(&self) -> Felt {
+        self.address
+    }
+
+    /// Reserves the next nonce.
+    pub async fn next(&self) -> Result<Felt, ProviderError> {
+        let mut state = self.state.lock().await;
+        if state.next.is_none() {
+            let chain_nonce = self.fetch_nonce().await?;
+            state.sync(chain_nonce);
+        }
+        Ok(Felt::from(state.take()))
+    }
+
+    /// Returns the nonce the next call to [`NonceManager::next`] would reserve, without
+    /// reserving it.
+    pub async fn peek(&self) -> Result<Felt, ProviderError> {
+        let mut state = self.state.lock().await;
+        if state.next.is_none() {
+            let chain_nonce = self.fetch_nonce().await?;
+            state.sync(chain_nonce);
+        }
+        Ok(Felt::from(state.peek().unwrap()))
+    }
+
+    /// Gives back a nonce whose transaction was never submitted.
+    pub async fn release(&self, nonce: Felt) {
+        if let Some(nonce) = nonce.to_u64() {
+            self.state.lock().await.release(nonce);
+        }
+    }
+
+    /// Records that the transaction using `nonce` was accepted by the node for processing.
+    pub async fn submitted(&self, nonce: Felt, transaction_hash: Felt) {
+        if let Some(nonce) = nonce.to_u64() {
+            self.state.lock().await.submitted(nonce, transaction_hash);
+        }
+    }
+
+    /// Records that submitting the transaction using `nonce` failed with `err`. A nonce the node
+    /// refused as invalid is stale, so it is dropped and the manager resyncs instead of handing
+    /// it out again; after any other error, or if the resync fails, the nonce is released.
+    pub async fn failed(&self, nonce: Felt, err: &ProviderError) {
+        let Some(nonce) = nonce.to_u64() else {
+            return;
+        };
+        let mut state = self.state.lock().await;
+        if is_invalid_nonce_error(err) {
+            if let Ok(chain_nonce) = self.fetch_nonce().await {
+                state.in_flight.remove(&nonce);
+                state.sync(chain_nonce);
+                return;
+            }
+        }
+        state.release(nonce);
+    }
+
+    /// Re-reads the account nonce from the node, returning the next nonce to be handed out.
+    pub async fn resync(&self) -> Result<Felt, ProviderError> {
+        let mut state = self.state.lock().await;
+        let chain_nonce = self.fetch_nonce().await?;
+        state.sync(chain_nonce);
+        Ok(Felt::from(state.peek().unwrap()))
+    }
+
+    /// Resyncs if `err` is an `INVALID_TRANSACTION_NONCE` error. Returns whether it did.
+    pub async fn handle_error(&self, err: &ProviderError) -> Result<bool, ProviderError> {
+        if is_invalid_nonce_error(err) {
+            self.resync().await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Finds nonces at or above the chain nonce that no pending transaction occupies, either
+    /// because they were released or because their transaction is unknown to the node. The
+    /// returned nonces are handed out again by [`NonceManager::next`].
+    pub async fn detect_gaps(&self) -> Result<Vec<Felt>, ProviderError> {
+        let chain_nonce = self.fetch_nonce().await?;
+        let submitted = {
+            let mut state = self.state.lock().await;
+            state.sync(chain_nonce);
+            state.submitted.clone()
+        };
+
+        // Query the node without holding the lock so `next` and `release` are not blocked.
+        let mut unknown = Vec::new();
+        for (nonce, transaction_hash) in submitted {
+            match self
+                .provider
+                .get_transaction_by_hash(transaction_hash)
+                .await
+            {
+                Ok(_) => {}
+                Err(ProviderError::Rpc(err)) if err.code == TXN_HASH_NOT_FOUND => {
+                    unknown.push((nonce, transaction_hash));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let mut state = self.state.lock().await;
+        for (nonce, transaction_hash) in unknown {
+            // Skip nonces that were pruned or resubmitted while the lock was released.
+            if state.submitted.get(&nonce) == Some(&transaction_hash) {
+                state.submitted.remove(&nonce);
+                state.released.insert(nonce);
+            }
+        }
+        Ok(state
+            .released
+            .iter()
+            .map(|nonce| Felt::from(*nonce))
+            .collect())
+    }
+
+    async fn fetch_nonce(&self) -> Result<u64, ProviderError> {
+        let nonce = self
+            .provider
+            .stark_getnonce(self.address, BlockNumber::BlockTag(BlockTag::Pending))
+            .await?;
+        nonce.to_u64().ok_or_else(|| {
+            ProviderError::Deserialize(serde::de::Error::custom("nonce does not fit in u64"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stark_provider::rpc::{JsonRpcClient, JsonRpcError};
+    use stark_provider::transport::{HttpTransport, MemoryTransport};
+    use std::time::Duration;
+
+    #[test]
+    fn released_nonces_are_reused_first() {
+        let mut state = NonceState {
+            next: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(state.take(), 10);
+        assert_eq!(state.take(), 11);
+        assert_eq!(state.take(), 12);
+        state.release(11);
+        state.submitted(10, Felt::ONE);
+        assert_eq!(state.take(), 11);
+        assert_eq!(state.take(), 13);
+    }
+
+    #[test]
+    fn releasing_the_last_nonce_rewinds() {
+        let mut state = NonceState {
+            next: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(state.take(), 3);
+        state.release(3);
+        assert!(state.released.is_empty());
+        assert_eq!(state.take(), 3);
+    }
+
+    #[test]
+    fn sync_marks_unaccounted_nonces_as_gaps() {
+        let mut state = NonceState {
+            next: Some(5),
+            ..Default::default()
+        };
+        state.submitted(2, Felt::ONE);
+        state.submitted(4, Felt::TWO);
+        state.sync(2);
+        assert_eq!(state.released.iter().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(state.next, Some(5));
+
+        state.sync(5);
+        assert!(state.released.is_empty());
+        assert!(state.submitted.is_empty());
+        assert_eq!(state.take(), 5);
+    }
+
+    #[test]
+    fn sync_never_hands_out_a_nonce_twice() {
+        let mut state = NonceState {
+            next: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(state.take(), 10);
+        assert_eq!(state.take(), 11);
+        assert_eq!(state.take(), 12);
+        state.release(11);
+        state.release(12);
+        state.release(10);
+        state.sync(10);
+        assert_eq!([state.take(), state.take(), state.take()], [10, 11, 12]);
+    }
+
+    #[test]
+    fn detects_invalid_nonce_errors() {
+        let err = ProviderError::Rpc(JsonRpcError {
+            code: 52,
+            message: "Invalid transaction nonce".to_string(),
+            data: None,
+        });
+        assert!(is_invalid_nonce_error(&err));
+        assert!(is_invalid_nonce_error(&ProviderError::Submission(
+            SubmissionError::InvalidTransactionNonce
+        )));
+        assert!(!is_invalid_nonce_error(&ProviderError::Submission(
+            SubmissionError::DuplicateTx
+        )));
+    }
+
+    #[tokio::test]
+    async fn concurrent_callers_get_distinct_nonces() {
+        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(
+            "http://localhost:5050".parse().unwrap(),
+        )));
+        let manager = Arc::new(NonceManager::with_initial_nonce(
+            provider,
+            Felt::ONE,
+            Felt::from(7u64),
+        ));
+
+        let handles: Vec<_> = (0..32)
+            .map(|_| {
+                let manager = manager.clone();
+                tokio::spawn(async move { manager.next().await.unwrap() })
+            })
+            .collect();
+        let mut nonces = Vec::new();
+        for handle in handles {
+            nonces.push(handle.await.unwrap().to_u64().unwrap());
+        }
+        nonces.sort();
+        assert_eq!(nonces, (7..39).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn detect_gaps_does_not_block_senders() {
+        let (transport, mut server) = MemoryTransport::channel();
+        let manager = Arc::new(NonceManager::with_initial_nonce(
+            Arc::new(JsonRpcClient::new(transport)),
+            Felt::ONE,
+            Felt::from(3u64),
+        ));
+        let nonce = manager.next().await.unwrap();
+        manager.submitted(nonce, Felt::TWO).await;
+
+        let detecting = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.detect_gaps().await.unwrap() }
+        });
+        let request = server.next_request().await.unwrap();
+        assert_eq!(request.method(), Some("starknet_getNonce"));
+        request.respond_result(serde_json::json!("0x3"));
+        let lookup = server.next_request().await.unwrap();
+        assert_eq!(lookup.method(), Some("starknet_getTransactionByHash"));
+
+        // The lookup is still unanswered.
+        let next = tokio::time::timeout(Duration::from_secs(1), manager.next()).await;
+        assert_eq!(next.unwrap().unwrap(), Felt::from(4u64));
+
+        lookup.respond_error(JsonRpcError {
+            code: 29,
+            message: "Transaction hash not found".to_string(),
+            data: None,
+        });
+        assert_eq!(detecting.await.unwrap(), vec![Felt::from(3u64)]);
+    }
+
+    #[tokio::test]
+    async fn a_refused_nonce_is_handed_out_again_after_the_resync() {
+        let manager = NonceManager::with_initial_nonce(
+            Arc::new(JsonRpcClient::new(MemoryTransport::with_responder(
+                |request| {
+                    assert_eq!(request["method"], "starknet_getNonce");
+                    Ok(serde_json::json!("0x7"))
+                },
+            ))),
+            Felt::ONE,
+            Felt::from(7u64),
+        );
+        let refused = manager.next().await.unwrap();
+        let pending = manager.next().await.unwrap();
+        manager.submitted(pending, Felt::TWO).await;
+        let err = ProviderError::Submission(SubmissionError::InvalidTransactionNonce);
+        manager.failed(refused, &err).await;
+        assert_eq!(manager.next().await.unwrap(), Felt::from(7u64));
+        assert_eq!(manager.next().await.unwrap(), Felt::from(9u64));
+    }
+
+    #[tokio::test]
+    async fn other_failures_release_the_nonce() {
+        let manager = NonceManager::with_initial_nonce(
+            Arc::new(JsonRpcClient::new(MemoryTransport::with_responder(
+                |request| panic!("unexpected {}", request["method"]),
+            ))),
+            Felt::ONE,
+            Felt::from(7u64),
+        );
+        let nonce = manager.next().await.unwrap();
+        let err = ProviderError::Submission(SubmissionError::DuplicateTx);
+        manager.failed(nonce, &err).await;
+        assert_eq!(manager.next().await.unwrap(), nonce);
+    }
+}
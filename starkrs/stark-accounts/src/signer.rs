@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use stark_core::Felt;
+use starknet_crypto::{get_public_key, rfc6979_generate_k, sign, verify, SignError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub r: Felt,
+    pub s: Felt,
+}
+
+/// Produces signatures over transaction hashes on behalf of an account.
+#[async_trait]
+pub trait Signer {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    async fn get_public_key(&self) -> Result<Felt, Self::Error>;
+
+    async fn sign_hash(&self, hash: &Felt) -> Result<Signature, Self::Error>;
+}
+
+/// A signer holding the raw private key in memory.
+#[derive(Debug, Clone)]
+pub struct LocalWallet {
+    private_key: Felt,
+}
+
+impl LocalWallet {
+    pub fn from_private_key(private_key: Felt) -> Self {
+        Self { private_key }
+    }
+
+    pub fn public_key(&self) -> Felt {
+        get_public_key(&self.private_key.inner()).into()
+    }
+
+    pub fn sign(&self, hash: &Felt) -> Result<Signature, SignError> {
+        let private_key = self.private_key.inner();
+        let message = hash.inner();
+        let mut seed = None;
+        loop {
+            let k = rfc6979_generate_k(&message, &private_key, seed.as_ref());
+            match sign(&private_key, &message, &k) {
+                Ok(signature) => {
+                    return Ok(Signature {
+                        r: signature.r.into(),
+                        s: signature.s.into(),
+                    })
+                }
+                Err(SignError::InvalidK) => {
+                    seed = Some(
+                        seed.unwrap_or(starknet_crypto::FieldElement::ZERO)
+                            + starknet_crypto::FieldElement::ONE,
+                    )
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Signer for LocalWallet {
+    type Error = SignError;
+
+    async fn get_public_key(&self) -> Result<Felt, Self::Error> {
+        Ok(self.public_key())
+    }
+
+    async fn sign_hash(&self, hash: &Felt) -> Result<Signature, Self::Error> {
+        self.sign(hash)
+    }
+}
+
+/// Checks `signature` over `hash` against `public_key`.
+pub fn verify_signature(public_key: &Felt, hash: &Felt, signature: &Signature) -> bool {
+    verify(
+        &public_key.inner(),
+        &hash.inner(),
+        &signature.r.inner(),
+        &signature.s.inner(),
+    )
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_verifies_against_public_key() {
+        let wallet = LocalWallet::from_private_key(Felt::from_hex("0x1234").unwrap());
+        let hash = Felt::from_hex("0x2ab").unwrap();
+        let signature = wallet.sign(&hash).unwrap();
+        assert!(verify_signature(&wallet.public_key(), &hash, &signature));
+        assert!(!verify_signature(
+            &wallet.public_key(),
+            &Felt::from_hex("0x2ac").unwrap(),
+            &signature
+        ));
+    }
+}
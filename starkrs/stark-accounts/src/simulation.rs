@@ -0,0 +1,151 @@
+use num_bigint::{BigInt, Sign};
+use stark_core::types::fee::FeeEstimate;
+use stark_core::types::receipt::Event;
+use stark_core::types::trace::{ExecuteInvocation, SimulatedTransaction, TransactionTrace};
+use stark_core::utils::hash::get_selector_from_name;
+use stark_core::Felt;
+use std::collections::BTreeMap;
+
+/// What a transaction would do if it were sent now, as reported by
+/// [`Execution::simulate`](crate::Execution::simulate).
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub trace: TransactionTrace,
+    pub fee_estimate: FeeEstimate,
+    /// Every event the transaction would emit, in order.
+    pub events: Vec<Event>,
+    /// Net token movements derived from the ERC-20 `Transfer` events.
+    pub balance_changes: Vec<BalanceChange>,
+}
+
+/// The net change of one account's balance of one token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub token: Felt,
+    pub address: Felt,
+    /// Negative when the account's balance decreases.
+    pub delta: BigInt,
+}
+
+impl Simulation {
+    /// Why the execution would revert, if it would.
+    pub fn revert_reason(&self) -> Option<&str> {
+        match &self.trace {
+            TransactionTrace::Invoke(trace) => match &trace.execute_invocation {
+                ExecuteInvocation::Reverted(reverted) => Some(&reverted.revert_reason),
+                ExecuteInvocation::Success(_) => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl From<SimulatedTransaction> for Simulation {
+    fn from(simulated: SimulatedTransaction) -> Self {
+        let events = simulated.transaction_trace.events();
+        let balance_changes = balance_changes(&events);
+        Self {
+            trace: simulated.transaction_trace,
+            fee_estimate: simulated.fee_estimation,
+            events,
+            balance_changes,
+        }
+    }
+}
+
+/// Sums the ERC-20 `Transfer` events per token and account. Both the Cairo 0 layout (everything
+/// in `data`) and the Cairo 1 layout (`from` and `to` as keys) are understood; mints and burns
+/// only count for the non-zero side.
+pub fn balance_changes(events: &[Event]) -> Vec<BalanceChange> {
+    let transfer = get_selector_from_name("Transfer");
+    let mut deltas: BTreeMap<(Felt, Felt), BigInt> = BTreeMap::new();
+    for event in events {
+        if event.keys.first() != Some(&transfer) {
+            continue;
+        }
+        let (from, to, low, high) = match (&event.keys[1..], &event.data[..]) {
+            ([from, to], [low, high]) => (from, to, low, high),
+            ([], [from, to, low, high]) => (from, to, low, high),
+            _ => continue,
+        };
+        let amount = (to_bigint(high) << 128) + to_bigint(low);
+        if *from != Felt::ZERO {
+            *deltas.entry((event.from_address, *from)).or_default() -= &amount;
+        }
+        if *to != Felt::ZERO {
+            *deltas.entry((event.from_address, *to)).or_default() += &amount;
+        }
+    }
+    deltas
+        .into_iter()
+        .filter(|(_, delta)| delta.sign() != Sign::NoSign)
+        .map(|((token, address), delta)| BalanceChange {
+            token,
+            address,
+            delta,
+        })
+        .collect()
+}
+
+fn to_bigint(felt: &Felt) -> BigInt {
+    BigInt::from_bytes_be(Sign::Plus, &felt.to_bytes_be())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn felt(value: u64) -> Felt {
+        Felt::from(value)
+    }
+
+    #[test]
+    fn sums_transfers_of_both_layouts() {
+        let transfer = get_selector_from_name("Transfer");
+        let events = vec![
+            // Cairo 0: from, to, amount in data.
+            Event {
+                from_address: felt(0xe7),
+                keys: vec![transfer],
+                data: vec![felt(0xa), felt(0xb), felt(100), Felt::ZERO],
+            },
+            // Cairo 1: from and to as keys.
+            Event {
+                from_address: felt(0xe7),
+                keys: vec![transfer, felt(0xb), felt(0xa)],
+                data: vec![felt(30), Felt::ZERO],
+            },
+            // A mint of 2^128 of another token.
+            Event {
+                from_address: felt(0x57),
+                keys: vec![transfer, Felt::ZERO, felt(0xa)],
+                data: vec![Felt::ZERO, Felt::ONE],
+            },
+            Event {
+                from_address: felt(0xe7),
+                keys: vec![get_selector_from_name("Approval")],
+                data: vec![felt(0xa), felt(0xb), felt(1), Felt::ZERO],
+            },
+        ];
+        assert_eq!(
+            balance_changes(&events),
+            vec![
+                BalanceChange {
+                    token: felt(0x57),
+                    address: felt(0xa),
+                    delta: BigInt::from(1u8) << 128,
+                },
+                BalanceChange {
+                    token: felt(0xe7),
+                    address: felt(0xa),
+                    delta: BigInt::from(-70),
+                },
+                BalanceChange {
+                    token: felt(0xe7),
+                    address: felt(0xb),
+                    delta: BigInt::from(70),
+                },
+            ]
+        );
+    }
+}
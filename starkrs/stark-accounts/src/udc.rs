@@ -0,0 +1,103 @@
+use crate::call::Call;
+use stark_core::utils::hash::{calculate_contract_address, get_selector_from_name, pedersen_hash};
+use stark_core::Felt;
+
+/// The Universal Deployer Contract, at the same address on mainnet and Sepolia.
+pub const UDC_ADDRESS: &str = "0x041a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf";
+
+/// A contract deployment through the Universal Deployer Contract, executed as a call from an
+/// account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractDeployment {
+    pub class_hash: Felt,
+    pub salt: Felt,
+    /// Mixes the deploying account into the address, so no one else can deploy there.
+    pub unique: bool,
+    pub constructor_calldata: Vec<Felt>,
+}
+
+impl ContractDeployment {
+    /// The `deployContract` call to execute from `deployer`'s account.
+    pub fn call(&self) -> Call {
+        let mut calldata = vec![
+            self.class_hash,
+            self.salt,
+            Felt::from(self.unique as u8),
+            Felt::from(self.constructor_calldata.len()),
+        ];
+        calldata.extend_from_slice(&self.constructor_calldata);
+        Call {
+            to: Felt::from_hex(UDC_ADDRESS).unwrap(),
+            selector: get_selector_from_name("deployContract"),
+            calldata,
+        }
+    }
+
+    /// The address the contract lands at when the call is executed by `deployer`.
+    pub fn address(&self, deployer: Felt) -> Felt {
+        let (salt, deployer_address) = if self.unique {
+            (
+                pedersen_hash(&deployer, &self.salt),
+                Felt::from_hex(UDC_ADDRESS).unwrap(),
+            )
+        } else {
+            (self.salt, Felt::ZERO)
+        };
+        calculate_contract_address(
+            salt,
+            self.class_hash,
+            &self.constructor_calldata,
+            deployer_address,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_deployments_depend_on_the_deployer() {
+        let deployment = ContractDeployment {
+            class_hash: Felt::from(0x5u64),
+            salt: Felt::from(0x7u64),
+            unique: true,
+            constructor_calldata: vec![Felt::ONE, Felt::TWO],
+        };
+        let call = deployment.call();
+        assert_eq!(call.to, Felt::from_hex(UDC_ADDRESS).unwrap());
+        assert_eq!(
+            call.calldata,
+            vec![
+                Felt::from(0x5u64),
+                Felt::from(0x7u64),
+                Felt::ONE,
+                Felt::TWO,
+                Felt::ONE,
+                Felt::TWO
+            ]
+        );
+        assert_ne!(
+            deployment.address(Felt::from(0xau64)),
+            deployment.address(Felt::from(0xbu64))
+        );
+
+        let shared = ContractDeployment {
+            unique: false,
+            ..deployment
+        };
+        assert_eq!(
+            shared.address(Felt::from(0xau64)),
+            shared.address(Felt::from(0xbu64))
+        );
+        assert_eq!(
+            shared.address(Felt::ZERO),
+            calculate_contract_address(
+                shared.salt,
+                shared.class_hash,
+                &shared.constructor_calldata,
+                Felt::ZERO
+            )
+        );
+    }
+}
@@ -1,9 +1,9 @@
 
 use primitive_types::{H256};
-use serde::{Deserialize, Serialize};
 
 
 pub mod utils;
 pub mod types;
 pub type Address = H256;
 pub type Selector = H256;
+pub use types::felt::Felt;
@@ -0,0 +1,207 @@
+use crate::types::felt::Felt;
+use crate::types::receipt::TransactionReceipt;
+use crate::types::transaction::Transaction;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The result of `starknet_blockHashAndNumber`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockHashAndNumber {
+    pub block_hash: Felt,
+    pub block_number: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum L1DataAvailabilityMode {
+    Blob,
+    Calldata,
+}
+
+/// A gas price in both fee tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourcePrice {
+    pub price_in_fri: Felt,
+    pub price_in_wei: Felt,
+}
+
+/// The header of an accepted block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub block_hash: Felt,
+    pub parent_hash: Felt,
+    pub block_number: u64,
+    pub new_root: Felt,
+    pub timestamp: u64,
+    pub sequencer_address: Felt,
+    pub l1_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    /// Absent before RPC 0.8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l2_gas_price: Option<ResourcePrice>,
+    pub l1_da_mode: L1DataAvailabilityMode,
+    pub starknet_version: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlockStatus {
+    Pending,
+    AcceptedOnL2,
+    AcceptedOnL1,
+    Rejected,
+}
+
+/// The header of the pending block, which has no hash, number or state root yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PendingBlockHeader {
+    pub parent_hash: Felt,
+    pub timestamp: u64,
+    pub sequencer_address: Felt,
+    pub l1_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l2_gas_price: Option<ResourcePrice>,
+    pub l1_da_mode: L1DataAvailabilityMode,
+    pub starknet_version: String,
+}
+
+/// An accepted block whose transactions are given as `T`: hashes, transactions, or transactions
+/// with their receipts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Block<T> {
+    pub status: BlockStatus,
+    #[serde(flatten)]
+    pub header: BlockHeader,
+    pub transactions: Vec<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PendingBlock<T> {
+    #[serde(flatten)]
+    pub header: PendingBlockHeader,
+    pub transactions: Vec<T>,
+}
+
+/// A block read by a block id that may name the pending block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum MaybePendingBlock<T> {
+    Block(Block<T>),
+    Pending(PendingBlock<T>),
+}
+
+impl<T> MaybePendingBlock<T> {
+    /// The block if it is accepted, `None` for the pending block.
+    pub fn accepted(self) -> Option<Block<T>> {
+        match self {
+            MaybePendingBlock::Block(block) => Some(block),
+            MaybePendingBlock::Pending(_) => None,
+        }
+    }
+
+    pub fn transactions(&self) -> &[T] {
+        match self {
+            MaybePendingBlock::Block(block) => &block.transactions,
+            MaybePendingBlock::Pending(block) => &block.transactions,
+        }
+    }
+
+    pub fn into_transactions(self) -> Vec<T> {
+        match self {
+            MaybePendingBlock::Block(block) => block.transactions,
+            MaybePendingBlock::Pending(block) => block.transactions,
+        }
+    }
+}
+
+/// An element of `starknet_getBlockWithReceipts`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionWithReceipt {
+    pub transaction: Transaction,
+    pub receipt: TransactionReceipt,
+}
+
+/// The result of `starknet_syncing`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncStatus {
+    NotSyncing,
+    Syncing(SyncProgress),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SyncProgress {
+    pub starting_block_hash: Felt,
+    pub starting_block_num: u64,
+    pub current_block_hash: Felt,
+    pub current_block_num: u64,
+    pub highest_block_hash: Felt,
+    pub highest_block_num: u64,
+}
+
+/// The node answers `false` when it is not syncing.
+impl Serialize for SyncStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            SyncStatus::NotSyncing => serializer.serialize_bool(false),
+            SyncStatus::Syncing(progress) => progress.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SyncStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Bool(false) => Ok(SyncStatus::NotSyncing),
+            value => serde_json::from_value(value)
+                .map(SyncStatus::Syncing)
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn header() -> serde_json::Value {
+        json!({
+            "parent_hash": "0x1",
+            "timestamp": 1700000000,
+            "sequencer_address": "0x2",
+            "l1_gas_price": {"price_in_fri": "0x3", "price_in_wei": "0x4"},
+            "l1_data_gas_price": {"price_in_fri": "0x5", "price_in_wei": "0x6"},
+            "l1_da_mode": "BLOB",
+            "starknet_version": "0.13.2",
+            "transactions": ["0x7"],
+        })
+    }
+
+    #[test]
+    fn tells_pending_blocks_apart() {
+        let pending: MaybePendingBlock<Felt> = serde_json::from_value(header()).unwrap();
+        assert!(matches!(pending, MaybePendingBlock::Pending(_)));
+
+        let mut accepted = header();
+        accepted["status"] = json!("ACCEPTED_ON_L2");
+        accepted["block_hash"] = json!("0x8");
+        accepted["block_number"] = json!(9);
+        accepted["new_root"] = json!("0xa");
+        let accepted: MaybePendingBlock<Felt> = serde_json::from_value(accepted).unwrap();
+        match accepted {
+            MaybePendingBlock::Block(block) => {
+                assert_eq!(block.header.block_number, 9);
+                assert_eq!(block.transactions, vec![Felt::from(7u64)]);
+            }
+            MaybePendingBlock::Pending(_) => panic!("parsed an accepted block as pending"),
+        }
+    }
+
+    #[test]
+    fn sync_status_is_false_when_not_syncing() {
+        let status: SyncStatus = serde_json::from_value(json!(false)).unwrap();
+        assert_eq!(status, SyncStatus::NotSyncing);
+        assert_eq!(serde_json::to_value(&status).unwrap(), json!(false));
+    }
+}
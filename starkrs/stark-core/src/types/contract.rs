@@ -0,0 +1,306 @@
+use crate::types::felt::Felt;
+use crate::utils::hash::{poseidon_hash_many, starknet_keccak};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+const PREFIX_CONTRACT_CLASS_V0_1_0: &str = "CONTRACT_CLASS_V0.1.0";
+const PREFIX_COMPILED_CLASS_V1: &str = "COMPILED_CLASS_V1";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SierraEntryPoint {
+    pub selector: Felt,
+    pub function_idx: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct SierraEntryPoints {
+    pub constructor: Vec<SierraEntryPoint>,
+    pub external: Vec<SierraEntryPoint>,
+    pub l1_handler: Vec<SierraEntryPoint>,
+}
+
+/// A Cairo 1 class as declared.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SierraClass {
+    pub sierra_program: Vec<Felt>,
+    pub contract_class_version: String,
+    pub entry_points_by_type: SierraEntryPoints,
+    /// The ABI as a JSON string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abi: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LegacyEntryPoint {
+    pub offset: Felt,
+    pub selector: Felt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct LegacyEntryPoints {
+    pub constructor: Vec<LegacyEntryPoint>,
+    pub external: Vec<LegacyEntryPoint>,
+    pub l1_handler: Vec<LegacyEntryPoint>,
+}
+
+/// A Cairo 0 class.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LegacyClass {
+    /// The gzipped, base64-encoded program.
+    pub program: String,
+    pub entry_points_by_type: LegacyEntryPoints,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abi: Option<Value>,
+}
+
+/// The result of `starknet_getClass` and `starknet_getClassAt`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ContractClass {
+    Sierra(SierraClass),
+    Legacy(LegacyClass),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CasmEntryPoint {
+    pub selector: Felt,
+    pub offset: u64,
+    pub builtins: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct CasmEntryPoints {
+    pub constructor: Vec<CasmEntryPoint>,
+    pub external: Vec<CasmEntryPoint>,
+    pub l1_handler: Vec<CasmEntryPoint>,
+}
+
+/// The result of `starknet_getCompiledCasm`: a Sierra class compiled to CASM.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CompiledClass {
+    /// The field prime, which does not fit in a [`Felt`].
+    pub prime: String,
+    pub compiler_version: String,
+    pub bytecode: Vec<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytecode_segment_lengths: Option<Value>,
+    pub hints: Vec<Value>,
+    pub entry_points_by_type: CasmEntryPoints,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassHashError {
+    /// A builtin name that does not fit in a short string.
+    InvalidBuiltinName(String),
+    /// `bytecode_segment_lengths` is not a nested list of lengths covering the bytecode.
+    InvalidSegmentLengths,
+}
+
+impl fmt::Display for ClassHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassHashError::InvalidBuiltinName(name) => write!(f, "invalid builtin name `{name}`"),
+            ClassHashError::InvalidSegmentLengths => write!(f, "invalid bytecode segment lengths"),
+        }
+    }
+}
+
+impl std::error::Error for ClassHashError {}
+
+impl SierraClass {
+    /// The hash the class is declared under. The ABI is hashed as the exact string in `abi`, so
+    /// the class must be declared with that same string.
+    pub fn class_hash(&self) -> Felt {
+        let entry_points = |entry_points: &[SierraEntryPoint]| {
+            let flattened: Vec<Felt> = entry_points
+                .iter()
+                .flat_map(|entry_point| [entry_point.selector, entry_point.function_idx.into()])
+                .collect();
+            poseidon_hash_many(&flattened)
+        };
+        poseidon_hash_many(&[
+            Felt::from_short_string(PREFIX_CONTRACT_CLASS_V0_1_0).unwrap(),
+            entry_points(&self.entry_points_by_type.external),
+            entry_points(&self.entry_points_by_type.l1_handler),
+            entry_points(&self.entry_points_by_type.constructor),
+            starknet_keccak(self.abi.as_deref().unwrap_or_default().as_bytes()),
+            poseidon_hash_many(&self.sierra_program),
+        ])
+    }
+}
+
+impl CompiledClass {
+    /// The compiled class hash a declare transaction commits to. Bytecode with segment lengths
+    /// is hashed segment by segment, as the sequencer does since Starknet 0.13.2.
+    pub fn class_hash(&self) -> Result<Felt, ClassHashError> {
+        let bytecode_hash = match &self.bytecode_segment_lengths {
+            Some(lengths) => {
+                let (hash, length) = segmented_hash(&self.bytecode, lengths)?;
+                if length != self.bytecode.len() {
+                    return Err(ClassHashError::InvalidSegmentLengths);
+                }
+                hash
+            }
+            None => poseidon_hash_many(&self.bytecode),
+        };
+        Ok(poseidon_hash_many(&[
+            Felt::from_short_string(PREFIX_COMPILED_CLASS_V1).unwrap(),
+            casm_entry_points_hash(&self.entry_points_by_type.external)?,
+            casm_entry_points_hash(&self.entry_points_by_type.l1_handler)?,
+            casm_entry_points_hash(&self.entry_points_by_type.constructor)?,
+            bytecode_hash,
+        ]))
+    }
+}
+
+fn casm_entry_points_hash(entry_points: &[CasmEntryPoint]) -> Result<Felt, ClassHashError> {
+    let mut flattened = Vec::with_capacity(entry_points.len() * 3);
+    for entry_point in entry_points {
+        let builtins = entry_point
+            .builtins
+            .iter()
+            .map(|name| {
+                Felt::from_short_string(name)
+                    .map_err(|_| ClassHashError::InvalidBuiltinName(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        flattened.push(entry_point.selector);
+        flattened.push(entry_point.offset.into());
+        flattened.push(poseidon_hash_many(&builtins));
+    }
+    Ok(poseidon_hash_many(&flattened))
+}
+
+/// Hashes the segments of `bytecode` described by the nested list `lengths`, returning the hash
+/// and the number of bytecode elements covered. A leaf segment hashes its elements; a node
+/// hashes the `(length, hash)` pairs of its children, plus one.
+fn segmented_hash(bytecode: &[Felt], lengths: &Value) -> Result<(Felt, usize), ClassHashError> {
+    let segments = lengths
+        .as_array()
+        .ok_or(ClassHashError::InvalidSegmentLengths)?;
+    let mut pairs = Vec::with_capacity(segments.len() * 2);
+    let mut offset = 0;
+    for segment in segments {
+        let rest = &bytecode[offset..];
+        let (hash, length) = match segment {
+            Value::Number(length) => {
+                let length = length
+                    .as_u64()
+                    .map(|length| length as usize)
+                    .filter(|length| *length <= rest.len())
+                    .ok_or(ClassHashError::InvalidSegmentLengths)?;
+                (poseidon_hash_many(&rest[..length]), length)
+            }
+            nested => segmented_hash(rest, nested)?,
+        };
+        pairs.push(Felt::from(length));
+        pairs.push(hash);
+        offset += length;
+    }
+    Ok((poseidon_hash_many(&pairs) + Felt::ONE, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn felt(hex: &str) -> Felt {
+        Felt::from_hex(hex).unwrap()
+    }
+
+    fn casm() -> CompiledClass {
+        serde_json::from_value(json!({
+            "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+            "compiler_version": "2.9.2",
+            "bytecode": ["0xa0680017fff8000", "0x7", "0x482680017ffa8000", "0x208b7fff7fff7ffe"],
+            "hints": [],
+            "entry_points_by_type": {
+                "CONSTRUCTOR": [],
+                "EXTERNAL": [{
+                    "selector": "0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e",
+                    "offset": 0,
+                    "builtins": ["pedersen", "range_check"]
+                }],
+                "L1_HANDLER": [{"selector": "0x1", "offset": 2, "builtins": []}]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn sierra_class_hash_matches_reference() {
+        let class: SierraClass = serde_json::from_value(json!({
+            "sierra_program": ["0x1", "0x3", "0x5", "0x7"],
+            "contract_class_version": "0.1.0",
+            "entry_points_by_type": {
+                "CONSTRUCTOR": [{
+                    "selector": "0x28ffe4ff0f226a9107253e17a904099aa4f63a02a5621de0576e5aa71bc5194",
+                    "function_idx": 1
+                }],
+                "EXTERNAL": [
+                    {
+                        "selector": "0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e",
+                        "function_idx": 0
+                    },
+                    {
+                        "selector": "0x362398bec32bc0ebb411203221a35a0301193a96f317ebe5e40be9f60d15320",
+                        "function_idx": 2
+                    }
+                ],
+                "L1_HANDLER": []
+            },
+            "abi": "[{\"type\": \"function\", \"name\": \"balanceOf\"}]"
+        }))
+        .unwrap();
+        assert_eq!(
+            class.class_hash(),
+            felt("0x78c6cc04a4a4997fdb665ff4fcd5e7e34d4b79b56bc3b1476e6a928dff2d763")
+        );
+    }
+
+    #[test]
+    fn compiled_class_hash_matches_reference() {
+        assert_eq!(
+            casm().class_hash().unwrap(),
+            felt("0x5a3bb71615af5ec39e6b839441cb741edf2c8ae4c399a7270b540771938f50b")
+        );
+    }
+
+    #[test]
+    fn segmented_bytecode_hashes_per_segment() {
+        let mut class = casm();
+        class.bytecode_segment_lengths = Some(json!([1, [2, 1]]));
+        let bytecode = &class.bytecode;
+        let inner = poseidon_hash_many(&[
+            Felt::TWO,
+            poseidon_hash_many(&bytecode[1..3]),
+            Felt::ONE,
+            poseidon_hash_many(&bytecode[3..]),
+        ]) + Felt::ONE;
+        let root = poseidon_hash_many(&[
+            Felt::ONE,
+            poseidon_hash_many(&bytecode[..1]),
+            Felt::THREE,
+            inner,
+        ]) + Felt::ONE;
+        let expected = poseidon_hash_many(&[
+            Felt::from_short_string(PREFIX_COMPILED_CLASS_V1).unwrap(),
+            casm_entry_points_hash(&class.entry_points_by_type.external).unwrap(),
+            casm_entry_points_hash(&class.entry_points_by_type.l1_handler).unwrap(),
+            casm_entry_points_hash(&class.entry_points_by_type.constructor).unwrap(),
+            root,
+        ]);
+        assert_eq!(class.class_hash().unwrap(), expected);
+
+        class.bytecode_segment_lengths = Some(json!([1, 2]));
+        assert_eq!(
+            class.class_hash(),
+            Err(ClassHashError::InvalidSegmentLengths)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::types::felt::Felt;
+use crate::types::request::BlockNumber;
+use serde::{Deserialize, Serialize};
+
+/// An event together with where it was emitted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EmittedEvent {
+    pub from_address: Felt,
+    pub keys: Vec<Felt>,
+    pub data: Vec<Felt>,
+    /// Absent for events of the pending block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    pub transaction_hash: Felt,
+}
+
+/// Selects events for `starknet_getEvents`.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct EventFilter {
+    /// The first block searched; the node defaults to the genesis block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockNumber>,
+    /// The last block searched, inclusive; the node defaults to the latest block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<Felt>,
+    /// Each inner list matches the key at its position if it contains it; an empty list matches
+    /// any key. Keys past the last list are not checked.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<Vec<Felt>>,
+}
+
+impl EventFilter {
+    /// Whether an event from `from_address` with `keys` passes the address and key filters.
+    pub fn matches(&self, from_address: Felt, keys: &[Felt]) -> bool {
+        self.address.is_none_or(|address| address == from_address)
+            && self.keys.iter().enumerate().all(|(position, allowed)| {
+                allowed.is_empty() || keys.get(position).is_some_and(|key| allowed.contains(key))
+            })
+    }
+}
+
+/// One page of `starknet_getEvents` results.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EventsPage {
+    pub events: Vec<EmittedEvent>,
+    /// Passed back to fetch the next page; absent on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_only_set_bounds() {
+        let filter = EventFilter {
+            from_block: Some(BlockNumber::Number(100)),
+            to_block: None,
+            address: Some(Felt::from(0x49u64)),
+            keys: vec![vec![Felt::ONE], vec![]],
+        };
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap(),
+            serde_json::json!({
+                "from_block": {"block_number": 100},
+                "address": "0x49",
+                "keys": [["0x1"], []],
+            })
+        );
+    }
+
+    #[test]
+    fn matches_keys_by_position() {
+        let filter = EventFilter {
+            keys: vec![vec![], vec![Felt::ONE, Felt::TWO]],
+            ..Default::default()
+        };
+        assert!(filter.matches(Felt::ZERO, &[Felt::THREE, Felt::TWO]));
+        assert!(filter.matches(Felt::ZERO, &[Felt::THREE, Felt::ONE, Felt::THREE]));
+        assert!(!filter.matches(Felt::ZERO, &[Felt::THREE, Felt::THREE]));
+        assert!(!filter.matches(Felt::ZERO, &[Felt::THREE]));
+    }
+}
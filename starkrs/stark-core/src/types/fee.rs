@@ -0,0 +1,46 @@
+use crate::types::serde_hex::{u128_hex, u64_hex};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PriceUnit {
+    Wei,
+    Fri,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeeEstimate {
+    #[serde(with = "u64_hex")]
+    pub l1_gas_consumed: u64,
+    #[serde(with = "u128_hex")]
+    pub l1_gas_price: u128,
+    #[serde(with = "u64_hex")]
+    pub l2_gas_consumed: u64,
+    #[serde(with = "u128_hex")]
+    pub l2_gas_price: u128,
+    #[serde(with = "u64_hex")]
+    pub l1_data_gas_consumed: u64,
+    #[serde(with = "u128_hex")]
+    pub l1_data_gas_price: u128,
+    #[serde(with = "u128_hex")]
+    pub overall_fee: u128,
+    pub unit: PriceUnit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SimulationFlagForEstimateFee {
+    /// Estimate without running the account's `__validate__`, so the transaction does not have to
+    /// be signed.
+    SkipValidate,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SimulationFlag {
+    /// Simulate without running the account's `__validate__`, so the transactions do not have to
+    /// be signed.
+    SkipValidate,
+    /// Simulate without charging the fee, so the fee fields may be left at zero.
+    SkipFeeCharge,
+}
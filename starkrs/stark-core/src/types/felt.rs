@@ -0,0 +1,285 @@
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+use starknet_ff::FieldElement;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+/// A Starknet field element.
+///
+/// Serialized as a `0x`-prefixed hex string as required by the JSON-RPC spec. Deserializes from
+/// either hex or decimal strings.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Felt(FieldElement);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeltError {
+    InvalidCharacter,
+    OutOfRange,
+    InvalidLength,
+}
+
+impl fmt::Display for FeltError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeltError::InvalidCharacter => write!(f, "invalid character"),
+            FeltError::OutOfRange => write!(f, "number out of range"),
+            FeltError::InvalidLength => write!(f, "invalid length"),
+        }
+    }
+}
+
+impl std::error::Error for FeltError {}
+
+impl Felt {
+    pub const ZERO: Felt = Felt(FieldElement::ZERO);
+    pub const ONE: Felt = Felt(FieldElement::ONE);
+    pub const TWO: Felt = Felt(FieldElement::TWO);
+    pub const THREE: Felt = Felt(FieldElement::THREE);
+    pub const MAX: Felt = Felt(FieldElement::MAX);
+
+    pub fn from_hex(value: &str) -> Result<Self, FeltError> {
+        FieldElement::from_hex_be(value)
+            .map(Felt)
+            .map_err(|err| match err {
+                starknet_ff::FromStrError::InvalidCharacter => FeltError::InvalidCharacter,
+                starknet_ff::FromStrError::OutOfRange => FeltError::OutOfRange,
+            })
+    }
+
+    pub fn from_dec_str(value: &str) -> Result<Self, FeltError> {
+        FieldElement::from_dec_str(value)
+            .map(Felt)
+            .map_err(|err| match err {
+                starknet_ff::FromStrError::InvalidCharacter => FeltError::InvalidCharacter,
+                starknet_ff::FromStrError::OutOfRange => FeltError::OutOfRange,
+            })
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, FeltError> {
+        FieldElement::from_byte_slice_be(bytes)
+            .map(Felt)
+            .map_err(|err| match err {
+                starknet_ff::FromByteSliceError::InvalidLength => FeltError::InvalidLength,
+                starknet_ff::FromByteSliceError::OutOfRange => FeltError::OutOfRange,
+            })
+    }
+
+    /// Encodes an ASCII string of at most 31 characters as a felt (Cairo short string).
+    pub fn from_short_string(value: &str) -> Result<Self, FeltError> {
+        if !value.is_ascii() {
+            return Err(FeltError::InvalidCharacter);
+        }
+        if value.len() > 31 {
+            return Err(FeltError::InvalidLength);
+        }
+        Self::from_bytes_be(value.as_bytes())
+    }
+
+    /// Decodes a Cairo short string, the inverse of [`Felt::from_short_string`].
+    pub fn to_short_string(&self) -> Result<String, FeltError> {
+        let bytes = self.to_bytes_be();
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        if start == 0 {
+            return Err(FeltError::InvalidLength);
+        }
+        if !bytes[start..].is_ascii() {
+            return Err(FeltError::InvalidCharacter);
+        }
+        Ok(String::from_utf8(bytes[start..].to_vec()).unwrap())
+    }
+
+    pub fn to_dec_string(&self) -> String {
+        self.0.to_string()
+    }
+
+    pub fn to_bytes_be(&self) -> [u8; 32] {
+        self.0.to_bytes_be()
+    }
+
+    pub fn inner(&self) -> FieldElement {
+        self.0
+    }
+
+    /// Returns the value as a `u64` if it fits.
+    pub fn to_u64(&self) -> Option<u64> {
+        let bytes = self.to_bytes_be();
+        if bytes[..24].iter().any(|b| *b != 0) {
+            return None;
+        }
+        Some(u64::from_be_bytes(bytes[24..].try_into().unwrap()))
+    }
+
+    /// Returns the value as a `u128` if it fits.
+    pub fn to_u128(&self) -> Option<u128> {
+        let bytes = self.to_bytes_be();
+        if bytes[..16].iter().any(|b| *b != 0) {
+            return None;
+        }
+        Some(u128::from_be_bytes(bytes[16..].try_into().unwrap()))
+    }
+}
+
+impl From<FieldElement> for Felt {
+    fn from(value: FieldElement) -> Self {
+        Felt(value)
+    }
+}
+
+impl From<Felt> for FieldElement {
+    fn from(value: Felt) -> Self {
+        value.0
+    }
+}
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Felt {
+                fn from(value: $t) -> Self {
+                    Felt(FieldElement::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_uint!(u8, u16, u32, u64, u128, usize);
+
+impl FromStr for Felt {
+    type Err = FeltError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("0x") || s.starts_with("0X") {
+            Felt::from_hex(s)
+        } else {
+            Felt::from_dec_str(s)
+        }
+    }
+}
+
+impl Add for Felt {
+    type Output = Felt;
+
+    fn add(self, rhs: Felt) -> Felt {
+        Felt(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Felt {
+    type Output = Felt;
+
+    fn sub(self, rhs: Felt) -> Felt {
+        Felt(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Felt {
+    type Output = Felt;
+
+    fn mul(self, rhs: Felt) -> Felt {
+        Felt(self.0 * rhs.0)
+    }
+}
+
+impl fmt::Display for Felt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl fmt::Debug for Felt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl fmt::LowerHex for Felt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl Serialize for Felt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("{:#x}", self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Felt {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(FeltVisitor)
+    }
+}
+
+struct FeltVisitor;
+
+impl<'de> Visitor<'de> for FeltVisitor {
+    type Value = Felt;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a hex or decimal string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Felt::from_str(v).map_err(serde::de::Error::custom)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Felt::from(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Felt;
+
+    #[test]
+    fn serde_roundtrip_uses_hex() {
+        let felt = Felt::from(255u64);
+        assert_eq!(serde_json::to_string(&felt).unwrap(), "\"0xff\"");
+        let parsed: Felt = serde_json::from_str("\"0xff\"").unwrap();
+        assert_eq!(parsed, felt);
+        let parsed: Felt = serde_json::from_str("\"255\"").unwrap();
+        assert_eq!(parsed, felt);
+    }
+
+    #[test]
+    fn short_string_encoding() {
+        let felt = Felt::from_short_string("invoke").unwrap();
+        assert_eq!(felt, Felt::from_hex("0x696e766f6b65").unwrap());
+        assert!(Felt::from_short_string(&"a".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn integer_conversions() {
+        let felt = Felt::from(u128::MAX);
+        assert_eq!(felt.to_u128(), Some(u128::MAX));
+        assert_eq!(felt.to_u64(), None);
+        assert_eq!(Felt::from(7u64).to_u64(), Some(7));
+    }
+
+    #[test]
+    fn short_strings_round_trip() {
+        let felt = Felt::from_short_string("SN_SEPOLIA").unwrap();
+        assert_eq!(felt, Felt::from_hex("0x534e5f5345504f4c4941").unwrap());
+        assert_eq!(felt.to_short_string().unwrap(), "SN_SEPOLIA");
+        assert_eq!(Felt::ZERO.to_short_string().unwrap(), "");
+        assert_eq!(
+            Felt::from(0xffu64).to_short_string(),
+            Err(super::FeltError::InvalidCharacter)
+        );
+        assert_eq!(felt.to_dec_string(), "393402133025997798000961");
+    }
+}
@@ -0,0 +1,184 @@
+use crate::types::felt::Felt;
+use crate::types::receipt::{MsgToL1, TransactionExecutionStatus, TransactionFinalityStatus};
+use crate::types::request::L1HandlerTransaction;
+use ethers::types::{Address, H256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+
+/// A message from an L1 contract to an L2 contract's `#[l1_handler]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MsgFromL1 {
+    pub from_address: Address,
+    pub to_address: Felt,
+    pub entry_point_selector: Felt,
+    pub payload: Vec<Felt>,
+}
+
+impl MsgFromL1 {
+    /// The hash StarknetCore tracks the message under in `l1ToL2Messages`, given the nonce it was
+    /// sent with.
+    pub fn hash(&self, nonce: Felt) -> H256 {
+        let mut words = vec![
+            H256::from(self.from_address).to_fixed_bytes(),
+            self.to_address.to_bytes_be(),
+            nonce.to_bytes_be(),
+            self.entry_point_selector.to_bytes_be(),
+        ];
+        push_payload(&mut words, &self.payload);
+        H256(keccak256(words.concat()))
+    }
+
+    /// The transaction that delivers the message to `to_address` on L2.
+    pub fn l1_handler_transaction(&self, nonce: Felt) -> L1HandlerTransaction {
+        let mut calldata = vec![Felt::from_bytes_be(self.from_address.as_bytes()).unwrap()];
+        calldata.extend_from_slice(&self.payload);
+        L1HandlerTransaction {
+            contract_address: self.to_address,
+            entry_point_selector: self.entry_point_selector,
+            calldata,
+            nonce,
+        }
+    }
+}
+
+impl MsgToL1 {
+    /// The hash StarknetCore tracks the message under in `l2ToL1Messages` until it is consumed.
+    pub fn hash(&self) -> H256 {
+        let mut words = vec![
+            self.from_address.to_bytes_be(),
+            self.to_address.to_bytes_be(),
+        ];
+        push_payload(&mut words, &self.payload);
+        H256(keccak256(words.concat()))
+    }
+}
+
+/// Appends the length and elements of a payload as `abi.encodePacked` lays out `uint256` values.
+fn push_payload(words: &mut Vec<[u8; 32]>, payload: &[Felt]) {
+    words.push(Felt::from(payload.len()).to_bytes_be());
+    words.extend(payload.iter().map(Felt::to_bytes_be));
+}
+
+/// The status of the L2 transaction handling a message, as returned by
+/// `starknet_getMessagesStatus`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MessageStatus {
+    pub transaction_hash: Felt,
+    pub finality_status: TransactionFinalityStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::{encode, Token};
+    use ethers::types::U256;
+
+    fn uint(value: Felt) -> Token {
+        Token::Uint(U256::from_big_endian(&value.to_bytes_be()))
+    }
+
+    fn message() -> MsgFromL1 {
+        MsgFromL1 {
+            from_address: "0xbe3c44c09bc1a3566f3e1ca12e5aba0fa4ca72be"
+                .parse()
+                .unwrap(),
+            to_address: Felt::from_hex(
+                "0x039dc79e64f4bb3289240f88e0bae7d21735bef0d1a51b2bf3c4730cb16983e1",
+            )
+            .unwrap(),
+            entry_point_selector: Felt::from_hex(
+                "0x02f15cff7b0eed8b9beb162696cf4e3e0e35fa7032af69cd1b7d2ac67a13f40f",
+            )
+            .unwrap(),
+            payload: vec![Felt::ONE, Felt::TWO],
+        }
+    }
+
+    #[test]
+    fn hashes_messages_as_solidity_packs_uint256_words() {
+        let message = message();
+        let nonce = Felt::from(775628u64);
+        let mut tokens = vec![
+            Token::Uint(U256::from(message.from_address.as_bytes())),
+            uint(message.to_address),
+            uint(nonce),
+            uint(message.entry_point_selector),
+            Token::Uint(U256::from(2)),
+        ];
+        tokens.extend(message.payload.iter().copied().map(uint));
+        assert_eq!(message.hash(nonce), H256(keccak256(encode(&tokens))));
+
+        let sent = MsgToL1 {
+            from_address: message.to_address,
+            to_address: Felt::from_bytes_be(message.from_address.as_bytes()).unwrap(),
+            payload: vec![Felt::THREE],
+        };
+        let tokens = [
+            uint(sent.from_address),
+            uint(sent.to_address),
+            Token::Uint(U256::one()),
+            uint(Felt::THREE),
+        ];
+        assert_eq!(sent.hash(), H256(keccak256(encode(&tokens))));
+    }
+
+    #[test]
+    fn hashes_a_sepolia_deposit() {
+        let felt = |value: &str| Felt::from_dec_str(value).unwrap();
+        let message = MsgFromL1 {
+            from_address: "0x8453fc6cd1bcfe8d4dfc069c400b433054d47bdc"
+                .parse()
+                .unwrap(),
+            to_address: felt(
+                "2158142789748719025684046545159279785659305214176670733242887773692203401023",
+            ),
+            entry_point_selector: felt(
+                "774397379524139446221206168840917193112228400237242521560346153613428128537",
+            ),
+            payload: vec![
+                felt("4543560"),
+                felt("829565602143178078434185452406102222830667255948"),
+                felt(
+                    "3461886633118033953192540141609307739580461579986333346825796013261542798665",
+                ),
+                felt("9000000000000000"),
+                Felt::ZERO,
+            ],
+        };
+        let nonce = Felt::from(8288u64);
+        assert_eq!(
+            message
+                .l1_handler_transaction(nonce)
+                .transaction_hash(Felt::from_short_string("SN_SEPOLIA").unwrap()),
+            Felt::from_hex("0x67d959200d65d4ad293aa4b0da21bb050a1f669bce37d215c6edbf041269c07")
+                .unwrap()
+        );
+        assert_eq!(
+            format!("{:?}", message.hash(nonce)),
+            "0x2e350fa9d830482605cb68be4fdb9f0cb3e1f95a0c51623ac1a5d1bd997c2090"
+        );
+    }
+
+    #[test]
+    fn l1_handler_calldata_starts_with_the_sender() {
+        let message = message();
+        let tx = message.l1_handler_transaction(Felt::from(775628u64));
+        assert_eq!(
+            tx.calldata,
+            vec![
+                Felt::from_hex("0xbe3c44c09bc1a3566f3e1ca12e5aba0fa4ca72be").unwrap(),
+                Felt::ONE,
+                Felt::TWO
+            ]
+        );
+        assert_eq!(tx.contract_address, message.to_address);
+        assert_ne!(
+            tx.transaction_hash(Felt::from_short_string("SN_MAIN").unwrap()),
+            tx.transaction_hash(Felt::from_short_string("SN_SEPOLIA").unwrap())
+        );
+    }
+}
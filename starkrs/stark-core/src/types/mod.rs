@@ -1 +1,14 @@
-pub mod request;
\ No newline at end of file
+pub mod block;
+pub mod contract;
+pub mod event;
+pub mod fee;
+pub mod felt;
+pub mod message;
+pub mod proof;
+pub mod receipt;
+pub mod request;
+pub mod serde_hex;
+pub mod state_update;
+pub mod subscription;
+pub mod trace;
+pub mod transaction;
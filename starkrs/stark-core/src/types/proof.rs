@@ -0,0 +1,592 @@
+use crate::types::felt::Felt;
+use crate::utils::hash::{pedersen_hash, poseidon_hash, poseidon_hash_many};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+const PREFIX_STARKNET_STATE_V0: &str = "STARKNET_STATE_V0";
+const PREFIX_CONTRACT_CLASS_LEAF_V0: &str = "CONTRACT_CLASS_LEAF_V0";
+
+/// The height of Starknet's tries: keys are 251-bit paths from the root, most significant bit
+/// first.
+const TRIE_HEIGHT: usize = 251;
+
+/// The storage keys of one contract to prove with `starknet_getStorageProof`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractStorageKeys {
+    pub contract_address: Felt,
+    pub storage_keys: Vec<Felt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BinaryNode {
+    pub left: Felt,
+    pub right: Felt,
+}
+
+/// A path of `length` bits shared by every leaf below `child`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeNode {
+    pub path: Felt,
+    pub length: u64,
+    pub child: Felt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum MerkleNode {
+    Binary(BinaryNode),
+    Edge(EdgeNode),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeWithHash {
+    pub node_hash: Felt,
+    pub node: MerkleNode,
+}
+
+/// The leaf of a contract in the contracts tree.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractLeafData {
+    pub nonce: Felt,
+    pub class_hash: Felt,
+    /// Absent before RPC 0.8.1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_root: Option<Felt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractsProof {
+    pub nodes: Vec<NodeWithHash>,
+    /// The leaves of the requested contracts, in request order.
+    pub contract_leaves_data: Vec<ContractLeafData>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlobalRoots {
+    pub contracts_tree_root: Felt,
+    pub classes_tree_root: Felt,
+    pub block_hash: Felt,
+}
+
+/// The result of `starknet_getStorageProof`: Merkle paths into the classes tree, the contracts
+/// tree and each requested contract's storage tree.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageProof {
+    pub classes_proof: Vec<NodeWithHash>,
+    pub contracts_proof: ContractsProof,
+    /// One proof per requested contract, in request order.
+    pub contracts_storage_proofs: Vec<Vec<NodeWithHash>>,
+    pub global_roots: GlobalRoots,
+}
+
+/// The hash function of a trie: Pedersen for the contracts and storage tries, Poseidon for the
+/// classes trie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrieHash {
+    Pedersen,
+    Poseidon,
+}
+
+impl TrieHash {
+    pub fn hash(self, x: Felt, y: Felt) -> Felt {
+        match self {
+            TrieHash::Pedersen => pedersen_hash(&x, &y),
+            TrieHash::Poseidon => poseidon_hash(x, y),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    /// A node on the path to the key is not part of the proof.
+    MissingNode(Felt),
+    /// An edge node longer than the rest of the path.
+    InvalidEdge(Felt),
+    /// The global roots do not commit to the expected state root.
+    StateRootMismatch { expected: Felt, actual: Felt },
+    /// A contract's nonce, class hash and storage root do not hash to its proven leaf.
+    ContractLeafMismatch(Felt),
+    /// A class's compiled class hash does not hash to its proven leaf.
+    ClassLeafMismatch(Felt),
+    /// The proof has no leaf data for the contract, or no storage proof at the given position.
+    MissingContract(Felt),
+    /// The leaf data lacks the storage root, which nodes only return from RPC 0.8.1 on.
+    MissingStorageRoot(Felt),
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::MissingNode(hash) => write!(f, "proof is missing node {hash}"),
+            ProofError::InvalidEdge(hash) => write!(f, "edge node {hash} overruns the key"),
+            ProofError::StateRootMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "proof commits to state root {actual}, expected {expected}"
+                )
+            }
+            ProofError::ContractLeafMismatch(address) => {
+                write!(
+                    f,
+                    "leaf data of contract {address} does not match its proof"
+                )
+            }
+            ProofError::ClassLeafMismatch(class_hash) => {
+                write!(
+                    f,
+                    "compiled class hash of class {class_hash} does not match its proof"
+                )
+            }
+            ProofError::MissingContract(address) => {
+                write!(f, "proof does not cover contract {address}")
+            }
+            ProofError::MissingStorageRoot(address) => {
+                write!(f, "proof lacks the storage root of contract {address}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+impl MerkleNode {
+    pub fn hash(&self, hash: TrieHash) -> Felt {
+        match self {
+            MerkleNode::Binary(node) => hash.hash(node.left, node.right),
+            MerkleNode::Edge(node) => hash.hash(node.child, node.path) + Felt::from(node.length),
+        }
+    }
+}
+
+/// Walks the path of `key` from `root` through `nodes` and returns the value of its leaf, or zero
+/// when the proof shows the key is not in the trie.
+///
+/// Nodes are identified by their recomputed hashes, so the `node_hash` fields are not trusted.
+pub fn verify_merkle_proof(
+    root: Felt,
+    key: Felt,
+    nodes: &[NodeWithHash],
+    hash: TrieHash,
+) -> Result<Felt, ProofError> {
+    if root == Felt::ZERO {
+        return Ok(Felt::ZERO);
+    }
+    let nodes: HashMap<Felt, &MerkleNode> = nodes
+        .iter()
+        .map(|node| (node.node.hash(hash), &node.node))
+        .collect();
+    let key = U256::from_big_endian(&key.to_bytes_be());
+    let mut current = root;
+    let mut height = 0;
+    while height < TRIE_HEIGHT {
+        match nodes.get(&current) {
+            None => return Err(ProofError::MissingNode(current)),
+            Some(MerkleNode::Binary(node)) => {
+                current = if key.bit(TRIE_HEIGHT - 1 - height) {
+                    node.right
+                } else {
+                    node.left
+                };
+                height += 1;
+            }
+            Some(MerkleNode::Edge(node)) => {
+                let length = node.length as usize;
+                if length == 0 || height + length > TRIE_HEIGHT {
+                    return Err(ProofError::InvalidEdge(current));
+                }
+                let mask = (U256::one() << length) - 1;
+                let path = (key >> (TRIE_HEIGHT - height - length)) & mask;
+                if path != U256::from_big_endian(&node.path.to_bytes_be()) {
+                    // The key leaves the edge, so no leaf exists below it.
+                    return Ok(Felt::ZERO);
+                }
+                current = node.child;
+                height += length;
+            }
+        }
+    }
+    Ok(current)
+}
+
+impl ContractLeafData {
+    /// The contract's leaf in the contracts trie.
+    pub fn hash(&self, storage_root: Felt) -> Felt {
+        let hash = pedersen_hash(&self.class_hash, &storage_root);
+        let hash = pedersen_hash(&hash, &self.nonce);
+        pedersen_hash(&hash, &Felt::ZERO)
+    }
+}
+
+impl GlobalRoots {
+    /// The state commitment of a block header's `new_root`.
+    pub fn state_root(&self) -> Felt {
+        if self.classes_tree_root == Felt::ZERO {
+            self.contracts_tree_root
+        } else {
+            poseidon_hash_many(&[
+                Felt::from_short_string(PREFIX_STARKNET_STATE_V0).unwrap(),
+                self.contracts_tree_root,
+                self.classes_tree_root,
+            ])
+        }
+    }
+}
+
+impl StorageProof {
+    /// Checks that the proof's global roots commit to `state_root`, the `new_root` of the block
+    /// the proof was requested for.
+    pub fn verify_state_root(&self, state_root: Felt) -> Result<(), ProofError> {
+        let actual = self.global_roots.state_root();
+        if actual == state_root {
+            Ok(())
+        } else {
+            Err(ProofError::StateRootMismatch {
+                expected: state_root,
+                actual,
+            })
+        }
+    }
+
+    /// Checks the leaf data of `contract_address`, requested at position `index` of
+    /// `contract_addresses`, against the contracts tree root and returns its storage root. An
+    /// undeployed contract has an empty storage root of zero.
+    pub fn verify_contract(
+        &self,
+        index: usize,
+        contract_address: Felt,
+    ) -> Result<Felt, ProofError> {
+        let data = self
+            .contracts_proof
+            .contract_leaves_data
+            .get(index)
+            .ok_or(ProofError::MissingContract(contract_address))?;
+        let leaf = verify_merkle_proof(
+            self.global_roots.contracts_tree_root,
+            contract_address,
+            &self.contracts_proof.nodes,
+            TrieHash::Pedersen,
+        )?;
+        let storage_root = data
+            .storage_root
+            .ok_or(ProofError::MissingStorageRoot(contract_address))?;
+        let matches = if leaf == Felt::ZERO {
+            data.nonce == Felt::ZERO && data.class_hash == Felt::ZERO && storage_root == Felt::ZERO
+        } else {
+            data.hash(storage_root) == leaf
+        };
+        if matches {
+            Ok(storage_root)
+        } else {
+            Err(ProofError::ContractLeafMismatch(contract_address))
+        }
+    }
+
+    /// Proves the value at `key` of `contract_address`, requested at position `index` of
+    /// `contracts_storage_keys`, against that contract's `storage_root`.
+    pub fn verify_storage(
+        &self,
+        index: usize,
+        contract_address: Felt,
+        storage_root: Felt,
+        key: Felt,
+    ) -> Result<Felt, ProofError> {
+        let nodes = self
+            .contracts_storage_proofs
+            .get(index)
+            .ok_or(ProofError::MissingContract(contract_address))?;
+        verify_merkle_proof(storage_root, key, nodes, TrieHash::Pedersen)
+    }
+
+    /// Checks that `class_hash` is declared with `compiled_class_hash` in the classes tree.
+    pub fn verify_class(
+        &self,
+        class_hash: Felt,
+        compiled_class_hash: Felt,
+    ) -> Result<(), ProofError> {
+        let leaf = verify_merkle_proof(
+            self.global_roots.classes_tree_root,
+            class_hash,
+            &self.classes_proof,
+            TrieHash::Poseidon,
+        )?;
+        let expected = poseidon_hash(
+            Felt::from_short_string(PREFIX_CONTRACT_CLASS_LEAF_V0).unwrap(),
+            compiled_class_hash,
+        );
+        if leaf == expected {
+            Ok(())
+        } else {
+            Err(ProofError::ClassLeafMismatch(class_hash))
+        }
+    }
+
+    /// Verifies a whole `starknet_getStorageProof` response against the `new_root` of its block
+    /// and returns the proven storage values, in the order of `contracts_storage_keys`. Each
+    /// contract with storage keys must also be among `contract_addresses`, which provides its
+    /// storage root.
+    pub fn verify(
+        &self,
+        state_root: Felt,
+        contract_addresses: &[Felt],
+        contracts_storage_keys: &[ContractStorageKeys],
+    ) -> Result<Vec<Vec<Felt>>, ProofError> {
+        self.verify_state_root(state_root)?;
+        let storage_roots = contract_addresses
+            .iter()
+            .enumerate()
+            .map(|(index, address)| Ok((*address, self.verify_contract(index, *address)?)))
+            .collect::<Result<HashMap<_, _>, ProofError>>()?;
+        contracts_storage_keys
+            .iter()
+            .enumerate()
+            .map(|(index, keys)| {
+                let storage_root = *storage_roots
+                    .get(&keys.contract_address)
+                    .ok_or(ProofError::MissingContract(keys.contract_address))?;
+                keys.storage_keys
+                    .iter()
+                    .map(|key| {
+                        self.verify_storage(index, keys.contract_address, storage_root, *key)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(node: MerkleNode, hash: TrieHash) -> NodeWithHash {
+        NodeWithHash {
+            node_hash: node.hash(hash),
+            node,
+        }
+    }
+
+    fn edge(path: Felt, length: u64, child: Felt, hash: TrieHash) -> NodeWithHash {
+        node(
+            MerkleNode::Edge(EdgeNode {
+                path,
+                length,
+                child,
+            }),
+            hash,
+        )
+    }
+
+    fn binary(left: Felt, right: Felt, hash: TrieHash) -> NodeWithHash {
+        node(MerkleNode::Binary(BinaryNode { left, right }), hash)
+    }
+
+    #[test]
+    fn walks_binary_and_edge_nodes() {
+        let hash = TrieHash::Pedersen;
+        // Keys 0x10 and 0x11 share their first 250 bits and split at the last one.
+        let leaves = binary(Felt::from(7u64), Felt::from(9u64), hash);
+        let root = edge(Felt::from(0x8u64), 250, leaves.node_hash, hash);
+        let nodes = vec![root, leaves];
+
+        let value = |key: u64| verify_merkle_proof(root.node_hash, Felt::from(key), &nodes, hash);
+        assert_eq!(value(0x10), Ok(Felt::from(7u64)));
+        assert_eq!(value(0x11), Ok(Felt::from(9u64)));
+        assert_eq!(value(0x12), Ok(Felt::ZERO));
+        assert_eq!(
+            verify_merkle_proof(root.node_hash, Felt::from(0x10u64), &nodes[..1], hash),
+            Err(ProofError::MissingNode(leaves.node_hash))
+        );
+        // The same nodes hash differently under Poseidon, so they prove nothing there.
+        assert_eq!(
+            verify_merkle_proof(
+                root.node_hash,
+                Felt::from(0x10u64),
+                &nodes,
+                TrieHash::Poseidon
+            ),
+            Err(ProofError::MissingNode(root.node_hash))
+        );
+    }
+
+    fn proof() -> StorageProof {
+        let contract = Felt::from(0xc0ffeeu64);
+        let class_hash = Felt::from(0xc1a55u64);
+        let storage = edge(
+            Felt::from(0x5u64),
+            251,
+            Felt::from(100u64),
+            TrieHash::Pedersen,
+        );
+        let leaf = ContractLeafData {
+            nonce: Felt::from(3u64),
+            class_hash,
+            storage_root: Some(storage.node_hash),
+        };
+        let contracts = edge(
+            contract,
+            251,
+            leaf.hash(storage.node_hash),
+            TrieHash::Pedersen,
+        );
+        let class_leaf = poseidon_hash(
+            Felt::from_short_string(PREFIX_CONTRACT_CLASS_LEAF_V0).unwrap(),
+            Felt::from(0xca5du64),
+        );
+        let classes = edge(class_hash, 251, class_leaf, TrieHash::Poseidon);
+        StorageProof {
+            classes_proof: vec![classes],
+            contracts_proof: ContractsProof {
+                nodes: vec![contracts],
+                contract_leaves_data: vec![leaf],
+            },
+            contracts_storage_proofs: vec![vec![storage]],
+            global_roots: GlobalRoots {
+                contracts_tree_root: contracts.node_hash,
+                classes_tree_root: classes.node_hash,
+                block_hash: Felt::ONE,
+            },
+        }
+    }
+
+    #[test]
+    fn verifies_storage_against_the_state_root() {
+        let proof = proof();
+        let contract = Felt::from(0xc0ffeeu64);
+        let state_root = proof.global_roots.state_root();
+        let keys = [ContractStorageKeys {
+            contract_address: contract,
+            storage_keys: vec![Felt::from(0x5u64), Felt::from(0x6u64)],
+        }];
+        assert_eq!(
+            proof.verify(state_root, &[contract], &keys),
+            Ok(vec![vec![Felt::from(100u64), Felt::ZERO]])
+        );
+        assert_eq!(
+            proof.verify(Felt::ONE, &[contract], &keys),
+            Err(ProofError::StateRootMismatch {
+                expected: Felt::ONE,
+                actual: state_root
+            })
+        );
+        assert_eq!(
+            proof.verify(state_root, &[], &keys),
+            Err(ProofError::MissingContract(contract))
+        );
+        let mut truncated = proof.clone();
+        truncated.contracts_storage_proofs.clear();
+        assert_eq!(
+            truncated.verify(state_root, &[contract], &keys),
+            Err(ProofError::MissingContract(contract))
+        );
+        assert_eq!(
+            proof.verify_class(Felt::from(0xc1a55u64), Felt::from(0xca5du64)),
+            Ok(())
+        );
+        assert_eq!(
+            proof.verify_class(Felt::from(0xc1a55u64), Felt::ONE),
+            Err(ProofError::ClassLeafMismatch(Felt::from(0xc1a55u64)))
+        );
+    }
+
+    /// A synthetic proof, built here rather than recorded from a node, whose contracts and
+    /// storage tries both split at the root: contract 0xa and key 0x1 go left, contract
+    /// 0x4000...000b and key 0x4000...0002 go right.
+    #[test]
+    fn verifies_binary_paths_and_non_membership() {
+        let hash = TrieHash::Pedersen;
+        let high = |low: u64| Felt::from_hex(&format!("0x4{:062x}", low)).unwrap();
+        let (contract, other, absent) = (Felt::from(0xau64), high(0xb), Felt::from(0xcu64));
+
+        let left = edge(Felt::ONE, 250, Felt::from(50u64), hash);
+        let right = edge(Felt::TWO, 250, Felt::from(60u64), hash);
+        let storage = binary(left.node_hash, right.node_hash, hash);
+        let leaf = ContractLeafData {
+            nonce: Felt::ONE,
+            class_hash: Felt::from(0xc1a55u64),
+            storage_root: Some(storage.node_hash),
+        };
+        let empty = ContractLeafData {
+            nonce: Felt::ZERO,
+            class_hash: Felt::ZERO,
+            storage_root: Some(Felt::ZERO),
+        };
+        let contract_edge = edge(contract, 250, leaf.hash(storage.node_hash), hash);
+        let other_edge = edge(Felt::from(0xbu64), 250, Felt::from(0x1eafu64), hash);
+        let contracts = binary(contract_edge.node_hash, other_edge.node_hash, hash);
+        let proof = StorageProof {
+            classes_proof: vec![],
+            contracts_proof: ContractsProof {
+                nodes: vec![contracts, contract_edge, other_edge],
+                contract_leaves_data: vec![leaf, empty],
+            },
+            contracts_storage_proofs: vec![vec![storage, left, right], vec![]],
+            global_roots: GlobalRoots {
+                contracts_tree_root: contracts.node_hash,
+                classes_tree_root: Felt::ZERO,
+                block_hash: Felt::ONE,
+            },
+        };
+        // Binary nodes must survive the wire format next to edge nodes.
+        let proof: StorageProof =
+            serde_json::from_value(serde_json::to_value(&proof).unwrap()).unwrap();
+
+        let keys = [
+            ContractStorageKeys {
+                contract_address: contract,
+                storage_keys: vec![Felt::ONE, high(0x2), Felt::from(0x3u64)],
+            },
+            ContractStorageKeys {
+                contract_address: absent,
+                storage_keys: vec![Felt::ONE],
+            },
+        ];
+        assert_eq!(
+            proof.verify(contracts.node_hash, &[contract, absent], &keys),
+            Ok(vec![
+                vec![Felt::from(50u64), Felt::from(60u64), Felt::ZERO],
+                vec![Felt::ZERO]
+            ])
+        );
+        assert_eq!(
+            verify_merkle_proof(
+                contracts.node_hash,
+                other,
+                &proof.contracts_proof.nodes,
+                hash
+            ),
+            Ok(Felt::from(0x1eafu64))
+        );
+        // Without the right edge the path to its key cannot be followed.
+        assert_eq!(
+            verify_merkle_proof(storage.node_hash, high(0x2), &[storage, left], hash),
+            Err(ProofError::MissingNode(right.node_hash))
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_leaf_data() {
+        let contract = Felt::from(0xc0ffeeu64);
+        let mut proof = proof();
+        proof.contracts_proof.contract_leaves_data[0].nonce = Felt::from(4u64);
+        assert_eq!(
+            proof.verify_contract(0, contract),
+            Err(ProofError::ContractLeafMismatch(contract))
+        );
+
+        let mut proof = self::proof();
+        proof.contracts_proof.contract_leaves_data[0].storage_root = None;
+        assert_eq!(
+            proof.verify_contract(0, contract),
+            Err(ProofError::MissingStorageRoot(contract))
+        );
+
+        // A contract outside the trie must come with empty leaf data.
+        let proof = self::proof();
+        assert_eq!(
+            proof.verify_contract(0, Felt::from(0xdeadu64)),
+            Err(ProofError::ContractLeafMismatch(Felt::from(0xdeadu64)))
+        );
+    }
+}
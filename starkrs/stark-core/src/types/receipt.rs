@@ -0,0 +1,163 @@
+use crate::types::fee::PriceUnit;
+use crate::types::felt::Felt;
+use crate::types::serde_hex::option_h256_hex;
+use ethers::types::H256;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionType {
+    Invoke,
+    Declare,
+    DeployAccount,
+    Deploy,
+    L1Handler,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionFinalityStatus {
+    Received,
+    Rejected,
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionExecutionStatus {
+    Succeeded,
+    Reverted,
+}
+
+/// The result of `starknet_getTransactionStatus`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionStatus {
+    pub finality_status: TransactionFinalityStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeePayment {
+    pub amount: Felt,
+    pub unit: PriceUnit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub from_address: Felt,
+    pub keys: Vec<Felt>,
+    pub data: Vec<Felt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MsgToL1 {
+    pub from_address: Felt,
+    pub to_address: Felt,
+    pub payload: Vec<Felt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionResources {
+    #[serde(default)]
+    pub l1_gas: u64,
+    #[serde(default)]
+    pub l1_data_gas: u64,
+    #[serde(default)]
+    pub l2_gas: u64,
+}
+
+/// A transaction receipt. Fields that only exist for some transaction types are optional.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionReceipt {
+    #[serde(rename = "type")]
+    pub type_: TransactionType,
+    pub transaction_hash: Felt,
+    pub actual_fee: FeePayment,
+    pub execution_status: TransactionExecutionStatus,
+    pub finality_status: TransactionFinalityStatus,
+    /// Absent while the transaction is in the pending block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    pub messages_sent: Vec<MsgToL1>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub execution_resources: ExecutionResources,
+    /// Set for `DEPLOY` and `DEPLOY_ACCOUNT` receipts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<Felt>,
+    /// Set for `L1_HANDLER` receipts: the hash of the consumed L1 to L2 message.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_h256_hex"
+    )]
+    pub message_hash: Option<H256>,
+}
+
+impl TransactionReceipt {
+    /// The hashes StarknetCore tracks the receipt's L2 to L1 messages under once its block is
+    /// proven, in `messages_sent` order.
+    pub fn messages_sent_hashes(&self) -> Vec<H256> {
+        self.messages_sent.iter().map(MsgToL1::hash).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_reverted_invoke_receipt() {
+        let receipt: TransactionReceipt = serde_json::from_value(serde_json::json!({
+            "type": "INVOKE",
+            "transaction_hash": "0x1",
+            "actual_fee": {"amount": "0x2386f26fc10000", "unit": "WEI"},
+            "execution_status": "REVERTED",
+            "finality_status": "ACCEPTED_ON_L2",
+            "block_hash": "0x2",
+            "block_number": 12,
+            "messages_sent": [],
+            "revert_reason": "Error in the called contract",
+            "events": [{"from_address": "0x3", "keys": ["0x4"], "data": []}],
+            "execution_resources": {"l1_gas": 0, "l1_data_gas": 128, "l2_gas": 1000}
+        }))
+        .unwrap();
+        assert_eq!(
+            receipt.execution_status,
+            TransactionExecutionStatus::Reverted
+        );
+        assert_eq!(receipt.block_number, Some(12));
+        assert_eq!(receipt.execution_resources.l1_data_gas, 128);
+        assert_eq!(receipt.events[0].keys, vec![Felt::from(4u64)]);
+    }
+
+    #[test]
+    fn deserializes_l1_handler_message_hash() {
+        let receipt: TransactionReceipt = serde_json::from_value(serde_json::json!({
+            "type": "L1_HANDLER",
+            "transaction_hash": "0x1",
+            "actual_fee": {"amount": "0x0", "unit": "WEI"},
+            "execution_status": "SUCCEEDED",
+            "finality_status": "ACCEPTED_ON_L2",
+            "messages_sent": [],
+            "events": [],
+            "message_hash": "0xc51a543ef9563ad2545342b390b67edfcddf9886aa36846cf70382362fc5fab"
+        }))
+        .unwrap();
+        let hash = receipt.message_hash.unwrap();
+        assert_eq!(hash.as_bytes()[0], 0x0c);
+        let value = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(
+            value["message_hash"],
+            "0x0c51a543ef9563ad2545342b390b67edfcddf9886aa36846cf70382362fc5fab"
+        );
+    }
+}
@@ -1,18 +1,20 @@
-use crate::{
-    Address,Selector
-};
+use crate::Felt;
+use crate::utils::hash::compute_hash_on_elements;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]  // Notice we added Serialize here
-pub struct TransactionRequest {
-    pub contract_address: Option<String>,
-    pub entry_point_selector:Option<String>,
-    pub calldata: Vec<String>
+const PREFIX_L1_HANDLER: &str = "l1_handler";
+
+/// A read-only call of a contract entry point, as sent to `starknet_call`.
+#[derive(Serialize, Deserialize,Debug,Clone,PartialEq,Eq)]
+pub struct FunctionCall {
+    pub contract_address: Felt,
+    pub entry_point_selector: Felt,
+    pub calldata: Vec<Felt>
 }
  
 #[derive(Serialize, Deserialize,Debug,Clone)] // Notice we added Serialize
 pub struct CommonProperties{
-    pub max_fee:u64,
+    pub max_fee:Felt,
     pub version:u32,
     pub signature:Vec<String>,
     pub nonce: u32
@@ -43,7 +45,7 @@ pub struct InvokeTransactionV1{
     pub version:u64,
     pub signature:Vec<String>,
     pub nonce: u32,
-    pub max_fee:u64,
+    pub max_fee:Felt,
 }
 
 #[derive(Serialize, Deserialize,Debug,Clone)]
@@ -83,60 +85,114 @@ pub struct TypeOfTx{
 }
 
 #[derive(Serialize, Deserialize,Debug,Clone)]
+#[serde(rename_all = "lowercase")]
 pub enum BlockTag{
-    pending,
-    latest
+    Pending,
+    Latest
 }
 
-#[derive(Serialize, Deserialize,Debug,Clone)]
+#[derive(Debug,Clone)]
 pub enum BlockNumber{
     BlockTag(BlockTag),
     Number(u64),
     Hash(String)
 }
 
+/// Serializes as the spec's `BLOCK_ID`: a tag, `{"block_number":..}` or `{"block_hash":..}`.
+impl Serialize for BlockNumber {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BlockNumber::BlockTag(tag) => tag.serialize(serializer),
+            BlockNumber::Number(number) => {
+                serde_json::json!({ "block_number": number }).serialize(serializer)
+            }
+            BlockNumber::Hash(hash) => {
+                serde_json::json!({ "block_hash": hash }).serialize(serializer)
+            }
+        }
+    }
+}
+
+/// The three shapes of a `BLOCK_ID`, as [`BlockNumber`] serializes them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BlockId {
+    Tag(BlockTag),
+    Number { block_number: u64 },
+    Hash { block_hash: String },
+}
+
+impl<'de> Deserialize<'de> for BlockNumber {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match BlockId::deserialize(deserializer)? {
+            BlockId::Tag(tag) => BlockNumber::BlockTag(tag),
+            BlockId::Number { block_number } => BlockNumber::Number(block_number),
+            BlockId::Hash { block_hash } => BlockNumber::Hash(block_hash),
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize,Debug,Clone)]
 pub enum TypeTx{
     INVOKE
 }
 
-#[derive(Serialize, Deserialize,Debug,Clone)]
+/// The transaction the sequencer creates to deliver a message from L1, see
+/// [`MsgFromL1::l1_handler_transaction`](crate::types::message::MsgFromL1::l1_handler_transaction).
+#[derive(Serialize, Deserialize,Debug,Clone,PartialEq,Eq)]
 pub struct L1HandlerTransaction{
-    pub transaction_hash:String,
-    pub version:String,
-    #[serde(rename = "type")]
-    type_:String,
-    nonce:String
-}
-
-#[derive(Serialize, Deserialize,Debug,Clone)]
-pub struct EventFilter{
-    pub from_block:String,
-    pub to_block:String,
-    pub address:String,
-    pub keys:Vec<String>
-}
-
-#[derive(Serialize, Deserialize,Debug,Clone)]
-pub struct ResultPageRequest{
-    pub continuation_token:String,
-    pub chunk_size:i128
-}
-
-#[derive(Serialize, Deserialize,Debug,Clone)]
-pub enum Transaction{
-    EventEmitter(EventEmitter,CommonProperties),
-    InvokeTransactionV1(InvokeTransactionV1),
-    DeclareV1(DeclareV1, CommonProperties),
-    DeployAccountTransactionProperties(DeployAccountTransactionProperties,CommonProperties),
-    TxHash(TxHash,DeployAccountTransactionProperties),
-    L1HandlerTransaction(L1HandlerTransaction),
-    TxHashClassHash(TxHashClassHash),
-    InvokeTransactionV0(TypeOfTx,CommonProperties,InvokeTransactionV0),
-    EventFilter(EventFilter,ResultPageRequest)
-}
-
-#[derive(Serialize, Deserialize,Debug,Clone)]
-pub struct BroadcastedTransaction{
-    transactions: Vec<Transaction>
+    pub contract_address:Felt,
+    pub entry_point_selector:Felt,
+    /// The L1 sender's address followed by the message payload.
+    pub calldata:Vec<Felt>,
+    /// The nonce the message was sent with on L1.
+    pub nonce:Felt
+}
+
+impl L1HandlerTransaction {
+    /// The hash of the transaction, which is always version 0 and pays no fee.
+    pub fn transaction_hash(&self,chain_id:Felt) -> Felt {
+        compute_hash_on_elements(&[
+            Felt::from_short_string(PREFIX_L1_HANDLER).unwrap(),
+            Felt::ZERO,
+            self.contract_address,
+            self.entry_point_selector,
+            compute_hash_on_elements(&self.calldata),
+            Felt::ZERO,
+            chain_id,
+            self.nonce,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn block_ids_round_trip() {
+        for value in [
+            json!("latest"),
+            json!("pending"),
+            json!({"block_number": 95812}),
+            json!({"block_hash": "0x4029c604ad1da801b55ef0ff6ac5d72153564efb415e3e45bd27bd4abdb61bd"}),
+        ] {
+            let block_id: BlockNumber = serde_json::from_value(value.clone()).unwrap();
+            assert_eq!(serde_json::to_value(block_id).unwrap(), value);
+        }
+        assert!(serde_json::from_value::<BlockNumber>(json!({"Number": 1})).is_err());
+    }
+
+    #[test]
+    fn function_calls_serialize_as_felts() {
+        let call = FunctionCall {
+            contract_address: Felt::from(0x49d3u64),
+            entry_point_selector: Felt::from(0x2au64),
+            calldata: vec![Felt::from(7u64), Felt::ZERO],
+        };
+        let value = json!({"contract_address": "0x49d3", "entry_point_selector": "0x2a", "calldata": ["0x7", "0x0"]});
+        assert_eq!(serde_json::to_value(&call).unwrap(), value);
+        assert_eq!(serde_json::from_value::<FunctionCall>(value).unwrap(), call);
+    }
 }
@@ -0,0 +1,73 @@
+//! Serde helpers for integers that the JSON-RPC spec encodes as `0x`-prefixed hex strings.
+
+use ethers::types::H256;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+fn parse_hex<E: Error>(value: &str) -> Result<u128, E> {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .ok_or_else(|| E::custom(format!("missing 0x prefix in {value}")))?;
+    u128::from_str_radix(digits, 16).map_err(E::custom)
+}
+
+pub mod u64_hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{value:#x}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        u64::try_from(parse_hex::<D::Error>(&value)?).map_err(D::Error::custom)
+    }
+}
+
+pub mod u128_hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{value:#x}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_hex(&value)
+    }
+}
+
+/// 256-bit hashes such as L1 message hashes, which nodes may send without leading zeros.
+pub mod option_h256_hex {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<H256>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(hash) => serializer.serialize_str(&format!("{hash:#x}")),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<H256>, D::Error> {
+        let Some(value) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let digits = value
+            .strip_prefix("0x")
+            .ok_or_else(|| D::Error::custom(format!("missing 0x prefix in {value}")))?;
+        if digits.len() > 64 {
+            return Err(D::Error::custom(format!(
+                "{value} does not fit in 256 bits"
+            )));
+        }
+        format!("{digits:0>64}")
+            .parse()
+            .map(Some)
+            .map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,73 @@
+use crate::types::felt::Felt;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageEntry {
+    pub key: Felt,
+    pub value: Felt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractStorageDiff {
+    pub address: Felt,
+    pub storage_entries: Vec<StorageEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeclaredClass {
+    pub class_hash: Felt,
+    pub compiled_class_hash: Felt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeployedContract {
+    pub address: Felt,
+    pub class_hash: Felt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReplacedClass {
+    pub contract_address: Felt,
+    pub class_hash: Felt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NonceUpdate {
+    pub contract_address: Felt,
+    pub nonce: Felt,
+}
+
+/// The changes a block made to the state.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    pub storage_diffs: Vec<ContractStorageDiff>,
+    /// Hashes of Cairo 0 classes declared in the block.
+    pub deprecated_declared_classes: Vec<Felt>,
+    pub declared_classes: Vec<DeclaredClass>,
+    pub deployed_contracts: Vec<DeployedContract>,
+    pub replaced_classes: Vec<ReplacedClass>,
+    pub nonces: Vec<NonceUpdate>,
+}
+
+/// The result of `starknet_getStateUpdate` for an accepted block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StateUpdate {
+    pub block_hash: Felt,
+    pub old_root: Felt,
+    pub new_root: Felt,
+    pub state_diff: StateDiff,
+}
+
+/// The state update of the pending block, which has no hash or new root yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PendingStateUpdate {
+    pub old_root: Felt,
+    pub state_diff: StateDiff,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum MaybePendingStateUpdate {
+    Update(StateUpdate),
+    Pending(PendingStateUpdate),
+}
@@ -0,0 +1,19 @@
+use crate::types::felt::Felt;
+use crate::types::receipt::TransactionStatus;
+use serde::{Deserialize, Serialize};
+
+/// A `starknet_subscriptionTransactionStatus` notification.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NewTransactionStatus {
+    pub transaction_hash: Felt,
+    pub status: TransactionStatus,
+}
+
+/// The blocks a `starknet_subscriptionReorg` notification reverts, inclusive.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorgData {
+    pub starting_block_hash: Felt,
+    pub starting_block_number: u64,
+    pub ending_block_hash: Felt,
+    pub ending_block_number: u64,
+}
@@ -0,0 +1,554 @@
+use crate::types::contract::ContractClass;
+use crate::types::fee::FeeEstimate;
+use crate::types::felt::Felt;
+use crate::types::receipt::{Event, ExecutionResources};
+use crate::types::state_update::StateDiff;
+use crate::utils::hash::get_selector_from_name;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EntryPointType {
+    External,
+    L1Handler,
+    Constructor,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CallType {
+    LibraryCall,
+    Call,
+    Delegate,
+}
+
+/// An event emitted by a call, with its position among the events of the transaction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OrderedEvent {
+    pub order: u64,
+    pub keys: Vec<Felt>,
+    pub data: Vec<Felt>,
+}
+
+/// A message to L1 sent by a call, with its position among the messages of the transaction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OrderedMessage {
+    pub order: u64,
+    pub from_address: Felt,
+    pub to_address: Felt,
+    pub payload: Vec<Felt>,
+}
+
+/// One call in a transaction's call tree.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FunctionInvocation {
+    pub contract_address: Felt,
+    pub entry_point_selector: Felt,
+    pub calldata: Vec<Felt>,
+    pub caller_address: Felt,
+    pub class_hash: Felt,
+    pub entry_point_type: EntryPointType,
+    pub call_type: CallType,
+    pub result: Vec<Felt>,
+    pub calls: Vec<FunctionInvocation>,
+    pub events: Vec<OrderedEvent>,
+    pub messages: Vec<OrderedMessage>,
+    /// The gas spent by this call alone; `l1_data_gas` is always zero.
+    #[serde(default)]
+    pub execution_resources: ExecutionResources,
+    /// Set when the call failed but the transaction went on, which Cairo 1 allows for calls
+    /// made in a library call or with a caught panic.
+    #[serde(default)]
+    pub is_reverted: bool,
+}
+
+/// The top-level invocation of a transaction: its call tree, or why it reverted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ExecuteInvocation {
+    Success(Box<FunctionInvocation>),
+    Reverted(RevertedInvocation),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RevertedInvocation {
+    pub revert_reason: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InvokeTransactionTrace {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub execute_invocation: ExecuteInvocation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiff>,
+    #[serde(default)]
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeclareTransactionTrace {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiff>,
+    #[serde(default)]
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeployAccountTransactionTrace {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub constructor_invocation: Box<FunctionInvocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiff>,
+    #[serde(default)]
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct L1HandlerTransactionTrace {
+    pub function_invocation: ExecuteInvocation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<StateDiff>,
+    #[serde(default)]
+    pub execution_resources: ExecutionResources,
+}
+
+/// The result of `starknet_traceTransaction`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransactionTrace {
+    Invoke(InvokeTransactionTrace),
+    Declare(DeclareTransactionTrace),
+    DeployAccount(DeployAccountTransactionTrace),
+    L1Handler(L1HandlerTransactionTrace),
+}
+
+/// An element of the result of `starknet_traceBlockTransactions`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionTraceWithHash {
+    pub transaction_hash: Felt,
+    pub trace_root: TransactionTrace,
+}
+
+/// An element of the result of `starknet_simulateTransactions`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedTransaction {
+    pub transaction_trace: TransactionTrace,
+    pub fee_estimation: FeeEstimate,
+}
+
+impl FunctionInvocation {
+    fn collect_events(&self, events: &mut Vec<(u64, Event)>) {
+        if self.is_reverted {
+            return;
+        }
+        events.extend(self.events.iter().map(|event| {
+            (
+                event.order,
+                Event {
+                    from_address: self.contract_address,
+                    keys: event.keys.clone(),
+                    data: event.data.clone(),
+                },
+            )
+        }));
+        for call in &self.calls {
+            call.collect_events(events);
+        }
+    }
+}
+
+impl TransactionTrace {
+    /// The top-level invocations that ran, in execution order: validation, then execution, then
+    /// the fee transfer.
+    pub fn invocations(&self) -> Vec<&FunctionInvocation> {
+        let execution = match self {
+            TransactionTrace::Invoke(trace) => match &trace.execute_invocation {
+                ExecuteInvocation::Success(invocation) => Some(invocation.as_ref()),
+                ExecuteInvocation::Reverted(_) => None,
+            },
+            TransactionTrace::Declare(_) => None,
+            TransactionTrace::DeployAccount(trace) => Some(trace.constructor_invocation.as_ref()),
+            TransactionTrace::L1Handler(trace) => match &trace.function_invocation {
+                ExecuteInvocation::Success(invocation) => Some(invocation.as_ref()),
+                ExecuteInvocation::Reverted(_) => None,
+            },
+        };
+        [
+            self.validate_invocation(),
+            execution,
+            self.fee_transfer_invocation(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Every event the transaction emitted, in emission order. Events of reverted calls are left
+    /// out, as they are from the receipt.
+    pub fn events(&self) -> Vec<Event> {
+        let mut all = Vec::new();
+        for invocation in self.invocations() {
+            let mut events = Vec::new();
+            invocation.collect_events(&mut events);
+            events.sort_by_key(|(order, _)| *order);
+            all.extend(events.into_iter().map(|(_, event)| event));
+        }
+        all
+    }
+
+    pub fn validate_invocation(&self) -> Option<&FunctionInvocation> {
+        match self {
+            TransactionTrace::Invoke(trace) => trace.validate_invocation.as_ref(),
+            TransactionTrace::Declare(trace) => trace.validate_invocation.as_ref(),
+            TransactionTrace::DeployAccount(trace) => trace.validate_invocation.as_ref(),
+            TransactionTrace::L1Handler(_) => None,
+        }
+    }
+
+    pub fn fee_transfer_invocation(&self) -> Option<&FunctionInvocation> {
+        match self {
+            TransactionTrace::Invoke(trace) => trace.fee_transfer_invocation.as_ref(),
+            TransactionTrace::Declare(trace) => trace.fee_transfer_invocation.as_ref(),
+            TransactionTrace::DeployAccount(trace) => trace.fee_transfer_invocation.as_ref(),
+            TransactionTrace::L1Handler(_) => None,
+        }
+    }
+
+    pub fn state_diff(&self) -> Option<&StateDiff> {
+        match self {
+            TransactionTrace::Invoke(trace) => trace.state_diff.as_ref(),
+            TransactionTrace::Declare(trace) => trace.state_diff.as_ref(),
+            TransactionTrace::DeployAccount(trace) => trace.state_diff.as_ref(),
+            TransactionTrace::L1Handler(trace) => trace.state_diff.as_ref(),
+        }
+    }
+
+    pub fn execution_resources(&self) -> &ExecutionResources {
+        match self {
+            TransactionTrace::Invoke(trace) => &trace.execution_resources,
+            TransactionTrace::Declare(trace) => &trace.execution_resources,
+            TransactionTrace::DeployAccount(trace) => &trace.execution_resources,
+            TransactionTrace::L1Handler(trace) => &trace.execution_resources,
+        }
+    }
+
+    /// Renders the call tree. See [`TracePrinter`] to resolve selectors to names.
+    pub fn printer(&self) -> TracePrinter<'_> {
+        TracePrinter::new(self)
+    }
+}
+
+/// Renders a [`TransactionTrace`] as an indented call tree, one call, event or message per line.
+///
+/// Entry point and event selectors are printed in hex unless an ABI declaring them was added
+/// with [`TracePrinter::with_abi`] or [`TracePrinter::with_class`].
+pub struct TracePrinter<'a> {
+    trace: &'a TransactionTrace,
+    names: HashMap<Felt, String>,
+}
+
+impl<'a> TracePrinter<'a> {
+    pub fn new(trace: &'a TransactionTrace) -> Self {
+        Self {
+            trace,
+            names: HashMap::new(),
+        }
+    }
+
+    /// Learns the names of the functions and events of a Sierra or Cairo 0 ABI.
+    pub fn with_abi(mut self, abi: &Value) -> Self {
+        for item in abi.as_array().into_iter().flatten() {
+            match item["type"].as_str() {
+                Some("function" | "l1_handler" | "constructor" | "event") => {
+                    if let Some(name) = item["name"].as_str() {
+                        // Sierra events are named by their full path; the key is the last part.
+                        let name = name.rsplit("::").next().unwrap_or(name);
+                        self.names
+                            .insert(get_selector_from_name(name), name.to_string());
+                    }
+                }
+                Some("interface") => self = self.with_abi(&item["items"]),
+                _ => {}
+            }
+        }
+        self
+    }
+
+    /// Learns the names declared in the ABI of `class`.
+    pub fn with_class(self, class: &ContractClass) -> Self {
+        let abi = match class {
+            ContractClass::Sierra(class) => class
+                .abi
+                .as_deref()
+                .and_then(|abi| serde_json::from_str(abi).ok()),
+            ContractClass::Legacy(class) => class.abi.clone(),
+        };
+        match abi {
+            Some(abi) => self.with_abi(&abi),
+            None => self,
+        }
+    }
+
+    fn name(&self, selector: &Felt) -> String {
+        match self.names.get(selector) {
+            Some(name) => name.clone(),
+            None => selector.to_string(),
+        }
+    }
+
+    fn section(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        title: &str,
+        invocation: Option<&FunctionInvocation>,
+    ) -> fmt::Result {
+        if let Some(invocation) = invocation {
+            writeln!(f, "{title}:")?;
+            self.invocation(f, invocation, 1)?;
+        }
+        Ok(())
+    }
+
+    fn execute_section(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        title: &str,
+        invocation: &ExecuteInvocation,
+    ) -> fmt::Result {
+        match invocation {
+            ExecuteInvocation::Success(invocation) => self.section(f, title, Some(invocation)),
+            ExecuteInvocation::Reverted(reverted) => {
+                writeln!(f, "{title}: reverted: {}", reverted.revert_reason)
+            }
+        }
+    }
+
+    fn invocation(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        invocation: &FunctionInvocation,
+        depth: usize,
+    ) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        let call_type = match invocation.call_type {
+            CallType::Call => "call",
+            CallType::LibraryCall => "library_call",
+            CallType::Delegate => "delegate",
+        };
+        write!(
+            f,
+            "{indent}{call_type} {}::{}({}) -> ({})",
+            invocation.contract_address,
+            self.name(&invocation.entry_point_selector),
+            join(&invocation.calldata),
+            join(&invocation.result),
+        )?;
+        if invocation.is_reverted {
+            write!(f, " [reverted]")?;
+        }
+        writeln!(f)?;
+
+        // Events and messages in the order they were emitted, then the inner calls.
+        let mut lines: Vec<(u64, String)> = Vec::new();
+        for event in &invocation.events {
+            let (name, keys) = match event.keys.split_first() {
+                Some((selector, keys)) => (self.name(selector), keys),
+                None => (String::from("<anonymous>"), &event.keys[..]),
+            };
+            let keys = if keys.is_empty() {
+                String::new()
+            } else {
+                format!("[{}] ", join(keys))
+            };
+            lines.push((
+                event.order,
+                format!("{indent}  event {name} {keys}({})", join(&event.data)),
+            ));
+        }
+        for message in &invocation.messages {
+            lines.push((
+                message.order,
+                format!(
+                    "{indent}  message to {} ({})",
+                    message.to_address,
+                    join(&message.payload)
+                ),
+            ));
+        }
+        lines.sort_by_key(|(order, _)| *order);
+        for (_, line) in lines {
+            writeln!(f, "{line}")?;
+        }
+        for call in &invocation.calls {
+            self.invocation(f, call, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn join(felts: &[Felt]) -> String {
+    felts
+        .iter()
+        .map(Felt::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for TracePrinter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.trace {
+            TransactionTrace::Invoke(trace) => {
+                self.section(f, "validate", trace.validate_invocation.as_ref())?;
+                self.execute_section(f, "execute", &trace.execute_invocation)?;
+                self.section(f, "fee transfer", trace.fee_transfer_invocation.as_ref())
+            }
+            TransactionTrace::Declare(trace) => {
+                self.section(f, "validate", trace.validate_invocation.as_ref())?;
+                self.section(f, "fee transfer", trace.fee_transfer_invocation.as_ref())
+            }
+            TransactionTrace::DeployAccount(trace) => {
+                self.section(f, "validate", trace.validate_invocation.as_ref())?;
+                self.section(f, "constructor", Some(&trace.constructor_invocation))?;
+                self.section(f, "fee transfer", trace.fee_transfer_invocation.as_ref())
+            }
+            TransactionTrace::L1Handler(trace) => {
+                self.execute_section(f, "l1 handler", &trace.function_invocation)
+            }
+        }
+    }
+}
+
+impl fmt::Display for TransactionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        TracePrinter::new(self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn invocation(address: u64, selector: &str, calls: Value, events: Value) -> Value {
+        json!({
+            "contract_address": format!("{address:#x}"),
+            "entry_point_selector": get_selector_from_name(selector),
+            "calldata": ["0x1"],
+            "caller_address": "0x0",
+            "class_hash": "0xc1a55",
+            "entry_point_type": "EXTERNAL",
+            "call_type": "CALL",
+            "result": [],
+            "calls": calls,
+            "events": events,
+            "messages": [],
+            "execution_resources": {"l1_gas": 0, "l2_gas": 4000}
+        })
+    }
+
+    fn invoke_trace() -> TransactionTrace {
+        let transfer = json!([{
+            "order": 0,
+            "keys": [get_selector_from_name("Transfer"), "0xa"],
+            "data": ["0x64", "0x0"]
+        }]);
+        serde_json::from_value(json!({
+            "type": "INVOKE",
+            "validate_invocation": invocation(0xa, "__validate__", json!([]), json!([])),
+            "execute_invocation": invocation(
+                0xa,
+                "__execute__",
+                json!([invocation(0xe7, "transfer", json!([]), transfer)]),
+                json!([])
+            ),
+            "fee_transfer_invocation": invocation(0xe7, "transfer", json!([]), json!([])),
+            "state_diff": {
+                "storage_diffs": [],
+                "deprecated_declared_classes": [],
+                "declared_classes": [],
+                "deployed_contracts": [],
+                "replaced_classes": [],
+                "nonces": [{"contract_address": "0xa", "nonce": "0x2"}]
+            },
+            "execution_resources": {"l1_gas": 0, "l1_data_gas": 192, "l2_gas": 12000}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn deserializes_invoke_trace() {
+        let trace = invoke_trace();
+        let TransactionTrace::Invoke(invoke) = &trace else {
+            panic!("expected an invoke trace");
+        };
+        let ExecuteInvocation::Success(execute) = &invoke.execute_invocation else {
+            panic!("expected a successful execution");
+        };
+        assert_eq!(execute.calls[0].contract_address, Felt::from(0xe7u64));
+        assert_eq!(execute.calls[0].events[0].data[0], Felt::from(100u64));
+        assert_eq!(execute.execution_resources.l2_gas, 4000);
+        assert_eq!(trace.state_diff().unwrap().nonces.len(), 1);
+        assert_eq!(trace.execution_resources().l1_data_gas, 192);
+        assert!(trace.validate_invocation().is_some());
+    }
+
+    #[test]
+    fn events_follow_execution_order() {
+        let trace = invoke_trace();
+        let events = trace.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].from_address, Felt::from(0xe7u64));
+        assert_eq!(events[0].keys[1], Felt::from(0xau64));
+        assert_eq!(trace.invocations().len(), 3);
+    }
+
+    #[test]
+    fn deserializes_reverted_execution() {
+        let trace: TransactionTrace = serde_json::from_value(json!({
+            "type": "L1_HANDLER",
+            "function_invocation": {"revert_reason": "Insufficient balance"},
+            "execution_resources": {"l1_gas": 0, "l1_data_gas": 0, "l2_gas": 500}
+        }))
+        .unwrap();
+        assert_eq!(
+            trace.to_string(),
+            "l1 handler: reverted: Insufficient balance\n"
+        );
+    }
+
+    #[test]
+    fn printer_resolves_names_from_abi() {
+        let trace = invoke_trace();
+        let abi = json!([
+            {"type": "function", "name": "__execute__"},
+            {"type": "interface", "name": "IERC20", "items": [{"type": "function", "name": "transfer"}]},
+            {"type": "event", "name": "openzeppelin::token::erc20::ERC20Component::Transfer", "kind": "struct"}
+        ]);
+        let validate = get_selector_from_name("__validate__");
+        let expected = format!(
+            "validate:
+  call 0xa::{validate}(0x1) -> ()
+execute:
+  call 0xa::__execute__(0x1) -> ()
+    call 0xe7::transfer(0x1) -> ()
+      event Transfer [0xa] (0x64, 0x0)
+fee transfer:
+  call 0xe7::transfer(0x1) -> ()
+"
+        );
+        assert_eq!(trace.printer().with_abi(&abi).to_string(), expected);
+    }
+}
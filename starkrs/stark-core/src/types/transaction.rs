@@ -0,0 +1,884 @@
+use crate::types::contract::{LegacyClass, SierraClass};
+use crate::types::felt::Felt;
+use crate::types::receipt::TransactionType;
+use crate::types::serde_hex::{u128_hex, u64_hex};
+use crate::utils::hash::{
+    calculate_contract_address, compute_hash_on_elements, poseidon_hash_many,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const PREFIX_INVOKE: &str = "invoke";
+const PREFIX_DEPLOY_ACCOUNT: &str = "deploy_account";
+const PREFIX_DECLARE: &str = "declare";
+
+/// The version a transaction of `version` carries when sent for estimation or simulation only:
+/// `2^128 + version`. A signature over the query version can never be replayed as a real
+/// transaction.
+///
+/// Broadcasted transactions mark this with their `is_query` field, which serializes as the
+/// query version and is set when a query version is deserialized. The node rejects such
+/// transactions on `starknet_add*Transaction`, so they must only be passed to
+/// `starknet_estimateFee` and `starknet_simulateTransactions`.
+pub fn query_version(version: Felt) -> Felt {
+    Felt::from(u128::MAX) + Felt::ONE + version
+}
+
+fn transaction_version(version: Felt, is_query: bool) -> Felt {
+    if is_query {
+        query_version(version)
+    } else {
+        version
+    }
+}
+
+/// Splits a serialized version into the base version and the query flag.
+fn split_version(version: Felt) -> (Felt, bool) {
+    let offset = query_version(Felt::ZERO);
+    if version >= offset {
+        (version - offset, true)
+    } else {
+        (version, false)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataAvailabilityMode {
+    #[default]
+    L1,
+    L2,
+}
+
+impl DataAvailabilityMode {
+    fn as_u64(&self) -> u64 {
+        match self {
+            DataAvailabilityMode::L1 => 0,
+            DataAvailabilityMode::L2 => 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResourceBounds {
+    #[serde(with = "u64_hex")]
+    pub max_amount: u64,
+    #[serde(with = "u128_hex")]
+    pub max_price_per_unit: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResourceBoundsMapping {
+    pub l1_gas: ResourceBounds,
+    pub l2_gas: ResourceBounds,
+    /// Absent before RPC 0.8 and Starknet 0.13.4, when transactions only bound L1 and L2 gas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_data_gas: Option<ResourceBounds>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BroadcastedInvokeTransactionV1 {
+    pub sender_address: Felt,
+    pub calldata: Vec<Felt>,
+    pub max_fee: Felt,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    /// See [`query_version`].
+    #[serde(skip)]
+    pub is_query: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BroadcastedInvokeTransactionV3 {
+    pub sender_address: Felt,
+    pub calldata: Vec<Felt>,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    pub resource_bounds: ResourceBoundsMapping,
+    #[serde(with = "u64_hex")]
+    pub tip: u64,
+    pub paymaster_data: Vec<Felt>,
+    pub account_deployment_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+    /// See [`query_version`].
+    #[serde(skip)]
+    pub is_query: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BroadcastedDeployAccountTransactionV1 {
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    pub max_fee: Felt,
+    pub contract_address_salt: Felt,
+    pub constructor_calldata: Vec<Felt>,
+    pub class_hash: Felt,
+    /// See [`query_version`].
+    #[serde(skip)]
+    pub is_query: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BroadcastedDeployAccountTransactionV3 {
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    pub contract_address_salt: Felt,
+    pub constructor_calldata: Vec<Felt>,
+    pub class_hash: Felt,
+    pub resource_bounds: ResourceBoundsMapping,
+    #[serde(with = "u64_hex")]
+    pub tip: u64,
+    pub paymaster_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+    /// See [`query_version`].
+    #[serde(skip)]
+    pub is_query: bool,
+}
+
+/// Declares a Cairo 0 class.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BroadcastedDeclareTransactionV1 {
+    pub sender_address: Felt,
+    pub max_fee: Felt,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    pub contract_class: LegacyClass,
+    /// See [`query_version`].
+    #[serde(skip)]
+    pub is_query: bool,
+}
+
+/// Declares a Sierra class together with the hash of the CASM it must compile to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BroadcastedDeclareTransactionV3 {
+    pub sender_address: Felt,
+    pub compiled_class_hash: Felt,
+    pub signature: Vec<Felt>,
+    pub nonce: Felt,
+    pub contract_class: SierraClass,
+    pub resource_bounds: ResourceBoundsMapping,
+    #[serde(with = "u64_hex")]
+    pub tip: u64,
+    pub paymaster_data: Vec<Felt>,
+    pub account_deployment_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+    /// See [`query_version`].
+    #[serde(skip)]
+    pub is_query: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum BroadcastedInvokeTransaction {
+    V1(BroadcastedInvokeTransactionV1),
+    V3(BroadcastedInvokeTransactionV3),
+}
+
+#[derive(Debug, Clone)]
+pub enum BroadcastedDeclareTransaction {
+    V1(BroadcastedDeclareTransactionV1),
+    V3(BroadcastedDeclareTransactionV3),
+}
+
+#[derive(Debug, Clone)]
+pub enum BroadcastedDeployAccountTransaction {
+    V1(BroadcastedDeployAccountTransactionV1),
+    V3(BroadcastedDeployAccountTransactionV3),
+}
+
+/// A transaction as submitted to the node for estimation or inclusion.
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum BroadcastedTransaction {
+    Invoke(BroadcastedInvokeTransaction),
+    Declare(BroadcastedDeclareTransaction),
+    DeployAccount(BroadcastedDeployAccountTransaction),
+}
+
+/// The result of `starknet_addInvokeTransaction`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InvokeTransactionResult {
+    pub transaction_hash: Felt,
+}
+
+/// The result of `starknet_addDeclareTransaction`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeclareTransactionResult {
+    pub transaction_hash: Felt,
+    pub class_hash: Felt,
+}
+
+/// The result of `starknet_addDeployAccountTransaction`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeployAccountTransactionResult {
+    pub transaction_hash: Felt,
+    pub contract_address: Felt,
+}
+
+/// A transaction as returned by the node. Fields that only exist for some transaction types or
+/// versions are optional.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub transaction_hash: Felt,
+    #[serde(rename = "type")]
+    pub type_: TransactionType,
+    pub version: Felt,
+    /// The sending account of invoke (v1 and later) and declare transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender_address: Option<Felt>,
+    /// The called contract of `INVOKE` v0 and `L1_HANDLER` transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_point_selector: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calldata: Vec<Felt>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signature: Vec<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Felt>,
+    /// The fee limit of transactions before v3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_bounds: Option<ResourceBoundsMapping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tip: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paymaster_data: Vec<Felt>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub account_deployment_data: Vec<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce_data_availability_mode: Option<DataAvailabilityMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_data_availability_mode: Option<DataAvailabilityMode>,
+    /// Set for declare transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_hash: Option<Felt>,
+    /// Set for declare transactions from v2 on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<Felt>,
+    /// Set for deploy and deploy account transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_address_salt: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constructor_calldata: Vec<Felt>,
+}
+
+/// Adds the `type` and `version` fields the spec expects next to the transaction body.
+#[derive(Serialize)]
+struct Tagged<'a, T> {
+    #[serde(rename = "type")]
+    type_: &'static str,
+    version: Felt,
+    #[serde(flatten)]
+    transaction: &'a T,
+}
+
+#[derive(Deserialize)]
+struct VersionOnly {
+    version: Felt,
+}
+
+fn version_of<E: serde::de::Error>(value: &serde_json::Value) -> Result<Felt, E> {
+    VersionOnly::deserialize(value)
+        .map(|v| v.version)
+        .map_err(E::custom)
+}
+
+impl Serialize for BroadcastedInvokeTransaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BroadcastedInvokeTransaction::V1(tx) => Tagged {
+                type_: "INVOKE",
+                version: transaction_version(Felt::ONE, tx.is_query),
+                transaction: tx,
+            }
+            .serialize(serializer),
+            BroadcastedInvokeTransaction::V3(tx) => Tagged {
+                type_: "INVOKE",
+                version: transaction_version(Felt::THREE, tx.is_query),
+                transaction: tx,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BroadcastedInvokeTransaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let (version, is_query) = split_version(version_of::<D::Error>(&value)?);
+        if version == Felt::ONE {
+            serde_json::from_value(value)
+                .map(|tx| {
+                    BroadcastedInvokeTransaction::V1(BroadcastedInvokeTransactionV1 {
+                        is_query,
+                        ..tx
+                    })
+                })
+                .map_err(D::Error::custom)
+        } else if version == Felt::THREE {
+            serde_json::from_value(value)
+                .map(|tx| {
+                    BroadcastedInvokeTransaction::V3(BroadcastedInvokeTransactionV3 {
+                        is_query,
+                        ..tx
+                    })
+                })
+                .map_err(D::Error::custom)
+        } else {
+            Err(D::Error::custom(format!(
+                "unsupported invoke transaction version {version}"
+            )))
+        }
+    }
+}
+
+impl Serialize for BroadcastedDeclareTransaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BroadcastedDeclareTransaction::V1(tx) => Tagged {
+                type_: "DECLARE",
+                version: transaction_version(Felt::ONE, tx.is_query),
+                transaction: tx,
+            }
+            .serialize(serializer),
+            BroadcastedDeclareTransaction::V3(tx) => Tagged {
+                type_: "DECLARE",
+                version: transaction_version(Felt::THREE, tx.is_query),
+                transaction: tx,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BroadcastedDeclareTransaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let (version, is_query) = split_version(version_of::<D::Error>(&value)?);
+        if version == Felt::ONE {
+            serde_json::from_value(value)
+                .map(|tx| {
+                    BroadcastedDeclareTransaction::V1(BroadcastedDeclareTransactionV1 {
+                        is_query,
+                        ..tx
+                    })
+                })
+                .map_err(D::Error::custom)
+        } else if version == Felt::THREE {
+            serde_json::from_value(value)
+                .map(|tx| {
+                    BroadcastedDeclareTransaction::V3(BroadcastedDeclareTransactionV3 {
+                        is_query,
+                        ..tx
+                    })
+                })
+                .map_err(D::Error::custom)
+        } else {
+            Err(D::Error::custom(format!(
+                "unsupported declare transaction version {version}"
+            )))
+        }
+    }
+}
+
+impl Serialize for BroadcastedDeployAccountTransaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BroadcastedDeployAccountTransaction::V1(tx) => Tagged {
+                type_: "DEPLOY_ACCOUNT",
+                version: transaction_version(Felt::ONE, tx.is_query),
+                transaction: tx,
+            }
+            .serialize(serializer),
+            BroadcastedDeployAccountTransaction::V3(tx) => Tagged {
+                type_: "DEPLOY_ACCOUNT",
+                version: transaction_version(Felt::THREE, tx.is_query),
+                transaction: tx,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BroadcastedDeployAccountTransaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let (version, is_query) = split_version(version_of::<D::Error>(&value)?);
+        if version == Felt::ONE {
+            serde_json::from_value(value)
+                .map(|tx| {
+                    BroadcastedDeployAccountTransaction::V1(BroadcastedDeployAccountTransactionV1 {
+                        is_query,
+                        ..tx
+                    })
+                })
+                .map_err(D::Error::custom)
+        } else if version == Felt::THREE {
+            serde_json::from_value(value)
+                .map(|tx| {
+                    BroadcastedDeployAccountTransaction::V3(BroadcastedDeployAccountTransactionV3 {
+                        is_query,
+                        ..tx
+                    })
+                })
+                .map_err(D::Error::custom)
+        } else {
+            Err(D::Error::custom(format!(
+                "unsupported deploy account transaction version {version}"
+            )))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BroadcastedTransaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let value = serde_json::Value::deserialize(deserializer)?;
+        match value.get("type").and_then(|t| t.as_str()) {
+            Some("INVOKE") => serde_json::from_value(value)
+                .map(BroadcastedTransaction::Invoke)
+                .map_err(D::Error::custom),
+            Some("DECLARE") => serde_json::from_value(value)
+                .map(BroadcastedTransaction::Declare)
+                .map_err(D::Error::custom),
+            Some("DEPLOY_ACCOUNT") => serde_json::from_value(value)
+                .map(BroadcastedTransaction::DeployAccount)
+                .map_err(D::Error::custom),
+            other => Err(D::Error::custom(format!(
+                "unsupported transaction type {other:?}"
+            ))),
+        }
+    }
+}
+
+impl From<BroadcastedInvokeTransaction> for BroadcastedTransaction {
+    fn from(tx: BroadcastedInvokeTransaction) -> Self {
+        BroadcastedTransaction::Invoke(tx)
+    }
+}
+
+impl From<BroadcastedDeclareTransaction> for BroadcastedTransaction {
+    fn from(tx: BroadcastedDeclareTransaction) -> Self {
+        BroadcastedTransaction::Declare(tx)
+    }
+}
+
+impl From<BroadcastedDeployAccountTransaction> for BroadcastedTransaction {
+    fn from(tx: BroadcastedDeployAccountTransaction) -> Self {
+        BroadcastedTransaction::DeployAccount(tx)
+    }
+}
+
+/// Hashes the tip and resource bounds as defined by SNIP-8.
+///
+/// Each bound is packed as `resource_name (60 bits) | max_amount (64 bits) | max_price (128 bits)`.
+/// Transactions without an L1 data gas bound predate it and hash only the other two.
+fn fee_fields_hash(tip: u64, bounds: &ResourceBoundsMapping) -> Felt {
+    let encode = |name: &[u8], bound: &ResourceBounds| {
+        let mut buffer = [0u8; 32];
+        buffer[8 - name.len()..8].copy_from_slice(name);
+        buffer[8..16].copy_from_slice(&bound.max_amount.to_be_bytes());
+        buffer[16..].copy_from_slice(&bound.max_price_per_unit.to_be_bytes());
+        Felt::from_bytes_be(&buffer).unwrap()
+    };
+
+    let mut elements = vec![
+        Felt::from(tip),
+        encode(b"L1_GAS", &bounds.l1_gas),
+        encode(b"L2_GAS", &bounds.l2_gas),
+    ];
+    if let Some(l1_data_gas) = &bounds.l1_data_gas {
+        elements.push(encode(b"L1_DATA", l1_data_gas));
+    }
+    poseidon_hash_many(&elements)
+}
+
+fn data_availability_modes(nonce: DataAvailabilityMode, fee: DataAvailabilityMode) -> Felt {
+    Felt::from((nonce.as_u64() << 32) + fee.as_u64())
+}
+
+impl BroadcastedInvokeTransactionV1 {
+    pub fn transaction_hash(&self, chain_id: Felt) -> Felt {
+        compute_hash_on_elements(&[
+            Felt::from_short_string(PREFIX_INVOKE).unwrap(),
+            transaction_version(Felt::ONE, self.is_query),
+            self.sender_address,
+            Felt::ZERO,
+            compute_hash_on_elements(&self.calldata),
+            self.max_fee,
+            chain_id,
+            self.nonce,
+        ])
+    }
+}
+
+impl BroadcastedInvokeTransactionV3 {
+    pub fn transaction_hash(&self, chain_id: Felt) -> Felt {
+        poseidon_hash_many(&[
+            Felt::from_short_string(PREFIX_INVOKE).unwrap(),
+            transaction_version(Felt::THREE, self.is_query),
+            self.sender_address,
+            fee_fields_hash(self.tip, &self.resource_bounds),
+            poseidon_hash_many(&self.paymaster_data),
+            chain_id,
+            self.nonce,
+            data_availability_modes(
+                self.nonce_data_availability_mode,
+                self.fee_data_availability_mode,
+            ),
+            poseidon_hash_many(&self.account_deployment_data),
+            poseidon_hash_many(&self.calldata),
+        ])
+    }
+}
+
+impl BroadcastedDeployAccountTransactionV1 {
+    pub fn contract_address(&self) -> Felt {
+        calculate_contract_address(
+            self.contract_address_salt,
+            self.class_hash,
+            &self.constructor_calldata,
+            Felt::ZERO,
+        )
+    }
+
+    pub fn transaction_hash(&self, chain_id: Felt) -> Felt {
+        let mut calldata = vec![self.class_hash, self.contract_address_salt];
+        calldata.extend_from_slice(&self.constructor_calldata);
+
+        compute_hash_on_elements(&[
+            Felt::from_short_string(PREFIX_DEPLOY_ACCOUNT).unwrap(),
+            transaction_version(Felt::ONE, self.is_query),
+            self.contract_address(),
+            Felt::ZERO,
+            compute_hash_on_elements(&calldata),
+            self.max_fee,
+            chain_id,
+            self.nonce,
+        ])
+    }
+}
+
+impl BroadcastedDeployAccountTransactionV3 {
+    pub fn contract_address(&self) -> Felt {
+        calculate_contract_address(
+            self.contract_address_salt,
+            self.class_hash,
+            &self.constructor_calldata,
+            Felt::ZERO,
+        )
+    }
+
+    pub fn transaction_hash(&self, chain_id: Felt) -> Felt {
+        poseidon_hash_many(&[
+            Felt::from_short_string(PREFIX_DEPLOY_ACCOUNT).unwrap(),
+            transaction_version(Felt::THREE, self.is_query),
+            self.contract_address(),
+            fee_fields_hash(self.tip, &self.resource_bounds),
+            poseidon_hash_many(&self.paymaster_data),
+            chain_id,
+            self.nonce,
+            data_availability_modes(
+                self.nonce_data_availability_mode,
+                self.fee_data_availability_mode,
+            ),
+            poseidon_hash_many(&self.constructor_calldata),
+            self.class_hash,
+            self.contract_address_salt,
+        ])
+    }
+}
+
+impl BroadcastedDeclareTransactionV3 {
+    /// The hash of the declared class, see [`SierraClass::class_hash`].
+    pub fn class_hash(&self) -> Felt {
+        self.contract_class.class_hash()
+    }
+
+    pub fn transaction_hash(&self, chain_id: Felt) -> Felt {
+        poseidon_hash_many(&[
+            Felt::from_short_string(PREFIX_DECLARE).unwrap(),
+            transaction_version(Felt::THREE, self.is_query),
+            self.sender_address,
+            fee_fields_hash(self.tip, &self.resource_bounds),
+            poseidon_hash_many(&self.paymaster_data),
+            chain_id,
+            self.nonce,
+            data_availability_modes(
+                self.nonce_data_availability_mode,
+                self.fee_data_availability_mode,
+            ),
+            poseidon_hash_many(&self.account_deployment_data),
+            self.class_hash(),
+            self.compiled_class_hash,
+        ])
+    }
+}
+
+impl BroadcastedInvokeTransaction {
+    pub fn transaction_hash(&self, chain_id: Felt) -> Felt {
+        match self {
+            BroadcastedInvokeTransaction::V1(tx) => tx.transaction_hash(chain_id),
+            BroadcastedInvokeTransaction::V3(tx) => tx.transaction_hash(chain_id),
+        }
+    }
+
+    pub fn is_query(&self) -> bool {
+        match self {
+            BroadcastedInvokeTransaction::V1(tx) => tx.is_query,
+            BroadcastedInvokeTransaction::V3(tx) => tx.is_query,
+        }
+    }
+}
+
+impl BroadcastedDeployAccountTransaction {
+    pub fn transaction_hash(&self, chain_id: Felt) -> Felt {
+        match self {
+            BroadcastedDeployAccountTransaction::V1(tx) => tx.transaction_hash(chain_id),
+            BroadcastedDeployAccountTransaction::V3(tx) => tx.transaction_hash(chain_id),
+        }
+    }
+
+    pub fn contract_address(&self) -> Felt {
+        match self {
+            BroadcastedDeployAccountTransaction::V1(tx) => tx.contract_address(),
+            BroadcastedDeployAccountTransaction::V3(tx) => tx.contract_address(),
+        }
+    }
+
+    pub fn is_query(&self) -> bool {
+        match self {
+            BroadcastedDeployAccountTransaction::V1(tx) => tx.is_query,
+            BroadcastedDeployAccountTransaction::V3(tx) => tx.is_query,
+        }
+    }
+}
+
+impl BroadcastedDeclareTransaction {
+    pub fn is_query(&self) -> bool {
+        match self {
+            BroadcastedDeclareTransaction::V1(tx) => tx.is_query,
+            BroadcastedDeclareTransaction::V3(tx) => tx.is_query,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invoke_v3() -> BroadcastedInvokeTransactionV3 {
+        BroadcastedInvokeTransactionV3 {
+            sender_address: Felt::from_hex("0x1234").unwrap(),
+            calldata: vec![Felt::ONE, Felt::TWO],
+            signature: vec![],
+            nonce: Felt::from(5u64),
+            resource_bounds: ResourceBoundsMapping {
+                l1_gas: ResourceBounds {
+                    max_amount: 0,
+                    max_price_per_unit: 0x5af3107a4000,
+                },
+                l2_gas: ResourceBounds {
+                    max_amount: 0x186a0,
+                    max_price_per_unit: 0x2540be400,
+                },
+                l1_data_gas: Some(ResourceBounds {
+                    max_amount: 0x180,
+                    max_price_per_unit: 0x3e8,
+                }),
+            },
+            tip: 0,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            is_query: false,
+        }
+    }
+
+    #[test]
+    fn v3_invoke_serializes_type_version_and_hex_bounds() {
+        let tx = BroadcastedInvokeTransaction::V3(invoke_v3());
+        let value = serde_json::to_value(&tx).unwrap();
+        assert_eq!(value["type"], "INVOKE");
+        assert_eq!(value["version"], "0x3");
+        assert_eq!(value["tip"], "0x0");
+        assert_eq!(value["nonce_data_availability_mode"], "L1");
+        assert_eq!(value["resource_bounds"]["l2_gas"]["max_amount"], "0x186a0");
+        assert_eq!(
+            value["resource_bounds"]["l1_data_gas"]["max_price_per_unit"],
+            "0x3e8"
+        );
+
+        let parsed: BroadcastedInvokeTransaction = serde_json::from_value(value).unwrap();
+        assert!(matches!(parsed, BroadcastedInvokeTransaction::V3(_)));
+    }
+
+    #[test]
+    fn broadcasted_transaction_dispatches_on_type() {
+        let tx = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V3(invoke_v3()));
+        let value = serde_json::to_value(&tx).unwrap();
+        let parsed: BroadcastedTransaction = serde_json::from_value(value).unwrap();
+        assert!(matches!(
+            parsed,
+            BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V3(_))
+        ));
+
+        let value = serde_json::json!({"type": "DECLARE", "version": "0x3"});
+        assert!(serde_json::from_value::<BroadcastedTransaction>(value).is_err());
+    }
+
+    fn declare_v3() -> BroadcastedDeclareTransactionV3 {
+        let invoke = invoke_v3();
+        BroadcastedDeclareTransactionV3 {
+            sender_address: invoke.sender_address,
+            compiled_class_hash: Felt::from(0xcafeu64),
+            signature: vec![],
+            nonce: invoke.nonce,
+            contract_class: SierraClass {
+                sierra_program: vec![Felt::ONE],
+                contract_class_version: "0.1.0".to_string(),
+                entry_points_by_type: Default::default(),
+                abi: Some("[]".to_string()),
+            },
+            resource_bounds: invoke.resource_bounds,
+            tip: 0,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            is_query: false,
+        }
+    }
+
+    #[test]
+    fn declare_v3_carries_the_sierra_class() {
+        let tx = BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V3(declare_v3()));
+        let value = serde_json::to_value(&tx).unwrap();
+        assert_eq!(value["type"], "DECLARE");
+        assert_eq!(value["version"], "0x3");
+        assert_eq!(value["compiled_class_hash"], "0xcafe");
+        assert_eq!(value["contract_class"]["contract_class_version"], "0.1.0");
+
+        let parsed: BroadcastedTransaction = serde_json::from_value(value).unwrap();
+        assert!(matches!(
+            parsed,
+            BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V3(_))
+        ));
+    }
+
+    #[test]
+    fn declare_v3_hash_commits_to_both_class_hashes() {
+        let chain_id = Felt::from_short_string("SN_SEPOLIA").unwrap();
+        let tx = declare_v3();
+        let hash = tx.transaction_hash(chain_id);
+
+        let mut recompiled = tx.clone();
+        recompiled.compiled_class_hash = Felt::from(0xbeefu64);
+        assert_ne!(recompiled.transaction_hash(chain_id), hash);
+
+        let mut other_class = tx.clone();
+        other_class.contract_class.abi = Some("[ ]".to_string());
+        assert_ne!(other_class.class_hash(), tx.class_hash());
+        assert_ne!(other_class.transaction_hash(chain_id), hash);
+    }
+
+    #[test]
+    fn query_version_is_offset_and_signed() {
+        let chain_id = Felt::from_short_string("SN_SEPOLIA").unwrap();
+        let tx = invoke_v3();
+        let query = BroadcastedInvokeTransactionV3 {
+            is_query: true,
+            ..tx.clone()
+        };
+        assert_ne!(
+            tx.transaction_hash(chain_id),
+            query.transaction_hash(chain_id)
+        );
+
+        let value = serde_json::to_value(BroadcastedInvokeTransaction::V3(query)).unwrap();
+        assert_eq!(value["version"], "0x100000000000000000000000000000003");
+        let parsed: BroadcastedInvokeTransaction = serde_json::from_value(value).unwrap();
+        assert!(matches!(parsed, BroadcastedInvokeTransaction::V3(tx) if tx.is_query));
+    }
+
+    #[test]
+    fn v3_hash_commits_to_resource_bounds() {
+        let chain_id = Felt::from_short_string("SN_SEPOLIA").unwrap();
+        let tx = invoke_v3();
+        let mut bumped = tx.clone();
+        bumped
+            .resource_bounds
+            .l1_data_gas
+            .as_mut()
+            .unwrap()
+            .max_amount += 1;
+        assert_ne!(
+            tx.transaction_hash(chain_id),
+            bumped.transaction_hash(chain_id)
+        );
+    }
+
+    #[test]
+    fn two_bound_transactions_hash_without_l1_data_gas() {
+        let chain_id = Felt::from_short_string("SN_SEPOLIA").unwrap();
+        let mut value =
+            serde_json::to_value(BroadcastedInvokeTransaction::V3(invoke_v3())).unwrap();
+        value["resource_bounds"]
+            .as_object_mut()
+            .unwrap()
+            .remove("l1_data_gas");
+        let BroadcastedInvokeTransaction::V3(tx) = serde_json::from_value(value.clone()).unwrap()
+        else {
+            panic!("parsed as v1");
+        };
+        assert_eq!(tx.resource_bounds.l1_data_gas, None);
+        assert_eq!(
+            serde_json::to_value(BroadcastedInvokeTransaction::V3(tx.clone())).unwrap(),
+            value
+        );
+
+        let mut zero_data_gas = tx.clone();
+        zero_data_gas.resource_bounds.l1_data_gas = Some(ResourceBounds::default());
+        assert_ne!(
+            tx.transaction_hash(chain_id),
+            zero_data_gas.transaction_hash(chain_id)
+        );
+    }
+
+    #[test]
+    fn deploy_account_address_ignores_version() {
+        let v1 = BroadcastedDeployAccountTransactionV1 {
+            signature: vec![],
+            nonce: Felt::ZERO,
+            max_fee: Felt::ZERO,
+            contract_address_salt: Felt::from(0x1234u64),
+            constructor_calldata: vec![Felt::from(0xabcdu64)],
+            class_hash: Felt::from_hex(
+                "0x05400e90f7e0ae78bd02c77cd75527280470e2fe19c54970dd79dc37a9d3645c",
+            )
+            .unwrap(),
+            is_query: false,
+        };
+        let v3 = BroadcastedDeployAccountTransactionV3 {
+            signature: vec![],
+            nonce: Felt::ZERO,
+            contract_address_salt: v1.contract_address_salt,
+            constructor_calldata: v1.constructor_calldata.clone(),
+            class_hash: v1.class_hash,
+            resource_bounds: ResourceBoundsMapping::default(),
+            tip: 0,
+            paymaster_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            is_query: false,
+        };
+        assert_eq!(v1.contract_address(), v3.contract_address());
+    }
+}
@@ -0,0 +1,661 @@
+//! Recomputation of block commitments and block hashes, to check blocks served by an untrusted
+//! node.
+//!
+//! Before Starknet 0.13.2 a block hash covers Pedersen tries of its transactions and events. From
+//! 0.13.2 on the tries use Poseidon, and the hash also covers the receipts, the state diff and the
+//! gas prices. Blocks before 0.11.1 are not supported: their transaction commitment only covers
+//! the signatures of invoke transactions.
+
+use crate::types::block::{Block, BlockHeader, L1DataAvailabilityMode, TransactionWithReceipt};
+use crate::types::felt::Felt;
+use crate::types::proof::TrieHash;
+use crate::types::receipt::{TransactionExecutionStatus, TransactionReceipt};
+use crate::types::state_update::StateDiff;
+use crate::types::transaction::Transaction;
+use crate::utils::hash::{
+    compute_hash_on_elements, pedersen_hash, poseidon_hash_many, starknet_keccak,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+
+const PREFIX_BLOCK_HASH_V0: &str = "STARKNET_BLOCK_HASH0";
+const PREFIX_BLOCK_HASH_V1: &str = "STARKNET_BLOCK_HASH1";
+const PREFIX_GAS_PRICES_V0: &str = "STARKNET_GAS_PRICES0";
+const PREFIX_STATE_DIFF_V0: &str = "STARKNET_STATE_DIFF0";
+
+/// The height of the transaction, event and receipt tries, which are keyed by index.
+const COMMITMENT_TRIE_HEIGHT: usize = 64;
+
+/// How a block commits to its contents, which depends on its Starknet version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BlockHashVersion {
+    /// Pedersen tries of transactions and events, from Starknet 0.11.1 to 0.13.1.
+    Legacy,
+    /// Poseidon tries and commitments to receipts and the state diff, from 0.13.2.
+    V0_13_2,
+    /// Like 0.13.2 with the gas prices hashed together with the L2 gas price, from 0.13.4.
+    V0_13_4,
+}
+
+impl BlockHashVersion {
+    /// The version of a block header's `starknet_version`, such as `0.13.2.1`.
+    pub fn from_starknet_version(version: &str) -> Result<Self, BlockError> {
+        let parts = version
+            .split('.')
+            .map(str::parse)
+            .collect::<Result<Vec<u64>, _>>()
+            .map_err(|_| BlockError::UnsupportedVersion(version.to_string()))?;
+        if parts.as_slice() < [0, 11, 1].as_slice() {
+            Err(BlockError::UnsupportedVersion(version.to_string()))
+        } else if parts.as_slice() < [0, 13, 2].as_slice() {
+            Ok(BlockHashVersion::Legacy)
+        } else if parts.as_slice() < [0, 13, 4].as_slice() {
+            Ok(BlockHashVersion::V0_13_2)
+        } else {
+            Ok(BlockHashVersion::V0_13_4)
+        }
+    }
+
+    fn trie_hash(self) -> TrieHash {
+        match self {
+            BlockHashVersion::Legacy => TrieHash::Pedersen,
+            BlockHashVersion::V0_13_2 | BlockHashVersion::V0_13_4 => TrieHash::Poseidon,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockError {
+    /// A `starknet_version` that is malformed or predates the supported block hash formats.
+    UnsupportedVersion(String),
+    /// A 0.13.4 or later header without an L2 gas price.
+    MissingL2GasPrice,
+    /// The receipt at this index is not the receipt of the transaction next to it.
+    ReceiptMismatch(usize),
+    /// The recomputed block hash differs from the one in the header.
+    BlockHashMismatch { expected: Felt, actual: Felt },
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockError::UnsupportedVersion(version) => {
+                write!(f, "unsupported Starknet version `{version}`")
+            }
+            BlockError::MissingL2GasPrice => write!(f, "header lacks the L2 gas price"),
+            BlockError::ReceiptMismatch(index) => {
+                write!(f, "receipt {index} belongs to another transaction")
+            }
+            BlockError::BlockHashMismatch { expected, actual } => {
+                write!(f, "block hash is {actual}, header claims {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlockError {}
+
+/// The root of a Patricia trie of height 64 holding `leaves` at their indices, zero when empty.
+pub fn commitment_root(leaves: &[Felt], hash: TrieHash) -> Felt {
+    let leaves: Vec<(u64, Felt)> = leaves
+        .iter()
+        .enumerate()
+        .map(|(index, leaf)| (index as u64, *leaf))
+        .collect();
+    if leaves.is_empty() {
+        Felt::ZERO
+    } else {
+        subtree_root(&leaves, COMMITMENT_TRIE_HEIGHT, hash)
+    }
+}
+
+/// The hash of the node `height` levels above the sorted `leaves`, which share all higher bits.
+fn subtree_root(leaves: &[(u64, Felt)], height: usize, hash: TrieHash) -> Felt {
+    if height == 0 {
+        return leaves[0].1;
+    }
+    let first = leaves[0].0;
+    let last = leaves[leaves.len() - 1].0;
+    let differing = COMMITMENT_TRIE_HEIGHT - (first ^ last).leading_zeros() as usize;
+    let shared = height - differing;
+    if shared > 0 {
+        let below = height - shared;
+        let mask = u64::MAX >> (COMMITMENT_TRIE_HEIGHT - shared);
+        let path = (first >> below) & mask;
+        let child = subtree_root(leaves, below, hash);
+        return hash.hash(child, Felt::from(path)) + Felt::from(shared as u64);
+    }
+    let split = leaves.partition_point(|(index, _)| index >> (height - 1) & 1 == 0);
+    hash.hash(
+        subtree_root(&leaves[..split], height - 1, hash),
+        subtree_root(&leaves[split..], height - 1, hash),
+    )
+}
+
+/// Commits to the hashes and signatures of a block's transactions.
+pub fn transaction_commitment<'a>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+    version: BlockHashVersion,
+) -> Felt {
+    let leaves: Vec<Felt> = transactions
+        .into_iter()
+        .map(|tx| match version {
+            BlockHashVersion::Legacy => pedersen_hash(
+                &tx.transaction_hash,
+                &compute_hash_on_elements(&tx.signature),
+            ),
+            _ => {
+                let mut elements = vec![tx.transaction_hash];
+                if tx.signature.is_empty() {
+                    elements.push(Felt::ZERO);
+                } else {
+                    elements.extend_from_slice(&tx.signature);
+                }
+                poseidon_hash_many(&elements)
+            }
+        })
+        .collect();
+    commitment_root(&leaves, version.trie_hash())
+}
+
+/// Commits to the events of a block, in emission order.
+pub fn event_commitment<'a>(
+    receipts: impl IntoIterator<Item = &'a TransactionReceipt>,
+    version: BlockHashVersion,
+) -> Felt {
+    let leaves: Vec<Felt> = receipts
+        .into_iter()
+        .flat_map(|receipt| {
+            receipt.events.iter().map(move |event| match version {
+                BlockHashVersion::Legacy => compute_hash_on_elements(&[
+                    event.from_address,
+                    compute_hash_on_elements(&event.keys),
+                    compute_hash_on_elements(&event.data),
+                ]),
+                _ => {
+                    let mut elements = vec![event.from_address, receipt.transaction_hash];
+                    push_with_length(&mut elements, &event.keys);
+                    push_with_length(&mut elements, &event.data);
+                    poseidon_hash_many(&elements)
+                }
+            })
+        })
+        .collect();
+    commitment_root(&leaves, version.trie_hash())
+}
+
+/// Commits to the fees, messages, revert reasons and gas of a block's receipts. Only blocks from
+/// Starknet 0.13.2 on have one.
+pub fn receipt_commitment<'a>(receipts: impl IntoIterator<Item = &'a TransactionReceipt>) -> Felt {
+    let leaves: Vec<Felt> = receipts
+        .into_iter()
+        .map(|receipt| {
+            let mut messages = vec![Felt::from(receipt.messages_sent.len())];
+            for message in &receipt.messages_sent {
+                messages.push(message.from_address);
+                messages.push(message.to_address);
+                push_with_length(&mut messages, &message.payload);
+            }
+            let revert_reason = match receipt.execution_status {
+                TransactionExecutionStatus::Succeeded => Felt::ZERO,
+                TransactionExecutionStatus::Reverted => starknet_keccak(
+                    receipt
+                        .revert_reason
+                        .as_deref()
+                        .unwrap_or_default()
+                        .as_bytes(),
+                ),
+            };
+            poseidon_hash_many(&[
+                receipt.transaction_hash,
+                receipt.actual_fee.amount,
+                poseidon_hash_many(&messages),
+                revert_reason,
+                // L2 gas is not committed to yet.
+                Felt::ZERO,
+                Felt::from(receipt.execution_resources.l1_gas),
+                Felt::from(receipt.execution_resources.l1_data_gas),
+            ])
+        })
+        .collect();
+    commitment_root(&leaves, TrieHash::Poseidon)
+}
+
+/// Commits to a block's state diff, with every list sorted by address or class hash.
+pub fn state_diff_commitment(state_diff: &StateDiff) -> Felt {
+    let mut elements = vec![Felt::from_short_string(PREFIX_STATE_DIFF_V0).unwrap()];
+
+    let updated_contracts: BTreeMap<Felt, Felt> = state_diff
+        .deployed_contracts
+        .iter()
+        .map(|deployed| (deployed.address, deployed.class_hash))
+        .chain(
+            state_diff
+                .replaced_classes
+                .iter()
+                .map(|replaced| (replaced.contract_address, replaced.class_hash)),
+        )
+        .collect();
+    push_sorted_pairs(&mut elements, &updated_contracts);
+
+    let declared: BTreeMap<Felt, Felt> = state_diff
+        .declared_classes
+        .iter()
+        .map(|declared| (declared.class_hash, declared.compiled_class_hash))
+        .collect();
+    push_sorted_pairs(&mut elements, &declared);
+
+    let mut deprecated_declared = state_diff.deprecated_declared_classes.clone();
+    deprecated_declared.sort();
+    push_with_length(&mut elements, &deprecated_declared);
+
+    // Placeholders for the data availability modes of the following updates.
+    elements.extend([Felt::ONE, Felt::ZERO]);
+
+    let storage_diffs: BTreeMap<Felt, BTreeMap<Felt, Felt>> = state_diff
+        .storage_diffs
+        .iter()
+        .filter(|diff| !diff.storage_entries.is_empty())
+        .map(|diff| {
+            let entries = diff
+                .storage_entries
+                .iter()
+                .map(|entry| (entry.key, entry.value))
+                .collect();
+            (diff.address, entries)
+        })
+        .collect();
+    elements.push(Felt::from(storage_diffs.len()));
+    for (address, entries) in &storage_diffs {
+        elements.push(*address);
+        push_sorted_pairs(&mut elements, entries);
+    }
+
+    let nonces: BTreeMap<Felt, Felt> = state_diff
+        .nonces
+        .iter()
+        .map(|update| (update.contract_address, update.nonce))
+        .collect();
+    push_sorted_pairs(&mut elements, &nonces);
+
+    poseidon_hash_many(&elements)
+}
+
+/// The number of updates in a state diff, as counted by the block hash.
+pub fn state_diff_length(state_diff: &StateDiff) -> u64 {
+    let storage_entries: usize = state_diff
+        .storage_diffs
+        .iter()
+        .map(|diff| diff.storage_entries.len())
+        .sum();
+    (storage_entries
+        + state_diff.nonces.len()
+        + state_diff.deployed_contracts.len()
+        + state_diff.replaced_classes.len()
+        + state_diff.declared_classes.len()
+        + state_diff.deprecated_declared_classes.len()) as u64
+}
+
+fn push_with_length(elements: &mut Vec<Felt>, values: &[Felt]) {
+    elements.push(Felt::from(values.len()));
+    elements.extend_from_slice(values);
+}
+
+fn push_sorted_pairs(elements: &mut Vec<Felt>, pairs: &BTreeMap<Felt, Felt>) {
+    elements.push(Felt::from(pairs.len()));
+    for (key, value) in pairs {
+        elements.extend([*key, *value]);
+    }
+}
+
+/// Everything a block hash commits to besides the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockCommitments {
+    pub transaction_count: u64,
+    pub transaction_commitment: Felt,
+    pub event_count: u64,
+    pub event_commitment: Felt,
+    /// Zero before Starknet 0.13.2.
+    pub receipt_commitment: Felt,
+    /// Zero before Starknet 0.13.2.
+    pub state_diff_commitment: Felt,
+    /// Zero before Starknet 0.13.2.
+    pub state_diff_length: u64,
+}
+
+impl BlockCommitments {
+    /// Computes the commitments of `block`, whose state diff is `state_diff` as returned by
+    /// `starknet_getStateUpdate`.
+    pub fn compute(
+        block: &Block<TransactionWithReceipt>,
+        state_diff: &StateDiff,
+        version: BlockHashVersion,
+    ) -> Self {
+        let transactions = || block.transactions.iter().map(|tx| &tx.transaction);
+        let receipts = || block.transactions.iter().map(|tx| &tx.receipt);
+        let legacy = version == BlockHashVersion::Legacy;
+        BlockCommitments {
+            transaction_count: block.transactions.len() as u64,
+            transaction_commitment: transaction_commitment(transactions(), version),
+            event_count: receipts().map(|receipt| receipt.events.len() as u64).sum(),
+            event_commitment: event_commitment(receipts(), version),
+            receipt_commitment: if legacy {
+                Felt::ZERO
+            } else {
+                receipt_commitment(receipts())
+            },
+            state_diff_commitment: if legacy {
+                Felt::ZERO
+            } else {
+                state_diff_commitment(state_diff)
+            },
+            state_diff_length: if legacy {
+                0
+            } else {
+                state_diff_length(state_diff)
+            },
+        }
+    }
+}
+
+/// Packs the counts of a block and its data availability mode into one field element: 64 bits
+/// each for transactions, events and state diff entries, then one bit for blob mode.
+fn concat_counts(commitments: &BlockCommitments, da_mode: L1DataAvailabilityMode) -> Felt {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&commitments.transaction_count.to_be_bytes());
+    bytes[8..16].copy_from_slice(&commitments.event_count.to_be_bytes());
+    bytes[16..24].copy_from_slice(&commitments.state_diff_length.to_be_bytes());
+    if da_mode == L1DataAvailabilityMode::Blob {
+        bytes[24] = 0x80;
+    }
+    Felt::from_bytes_be(&bytes).expect("counts below 2^59 fit in a field element")
+}
+
+/// The hash of a block with `header` and `commitments`, in the format of its Starknet version.
+pub fn block_hash(
+    header: &BlockHeader,
+    commitments: &BlockCommitments,
+) -> Result<Felt, BlockError> {
+    let version = BlockHashVersion::from_starknet_version(&header.starknet_version)?;
+    if version == BlockHashVersion::Legacy {
+        return Ok(compute_hash_on_elements(&[
+            Felt::from(header.block_number),
+            header.new_root,
+            header.sequencer_address,
+            Felt::from(header.timestamp),
+            Felt::from(commitments.transaction_count),
+            commitments.transaction_commitment,
+            Felt::from(commitments.event_count),
+            commitments.event_commitment,
+            Felt::ZERO,
+            Felt::ZERO,
+            header.parent_hash,
+        ]));
+    }
+
+    let prefix = match version {
+        BlockHashVersion::V0_13_4 => PREFIX_BLOCK_HASH_V1,
+        _ => PREFIX_BLOCK_HASH_V0,
+    };
+    let mut elements = vec![
+        Felt::from_short_string(prefix).unwrap(),
+        Felt::from(header.block_number),
+        header.new_root,
+        header.sequencer_address,
+        Felt::from(header.timestamp),
+        concat_counts(commitments, header.l1_da_mode),
+        commitments.state_diff_commitment,
+        commitments.transaction_commitment,
+        commitments.event_commitment,
+        commitments.receipt_commitment,
+    ];
+    let gas_prices = [
+        header.l1_gas_price.price_in_wei,
+        header.l1_gas_price.price_in_fri,
+        header.l1_data_gas_price.price_in_wei,
+        header.l1_data_gas_price.price_in_fri,
+    ];
+    if version == BlockHashVersion::V0_13_4 {
+        let l2_gas_price = header.l2_gas_price.ok_or(BlockError::MissingL2GasPrice)?;
+        let mut prices = vec![Felt::from_short_string(PREFIX_GAS_PRICES_V0).unwrap()];
+        prices.extend(gas_prices);
+        prices.extend([l2_gas_price.price_in_wei, l2_gas_price.price_in_fri]);
+        elements.push(poseidon_hash_many(&prices));
+    } else {
+        elements.extend(gas_prices);
+    }
+    elements.extend([
+        Felt::from_short_string(&header.starknet_version)
+            .map_err(|_| BlockError::UnsupportedVersion(header.starknet_version.clone()))?,
+        Felt::ZERO,
+        header.parent_hash,
+    ]);
+    Ok(poseidon_hash_many(&elements))
+}
+
+/// Checks a block from `starknet_getBlockWithReceipts` by recomputing its hash from its
+/// transactions, receipts and `state_diff` (from `starknet_getStateUpdate` for the same block).
+/// The transaction hashes themselves are taken as given.
+pub fn verify_block(
+    block: &Block<TransactionWithReceipt>,
+    state_diff: &StateDiff,
+) -> Result<(), BlockError> {
+    for (index, tx) in block.transactions.iter().enumerate() {
+        if tx.receipt.transaction_hash != tx.transaction.transaction_hash {
+            return Err(BlockError::ReceiptMismatch(index));
+        }
+    }
+    let version = BlockHashVersion::from_starknet_version(&block.header.starknet_version)?;
+    let commitments = BlockCommitments::compute(block, state_diff, version);
+    let actual = block_hash(&block.header, &commitments)?;
+    if actual == block.header.block_hash {
+        Ok(())
+    } else {
+        Err(BlockError::BlockHashMismatch {
+            expected: block.header.block_hash,
+            actual,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::state_update::{ContractStorageDiff, NonceUpdate, StorageEntry};
+    use serde_json::json;
+
+    #[test]
+    fn commitment_roots_use_edges_for_shared_index_bits() {
+        let hash = TrieHash::Poseidon;
+        let leaf = |n: u64| Felt::from(n);
+        let edge = |child: Felt, path: u64, length: u64| {
+            hash.hash(child, Felt::from(path)) + Felt::from(length)
+        };
+
+        assert_eq!(commitment_root(&[], hash), Felt::ZERO);
+        assert_eq!(commitment_root(&[leaf(7)], hash), edge(leaf(7), 0, 64));
+        // Indices 0 and 1 split at the last bit, below an edge of 63 zeros.
+        let pair = hash.hash(leaf(7), leaf(8));
+        assert_eq!(
+            commitment_root(&[leaf(7), leaf(8)], hash),
+            edge(pair, 0, 63)
+        );
+        // Index 2 leaves the pair at the second to last bit and keeps a one-bit edge of its own.
+        assert_eq!(
+            commitment_root(&[leaf(7), leaf(8), leaf(9)], hash),
+            edge(hash.hash(pair, edge(leaf(9), 0, 1)), 0, 62)
+        );
+    }
+
+    #[test]
+    fn packs_counts_and_the_data_availability_mode() {
+        let commitments = BlockCommitments {
+            transaction_count: 1,
+            transaction_commitment: Felt::ZERO,
+            event_count: 2,
+            event_commitment: Felt::ZERO,
+            receipt_commitment: Felt::ZERO,
+            state_diff_commitment: Felt::ZERO,
+            state_diff_length: 3,
+        };
+        assert_eq!(
+            concat_counts(&commitments, L1DataAvailabilityMode::Blob),
+            Felt::from_hex("0x0000000000000001000000000000000200000000000000038000000000000000")
+                .unwrap()
+        );
+        assert_eq!(
+            concat_counts(&commitments, L1DataAvailabilityMode::Calldata),
+            Felt::from_hex("0x0000000000000001000000000000000200000000000000030000000000000000")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn state_diff_commitment_ignores_order_and_empty_storage() {
+        let diff = StateDiff {
+            storage_diffs: vec![
+                ContractStorageDiff {
+                    address: Felt::from(2u64),
+                    storage_entries: vec![
+                        StorageEntry {
+                            key: Felt::from(5u64),
+                            value: Felt::ONE,
+                        },
+                        StorageEntry {
+                            key: Felt::from(4u64),
+                            value: Felt::TWO,
+                        },
+                    ],
+                },
+                ContractStorageDiff {
+                    address: Felt::from(1u64),
+                    storage_entries: vec![],
+                },
+            ],
+            nonces: vec![
+                NonceUpdate {
+                    contract_address: Felt::from(9u64),
+                    nonce: Felt::ONE,
+                },
+                NonceUpdate {
+                    contract_address: Felt::from(8u64),
+                    nonce: Felt::TWO,
+                },
+            ],
+            ..StateDiff::default()
+        };
+        let mut shuffled = diff.clone();
+        shuffled.storage_diffs.reverse();
+        shuffled.storage_diffs[1].storage_entries.reverse();
+        shuffled.nonces.reverse();
+        assert_eq!(
+            state_diff_commitment(&diff),
+            state_diff_commitment(&shuffled)
+        );
+        assert_eq!(state_diff_length(&diff), 4);
+
+        let mut elements = vec![Felt::from_short_string(PREFIX_STATE_DIFF_V0).unwrap()];
+        elements.extend([Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ONE, Felt::ZERO]);
+        elements.extend([Felt::ONE, Felt::TWO, Felt::TWO]);
+        elements.extend([Felt::from(4u64), Felt::TWO, Felt::from(5u64), Felt::ONE]);
+        elements.extend([
+            Felt::TWO,
+            Felt::from(8u64),
+            Felt::TWO,
+            Felt::from(9u64),
+            Felt::ONE,
+        ]);
+        assert_eq!(state_diff_commitment(&diff), poseidon_hash_many(&elements));
+    }
+
+    fn block(starknet_version: &str) -> Block<TransactionWithReceipt> {
+        serde_json::from_value(json!({
+            "status": "ACCEPTED_ON_L2",
+            "block_hash": "0x0",
+            "parent_hash": "0x1",
+            "block_number": 9,
+            "new_root": "0xa",
+            "timestamp": 1700000000,
+            "sequencer_address": "0x2",
+            "l1_gas_price": {"price_in_fri": "0x3", "price_in_wei": "0x4"},
+            "l1_data_gas_price": {"price_in_fri": "0x5", "price_in_wei": "0x6"},
+            "l2_gas_price": {"price_in_fri": "0x7", "price_in_wei": "0x8"},
+            "l1_da_mode": "BLOB",
+            "starknet_version": starknet_version,
+            "transactions": [{
+                "transaction": {
+                    "transaction_hash": "0xb",
+                    "type": "INVOKE",
+                    "version": "0x3",
+                    "sender_address": "0xc",
+                    "calldata": ["0x1"],
+                    "signature": ["0xd", "0xe"],
+                    "nonce": "0x0",
+                },
+                "receipt": {
+                    "type": "INVOKE",
+                    "transaction_hash": "0xb",
+                    "actual_fee": {"amount": "0x64", "unit": "FRI"},
+                    "execution_status": "SUCCEEDED",
+                    "finality_status": "ACCEPTED_ON_L2",
+                    "messages_sent": [],
+                    "events": [{"from_address": "0xc", "keys": ["0x1"], "data": []}],
+                    "execution_resources": {"l1_gas": 0, "l1_data_gas": 128, "l2_gas": 0},
+                },
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn verifies_block_hashes_of_every_format() {
+        let state_diff = StateDiff::default();
+        for version in ["0.13.1.1", "0.13.2", "0.13.4"] {
+            let mut block = block(version);
+            let format = BlockHashVersion::from_starknet_version(version).unwrap();
+            let commitments = BlockCommitments::compute(&block, &state_diff, format);
+            block.header.block_hash = block_hash(&block.header, &commitments).unwrap();
+            assert_eq!(verify_block(&block, &state_diff), Ok(()));
+
+            let claimed = block.header.block_hash;
+            block.transactions[0].receipt.events[0].data.push(Felt::ONE);
+            assert!(matches!(
+                verify_block(&block, &state_diff),
+                Err(BlockError::BlockHashMismatch { expected, .. }) if expected == claimed
+            ));
+        }
+    }
+
+    #[test]
+    fn reports_malformed_blocks() {
+        let mut block = block("0.13.2");
+        block.transactions[0].receipt.transaction_hash = Felt::ONE;
+        assert_eq!(
+            verify_block(&block, &StateDiff::default()),
+            Err(BlockError::ReceiptMismatch(0))
+        );
+
+        let mut block = self::block("0.13.4");
+        block.header.l2_gas_price = None;
+        assert_eq!(
+            verify_block(&block, &StateDiff::default()),
+            Err(BlockError::MissingL2GasPrice)
+        );
+        assert_eq!(
+            BlockHashVersion::from_starknet_version("0.6.2"),
+            Err(BlockError::UnsupportedVersion("0.6.2".to_string()))
+        );
+        assert_eq!(
+            BlockHashVersion::from_starknet_version("0.11.0.2"),
+            Err(BlockError::UnsupportedVersion("0.11.0.2".to_string()))
+        );
+        assert_eq!(
+            BlockHashVersion::from_starknet_version("0.11.1"),
+            Ok(BlockHashVersion::Legacy)
+        );
+        assert_eq!(
+            BlockHashVersion::from_starknet_version(""),
+            Err(BlockError::UnsupportedVersion(String::new()))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::types::felt::Felt;
+use ethers::utils::keccak256;
+use lazy_static::lazy_static;
+use starknet_ff::FieldElement;
+
+const CONTRACT_ADDRESS_PREFIX: &str = "STARKNET_CONTRACT_ADDRESS";
+const DEFAULT_ENTRY_POINT_NAME: &str = "__default__";
+const DEFAULT_L1_ENTRY_POINT_NAME: &str = "__l1_default__";
+
+lazy_static! {
+    /// 2 ** 251 - 256
+    static ref ADDR_BOUND: FieldElement = FieldElement::from_hex_be(
+        "0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff00"
+    )
+    .unwrap();
+}
+
+pub fn pedersen_hash(x: &Felt, y: &Felt) -> Felt {
+    starknet_crypto::pedersen_hash(&x.inner(), &y.inner()).into()
+}
+
+/// Pedersen hash chain over `data`, terminated by the length of `data`.
+pub fn compute_hash_on_elements(data: &[Felt]) -> Felt {
+    let hash = data
+        .iter()
+        .fold(Felt::ZERO, |acc, item| pedersen_hash(&acc, item));
+    pedersen_hash(&hash, &Felt::from(data.len()))
+}
+
+/// Keccak-256 of `data` truncated to its low 250 bits.
+pub fn starknet_keccak(data: &[u8]) -> Felt {
+    let mut hash = keccak256(data);
+    hash[0] &= 0x03;
+    Felt::from_bytes_be(&hash).unwrap()
+}
+
+/// The selector of the entry point or event called `name`. The Cairo 0 default entry points
+/// `__default__` and `__l1_default__` have selector zero.
+pub fn get_selector_from_name(name: &str) -> Felt {
+    if name == DEFAULT_ENTRY_POINT_NAME || name == DEFAULT_L1_ENTRY_POINT_NAME {
+        return Felt::ZERO;
+    }
+    starknet_keccak(name.as_bytes())
+}
+
+pub fn poseidon_hash(x: Felt, y: Felt) -> Felt {
+    starknet_crypto::poseidon_hash(x.inner(), y.inner()).into()
+}
+
+pub fn poseidon_hash_many(data: &[Felt]) -> Felt {
+    let data: Vec<FieldElement> = data.iter().map(|f| f.inner()).collect();
+    starknet_crypto::poseidon_hash_many(&data).into()
+}
+
+/// Computes the address of a contract deployed with `deployer_address` (zero for
+/// `DEPLOY_ACCOUNT` transactions and the UDC's `unique = false` mode).
+pub fn calculate_contract_address(
+    salt: Felt,
+    class_hash: Felt,
+    constructor_calldata: &[Felt],
+    deployer_address: Felt,
+) -> Felt {
+    let hash = compute_hash_on_elements(&[
+        Felt::from_short_string(CONTRACT_ADDRESS_PREFIX).unwrap(),
+        deployer_address,
+        salt,
+        class_hash,
+        compute_hash_on_elements(constructor_calldata),
+    ]);
+    Felt::from(hash.inner() % *ADDR_BOUND)
+}
+
+/// The storage address of the Cairo storage variable `name`, at `keys` for mappings.
+pub fn get_storage_var_address(name: &str, keys: &[Felt]) -> Felt {
+    let address = keys
+        .iter()
+        .fold(starknet_keccak(name.as_bytes()), |acc, key| {
+            pedersen_hash(&acc, key)
+        });
+    Felt::from(address.inner() % *ADDR_BOUND)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_on_elements_matches_reference() {
+        let hash = compute_hash_on_elements(&[Felt::ONE, Felt::TWO, Felt::THREE]);
+        assert_eq!(
+            hash,
+            Felt::from_hex("0xf9d95fbf356fbeda26538c92f7040abe51bf142350f73c9ee5ba7c660bae71")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn selector_matches_reference() {
+        assert_eq!(
+            get_selector_from_name("transfer"),
+            Felt::from_hex("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn default_entry_points_have_selector_zero() {
+        assert_eq!(get_selector_from_name("__default__"), Felt::ZERO);
+        assert_eq!(get_selector_from_name("__l1_default__"), Felt::ZERO);
+    }
+
+    #[test]
+    fn contract_address_matches_reference() {
+        let address = calculate_contract_address(
+            Felt::from(0x1234u64),
+            Felt::from_hex("0x05400e90f7e0ae78bd02c77cd75527280470e2fe19c54970dd79dc37a9d3645c")
+                .unwrap(),
+            &[Felt::from(0xabcdu64)],
+            Felt::ZERO,
+        );
+        assert_eq!(
+            address,
+            Felt::from_hex("0xfc723061d45b1cdace0ec3a78290ec6c8b7bf84224dc741b372824b2ec990")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn storage_var_address_matches_reference() {
+        assert_eq!(
+            get_storage_var_address("ERC20_balances", &[Felt::from(0x1234u64)]),
+            Felt::from_hex("0x4fc7b23d1ef6e4f099416be09d83699fe35126d2a42325636e93745a36ce3cb")
+                .unwrap()
+        );
+    }
+}
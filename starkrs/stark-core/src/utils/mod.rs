@@ -1 +1,3 @@
-pub mod constants;
\ No newline at end of file
+pub mod commitment;
+pub mod constants;
+pub mod hash;
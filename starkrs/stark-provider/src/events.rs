@@ -0,0 +1,301 @@
+//! Reading events over large block ranges, following continuation tokens.
+
+use crate::provider::Provider;
+use crate::rpc::ProviderError;
+use futures::stream::{self, BoxStream, Stream};
+use stark_core::types::event::{EmittedEvent, EventFilter};
+use stark_core::types::request::{BlockNumber, BlockTag};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// `PAGE_SIZE_TOO_BIG`, returned when the chunk size exceeds the node's limit.
+const PAGE_SIZE_TOO_BIG: i64 = 31;
+
+const DEFAULT_CHUNK_SIZE: u64 = 1000;
+const DEFAULT_BLOCK_RANGE: u64 = 10_000;
+
+/// A stream of every event matching a filter.
+///
+/// The block range is split into queries of at most [`EventsStream::block_range`] blocks, each
+/// read page by page. A range ending at the latest block ends at the latest block when the
+/// stream starts. Ranges bounded by the pending block are queried whole. When the node rejects
+/// the chunk size it is halved. Errors are yielded and the failed page is retried on the next
+/// poll.
+pub struct EventsStream<'a, P> {
+    provider: &'a P,
+    filter: EventFilter,
+    chunk_size: u64,
+    block_range: u64,
+    stream: Option<BoxStream<'a, Result<EmittedEvent, ProviderError>>>,
+}
+
+impl<'a, P: Provider> EventsStream<'a, P> {
+    pub fn new(provider: &'a P, filter: EventFilter) -> Self {
+        Self {
+            provider,
+            filter,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            block_range: DEFAULT_BLOCK_RANGE,
+            stream: None,
+        }
+    }
+
+    /// Sets how many events are requested per page.
+    pub fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Sets how many blocks one query spans.
+    pub fn block_range(mut self, block_range: u64) -> Self {
+        self.block_range = block_range.max(1);
+        self
+    }
+}
+
+impl<'a, P: Provider> Stream for EventsStream<'a, P> {
+    type Item = Result<EmittedEvent, ProviderError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.stream.is_none() {
+            let state = State {
+                provider: self.provider,
+                filter: self.filter.clone(),
+                chunk_size: self.chunk_size,
+                block_range: self.block_range,
+                plan: Plan::Unresolved,
+                query: None,
+                continuation_token: None,
+                events: VecDeque::new(),
+            };
+            self.stream = Some(Box::pin(stream::unfold(state, |mut state| async move {
+                let event = state.next().await?;
+                Some((event, state))
+            })));
+        }
+        self.stream.as_mut().unwrap().as_mut().poll_next(cx)
+    }
+}
+
+/// The block ranges still to query.
+enum Plan {
+    Unresolved,
+    /// The filter is queried as given; `true` once it was.
+    Whole(bool),
+    Split {
+        next: u64,
+        last: u64,
+    },
+}
+
+struct State<'a, P> {
+    provider: &'a P,
+    filter: EventFilter,
+    chunk_size: u64,
+    block_range: u64,
+    plan: Plan,
+    /// The range being read.
+    query: Option<EventFilter>,
+    continuation_token: Option<String>,
+    events: VecDeque<EmittedEvent>,
+}
+
+impl<'a, P: Provider> State<'a, P> {
+    async fn next(&mut self) -> Option<Result<EmittedEvent, ProviderError>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(Ok(event));
+            }
+            let query = match self.query.take() {
+                Some(query) => query,
+                None => match self.next_query().await {
+                    Ok(Some(query)) => query,
+                    Ok(None) => return None,
+                    Err(err) => return Some(Err(err)),
+                },
+            };
+            let page = self
+                .provider
+                .get_events(
+                    query.clone(),
+                    self.continuation_token.clone(),
+                    self.chunk_size,
+                )
+                .await;
+            let page = match page {
+                Ok(page) => page,
+                Err(ProviderError::Rpc(err))
+                    if err.code == PAGE_SIZE_TOO_BIG && self.chunk_size > 1 =>
+                {
+                    self.chunk_size /= 2;
+                    self.query = Some(query);
+                    continue;
+                }
+                Err(err) => {
+                    self.query = Some(query);
+                    return Some(Err(err));
+                }
+            };
+            self.events.extend(page.events);
+            self.continuation_token = page.continuation_token;
+            if self.continuation_token.is_some() {
+                self.query = Some(query);
+            }
+        }
+    }
+
+    async fn next_query(&mut self) -> Result<Option<EventFilter>, ProviderError> {
+        if let Plan::Unresolved = self.plan {
+            self.plan = self.resolve().await?;
+        }
+        match &mut self.plan {
+            Plan::Unresolved => unreachable!(),
+            Plan::Whole(queried) => {
+                let query = (!*queried).then(|| self.filter.clone());
+                *queried = true;
+                Ok(query)
+            }
+            Plan::Split { next, last } => {
+                if *next > *last {
+                    return Ok(None);
+                }
+                let end = (*last).min(next.saturating_add(self.block_range - 1));
+                let query = EventFilter {
+                    from_block: Some(BlockNumber::Number(*next)),
+                    to_block: Some(BlockNumber::Number(end)),
+                    ..self.filter.clone()
+                };
+                *next = end + 1;
+                Ok(Some(query))
+            }
+        }
+    }
+
+    async fn resolve(&self) -> Result<Plan, ProviderError> {
+        let is_pending = |block_id: &Option<BlockNumber>| {
+            matches!(block_id, Some(BlockNumber::BlockTag(BlockTag::Pending)))
+        };
+        if is_pending(&self.filter.from_block) || is_pending(&self.filter.to_block) {
+            return Ok(Plan::Whole(false));
+        }
+        let next = match &self.filter.from_block {
+            None => 0,
+            Some(block_id) => self.block_number(block_id).await?,
+        };
+        let last = match &self.filter.to_block {
+            None => self.provider.stark_block_number().await?,
+            Some(block_id) => self.block_number(block_id).await?,
+        };
+        Ok(Plan::Split { next, last })
+    }
+
+    async fn block_number(&self, block_id: &BlockNumber) -> Result<u64, ProviderError> {
+        match block_id {
+            BlockNumber::Number(number) => Ok(*number),
+            BlockNumber::BlockTag(_) => self.provider.stark_block_number().await,
+            BlockNumber::Hash(_) => {
+                let block = self
+                    .provider
+                    .get_block_with_tx_hashes(block_id.clone())
+                    .await?;
+                match block.accepted() {
+                    Some(block) => Ok(block.header.block_number),
+                    None => Err(ProviderError::Deserialize(serde::de::Error::custom(
+                        "expected an accepted block, got the pending block",
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::JsonRpcClient;
+    use crate::transport::MemoryTransport;
+    use futures::StreamExt;
+    use serde_json::{json, Value};
+    use stark_core::Felt;
+    use std::sync::{Arc, Mutex};
+
+    /// A node with one event per block up to block 25, accepting at most 4 events per page.
+    fn node(queries: Arc<Mutex<Vec<Value>>>) -> JsonRpcClient<MemoryTransport> {
+        JsonRpcClient::new(MemoryTransport::with_handler(move |request| {
+            let result = match request["method"].as_str().unwrap() {
+                "starknet_blockNumber" => json!(25),
+                "starknet_getEvents" => {
+                    let chunk = &request["params"][0];
+                    queries.lock().unwrap().push(chunk.clone());
+                    let chunk_size = chunk["chunk_size"].as_u64().unwrap();
+                    if chunk_size > 4 {
+                        return json!({
+                            "jsonrpc": "2.0",
+                            "id": request["id"],
+                            "error": {"code": PAGE_SIZE_TOO_BIG, "message": "Requested page size is too big"},
+                        });
+                    }
+                    let from = chunk["from_block"]["block_number"].as_u64().unwrap();
+                    let to = chunk["to_block"]["block_number"].as_u64().unwrap();
+                    let offset: u64 = chunk["continuation_token"]
+                        .as_str()
+                        .map_or(0, |token| token.parse().unwrap());
+                    let start = from + offset;
+                    let end = to.min(start + chunk_size - 1);
+                    let events: Vec<Value> = (start..=end)
+                        .map(|block_number| {
+                            json!({
+                                "from_address": chunk["address"],
+                                "keys": [],
+                                "data": [],
+                                "block_hash": Felt::from(block_number + 0x100),
+                                "block_number": block_number,
+                                "transaction_hash": "0x1",
+                            })
+                        })
+                        .collect();
+                    let mut page = json!({ "events": events });
+                    if end < to {
+                        page["continuation_token"] = json!((end + 1 - from).to_string());
+                    }
+                    page
+                }
+                method => panic!("unexpected method {method}"),
+            };
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        }))
+    }
+
+    #[tokio::test]
+    async fn splits_ranges_and_follows_continuation_tokens() {
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let client = node(queries.clone());
+        let filter = EventFilter {
+            address: Some(Felt::from(0x49u64)),
+            ..Default::default()
+        };
+        let events: Vec<EmittedEvent> = client
+            .events_stream(filter)
+            .chunk_size(8)
+            .block_range(10)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        let block_numbers: Vec<u64> = events.iter().filter_map(|e| e.block_number).collect();
+        assert_eq!(block_numbers, (0..=25).collect::<Vec<_>>());
+        assert!(events.iter().all(|e| e.from_address == Felt::from(0x49u64)));
+
+        let queries = queries.lock().unwrap();
+        // One rejected page, then 3 + 3 + 2 pages over blocks 0-9, 10-19 and 20-25.
+        assert_eq!(queries.len(), 9);
+        assert_eq!(queries[0]["chunk_size"], 8);
+        assert_eq!(queries[1]["chunk_size"], 4);
+        assert_eq!(queries[1]["from_block"], json!({"block_number": 0}));
+        assert_eq!(queries[1]["to_block"], json!({"block_number": 9}));
+        assert_eq!(queries[2]["continuation_token"], "4");
+        assert_eq!(queries[7]["from_block"], json!({"block_number": 20}));
+        assert_eq!(queries[8]["to_block"], json!({"block_number": 25}));
+    }
+}
@@ -1,2 +1,12 @@
 
-pub mod rpc;
\ No newline at end of file
+pub mod events;
+pub mod messaging;
+pub mod middleware;
+pub mod pending;
+pub mod provider;
+pub mod rpc;
+pub mod subscription;
+pub mod transport;
+pub mod watch;
+
+pub use provider::Provider;
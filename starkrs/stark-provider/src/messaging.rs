@@ -0,0 +1,354 @@
+//! L1 side of Starknet messaging: bindings to the StarknetCore contract on Ethereum, to send
+//! messages to L2 contracts and consume messages sent by them.
+
+use ethers::contract::{abigen, ContractError};
+use ethers::providers::Middleware;
+use ethers::types::{Address, TransactionReceipt, H256, U256};
+use stark_core::types::message::MsgFromL1;
+use stark_core::types::receipt::MsgToL1;
+use stark_core::Felt;
+use std::fmt;
+use std::sync::Arc;
+
+/// StarknetCore on Ethereum mainnet.
+pub const MAINNET_CORE_ADDRESS: &str = "0xc662c410C0ECf747543f5bA90660f6ABeBD9C8c4";
+/// StarknetCore on Sepolia.
+pub const SEPOLIA_CORE_ADDRESS: &str = "0xE2Bb56ee936fd6433DC0F6e7e3b8365C906AA057";
+
+abigen!(
+    StarknetMessaging,
+    r#"[
+        function sendMessageToL2(uint256 toAddress, uint256 selector, uint256[] payload) external payable returns (bytes32, uint256)
+        function consumeMessageFromL2(uint256 fromAddress, uint256[] payload) external returns (bytes32)
+        function l1ToL2Messages(bytes32 msgHash) external view returns (uint256)
+        function l2ToL1Messages(bytes32 msgHash) external view returns (uint256)
+        function l1ToL2MessageNonce() external view returns (uint256)
+        event LogMessageToL2(address indexed fromAddress, uint256 indexed toAddress, uint256 indexed selector, uint256[] payload, uint256 nonce, uint256 fee)
+        event ConsumedMessageToL1(uint256 indexed fromAddress, address indexed toAddress, uint256[] payload)
+    ]"#
+);
+
+#[derive(Debug)]
+pub enum MessagingError<M: Middleware> {
+    Contract(ContractError<M>),
+    /// The L1 transaction left the mempool without being mined.
+    Dropped(H256),
+    /// The mined L1 transaction did not log the message it was expected to send.
+    MissingLog(H256),
+}
+
+impl<M: Middleware> fmt::Display for MessagingError<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessagingError::Contract(err) => write!(f, "StarknetCore call failed: {err}"),
+            MessagingError::Dropped(hash) => write!(f, "L1 transaction {hash:?} was dropped"),
+            MessagingError::MissingLog(hash) => {
+                write!(f, "L1 transaction {hash:?} did not log a message to L2")
+            }
+        }
+    }
+}
+
+impl<M: Middleware> std::error::Error for MessagingError<M> {}
+
+impl<M: Middleware> From<ContractError<M>> for MessagingError<M> {
+    fn from(err: ContractError<M>) -> Self {
+        MessagingError::Contract(err)
+    }
+}
+
+/// A message sent to L2, as logged by StarknetCore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentMessage {
+    pub message: MsgFromL1,
+    pub nonce: Felt,
+    /// The fee paid for the L1 handler transaction, in wei.
+    pub fee: U256,
+    pub l1_transaction_hash: H256,
+}
+
+impl SentMessage {
+    /// The key of the message in `l1ToL2Messages`.
+    pub fn hash(&self) -> H256 {
+        self.message.hash(self.nonce)
+    }
+
+    /// The hash of the L1 handler transaction that will deliver the message on the chain with
+    /// `chain_id`.
+    pub fn l2_transaction_hash(&self, chain_id: Felt) -> Felt {
+        self.message
+            .l1_handler_transaction(self.nonce)
+            .transaction_hash(chain_id)
+    }
+}
+
+/// The messaging functions of a StarknetCore contract, called through `client`.
+#[derive(Debug)]
+pub struct StarknetCore<M> {
+    contract: StarknetMessaging<M>,
+}
+
+impl<M> Clone for StarknetCore<M> {
+    fn clone(&self) -> Self {
+        StarknetCore {
+            contract: self.contract.clone(),
+        }
+    }
+}
+
+impl<M: Middleware> StarknetCore<M> {
+    pub fn new(address: Address, client: Arc<M>) -> Self {
+        StarknetCore {
+            contract: StarknetMessaging::new(address, client),
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.contract.address()
+    }
+
+    /// Sends a message to the `#[l1_handler]` `selector` of `to_address`, paying `fee` wei for
+    /// its delivery, and waits for the L1 transaction to be mined.
+    pub async fn send_message_to_l2(
+        &self,
+        to_address: Felt,
+        selector: Felt,
+        payload: Vec<Felt>,
+        fee: U256,
+    ) -> Result<SentMessage, MessagingError<M>> {
+        let call = self
+            .contract
+            .send_message_to_l2(
+                to_uint(to_address),
+                to_uint(selector),
+                payload.iter().copied().map(to_uint).collect(),
+            )
+            .value(fee);
+        let pending = call.send().await?;
+        let hash = pending.tx_hash();
+        let receipt = pending
+            .await
+            .map_err(ContractError::from)?
+            .ok_or(MessagingError::Dropped(hash))?;
+        let log = self
+            .message_log(&receipt)
+            .ok_or(MessagingError::MissingLog(hash))?;
+        Ok(SentMessage {
+            message: MsgFromL1 {
+                from_address: log.from_address,
+                to_address,
+                entry_point_selector: selector,
+                payload,
+            },
+            nonce: to_felt(log.nonce).ok_or(MessagingError::MissingLog(hash))?,
+            fee: log.fee,
+            l1_transaction_hash: hash,
+        })
+    }
+
+    /// The `LogMessageToL2` event this contract emitted in `receipt`.
+    fn message_log(&self, receipt: &TransactionReceipt) -> Option<LogMessageToL2Filter> {
+        receipt
+            .logs
+            .iter()
+            .filter(|log| log.address == self.address())
+            .find_map(|log| {
+                self.contract
+                    .decode_event("LogMessageToL2", log.topics.clone(), log.data.clone())
+                    .ok()
+            })
+    }
+
+    /// The fee plus one of a message to L2 that has not been delivered yet; zero once it is
+    /// consumed on L2 or was never sent.
+    pub async fn l1_to_l2_messages(&self, hash: H256) -> Result<U256, MessagingError<M>> {
+        Ok(self.contract.l_1_to_l2_messages(hash.0).call().await?)
+    }
+
+    /// How many copies of a message from L2 are proven on L1 and can be consumed.
+    pub async fn l2_to_l1_messages(&self, hash: H256) -> Result<U256, MessagingError<M>> {
+        Ok(self.contract.l_2_to_l1_messages(hash.0).call().await?)
+    }
+
+    /// The nonce the next message to L2 will be sent with.
+    pub async fn l1_to_l2_message_nonce(&self) -> Result<U256, MessagingError<M>> {
+        Ok(self.contract.l_1_to_l2_message_nonce().call().await?)
+    }
+
+    /// Consumes a message from L2. StarknetCore only lets the message's `to_address` consume it,
+    /// so the client must send from that address.
+    pub async fn consume_message_from_l2(
+        &self,
+        message: &MsgToL1,
+    ) -> Result<TransactionReceipt, MessagingError<M>> {
+        let call = self.contract.consume_message_from_l2(
+            to_uint(message.from_address),
+            message.payload.iter().copied().map(to_uint).collect(),
+        );
+        let pending = call.send().await?;
+        let hash = pending.tx_hash();
+        pending
+            .await
+            .map_err(ContractError::from)?
+            .ok_or(MessagingError::Dropped(hash))
+    }
+}
+
+fn to_uint(felt: Felt) -> U256 {
+    U256::from_big_endian(&felt.to_bytes_be())
+}
+
+fn to_felt(value: U256) -> Option<Felt> {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    Felt::from_bytes_be(&bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::{encode, Token};
+    use ethers::providers::{MockProvider, Provider};
+    use ethers::types::{Bytes, Log};
+    use ethers::utils::keccak256;
+
+    fn core() -> (StarknetCore<Provider<MockProvider>>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        let address = SEPOLIA_CORE_ADDRESS.parse().unwrap();
+        (StarknetCore::new(address, Arc::new(provider)), mock)
+    }
+
+    #[tokio::test]
+    async fn reads_message_counters() {
+        let (core, mock) = core();
+        mock.push::<Bytes, _>(Bytes::from(encode(&[Token::Uint(U256::from(2))])))
+            .unwrap();
+        assert_eq!(
+            core.l2_to_l1_messages(H256::repeat_byte(1)).await.unwrap(),
+            U256::from(2)
+        );
+    }
+
+    #[test]
+    fn finds_the_message_log_of_the_core_contract() {
+        let (core, _) = core();
+        let sender: Address = "0xbe3c44c09bc1a3566f3e1ca12e5aba0fa4ca72be"
+            .parse()
+            .unwrap();
+        let topics = vec![
+            H256(keccak256(
+                "LogMessageToL2(address,uint256,uint256,uint256[],uint256,uint256)",
+            )),
+            H256::from(sender),
+            H256::from_low_u64_be(0x10),
+            H256::from_low_u64_be(0x20),
+        ];
+        let data = encode(&[
+            Token::Array(vec![Token::Uint(U256::one())]),
+            Token::Uint(U256::from(7)),
+            Token::Uint(U256::from(1000)),
+        ]);
+        let log = |address| Log {
+            address,
+            topics: topics.clone(),
+            data: data.clone().into(),
+            ..Log::default()
+        };
+        let receipt = |logs| TransactionReceipt {
+            logs,
+            ..TransactionReceipt::default()
+        };
+
+        let found = core
+            .message_log(&receipt(vec![log(core.address())]))
+            .unwrap();
+        assert_eq!(found.from_address, sender);
+        assert_eq!(found.selector, U256::from(0x20));
+        assert_eq!(found.nonce, U256::from(7));
+        assert_eq!(found.fee, U256::from(1000));
+        // The same event from another contract does not count.
+        assert!(core
+            .message_log(&receipt(vec![log(Address::repeat_byte(9))]))
+            .is_none());
+    }
+
+    /// Runs the bindings against the contract in `test-data/l1` on a local anvil node.
+    #[tokio::test]
+    #[ignore = "needs anvil and solc on the PATH"]
+    async fn exchanges_messages_on_anvil() {
+        use ethers::contract::ContractFactory;
+        use ethers::middleware::SignerMiddleware;
+        use ethers::signers::{LocalWallet, Signer};
+        use ethers::solc::Solc;
+        use ethers::utils::Anvil;
+        use std::path::Path;
+
+        abigen!(
+            MockStarknetMessaging,
+            r#"[function mockSendMessageFromL2(uint256 fromAddress, uint256 toAddress, uint256[] payload) external]"#
+        );
+
+        let anvil = Anvil::new().spawn();
+        let wallet: LocalWallet = anvil.keys()[0].clone().into();
+        let provider = Provider::try_from(anvil.endpoint()).unwrap();
+        let client = Arc::new(SignerMiddleware::new(
+            provider,
+            wallet.with_chain_id(anvil.chain_id()),
+        ));
+
+        let source =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/l1/MockStarknetMessaging.sol");
+        let compiled = Solc::default().compile_source(source).unwrap();
+        let (abi, bytecode, _) = compiled
+            .find("MockStarknetMessaging")
+            .unwrap()
+            .into_parts_or_default();
+        let factory = ContractFactory::new(abi, bytecode, client.clone());
+        let address = factory.deploy(()).unwrap().send().await.unwrap().address();
+        let core = StarknetCore::new(address, client.clone());
+
+        let l2_contract = Felt::from(0x1234u64);
+        let sent = core
+            .send_message_to_l2(
+                l2_contract,
+                Felt::from(0x5678u64),
+                vec![Felt::ONE],
+                1000.into(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(sent.nonce, Felt::ZERO);
+        assert_eq!(sent.message.from_address, client.address());
+        assert_eq!(
+            core.l1_to_l2_messages(sent.hash()).await.unwrap(),
+            U256::from(1001)
+        );
+        assert_eq!(core.l1_to_l2_message_nonce().await.unwrap(), U256::one());
+
+        let message = MsgToL1 {
+            from_address: l2_contract,
+            to_address: Felt::from_bytes_be(client.address().as_bytes()).unwrap(),
+            payload: vec![Felt::TWO],
+        };
+        MockStarknetMessaging::new(address, client.clone())
+            .mock_send_message_from_l2(
+                to_uint(message.from_address),
+                to_uint(message.to_address),
+                vec![U256::from(2)],
+            )
+            .send()
+            .await
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(
+            core.l2_to_l1_messages(message.hash()).await.unwrap(),
+            U256::one()
+        );
+        core.consume_message_from_l2(&message).await.unwrap();
+        assert_eq!(
+            core.l2_to_l1_messages(message.hash()).await.unwrap(),
+            U256::zero()
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use super::is_write_method;
+use crate::rpc::ProviderError;
+use crate::transport::Transport;
+use async_trait::async_trait;
+use ethers::utils::keccak256;
+use lru::LruCache;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Methods whose successful result never changes.
+const IMMUTABLE_METHODS: &[&str] = &[
+    "starknet_chainId",
+    "starknet_specVersion",
+    "starknet_getTransactionByHash",
+    "starknet_getClass",
+    "starknet_getCompiledCasm",
+];
+
+/// Methods whose result is fixed once the block they read is pinned by hash.
+const BLOCK_SCOPED_METHODS: &[&str] = &[
+    "starknet_getBlockWithTxHashes",
+    "starknet_getBlockWithTxs",
+    "starknet_getBlockWithReceipts",
+    "starknet_getStateUpdate",
+    "starknet_getBlockTransactionCount",
+    "starknet_getTransactionByBlockIdAndIndex",
+    "starknet_getStorageAt",
+    "starknet_getNonce",
+    "starknet_getClassAt",
+    "starknet_getClassHashAt",
+    "starknet_call",
+    "starknet_estimateMessageFee",
+    "starknet_getStorageProof",
+    "starknet_traceBlockTransactions",
+];
+
+/// Caches responses that can never change and coalesces identical concurrent requests.
+///
+/// Cached are: the chain id and spec version, transactions and classes by hash, receipts of
+/// transactions accepted on L1, and block-scoped reads whose block is given by hash. Anything
+/// addressed by block number or tag, errors, batches and writes always go to the node.
+/// Results live in a bounded LRU in memory and, optionally, as files in a directory that
+/// survives restarts.
+///
+/// Identical single reads in flight at the same time share one request to the node, whether
+/// cacheable or not.
+pub struct CacheTransport<T> {
+    inner: T,
+    memory: Mutex<LruCache<String, Value>>,
+    directory: Option<PathBuf>,
+    in_flight: Mutex<HashMap<String, broadcast::Sender<Option<Value>>>>,
+}
+
+impl<T: Transport> CacheTransport<T> {
+    /// Keeps up to `capacity` results in memory.
+    pub fn new(inner: T, capacity: usize) -> Self {
+        Self {
+            inner,
+            memory: Mutex::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
+            )),
+            directory: None,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Also stores results as files in `directory`, which must exist.
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    async fn lookup(&self, key: &str) -> Option<Value> {
+        if let Some(result) = self.memory.lock().unwrap().get(key) {
+            return Some(result.clone());
+        }
+        let path = self.path(key)?;
+        let data = tokio::fs::read(path).await.ok()?;
+        let result: Value = serde_json::from_slice(&data).ok()?;
+        self.memory
+            .lock()
+            .unwrap()
+            .put(key.to_string(), result.clone());
+        Some(result)
+    }
+
+    async fn store(&self, key: &str, result: &Value) {
+        self.memory
+            .lock()
+            .unwrap()
+            .put(key.to_string(), result.clone());
+        if let Some(path) = self.path(key) {
+            // Write then rename so that readers never see a partial file. The disk store is
+            // best effort: failures leave the in-memory entry in place.
+            let partial = path.with_extension("partial");
+            if tokio::fs::write(&partial, result.to_string()).await.is_ok() {
+                let _ = tokio::fs::rename(&partial, &path).await;
+            }
+        }
+    }
+
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        let directory = self.directory.as_ref()?;
+        Some(directory.join(format!("{}.json", hex::encode(keccak256(key.as_bytes())))))
+    }
+
+    /// Sends `request` once for all concurrent callers with the same `key`. Returns the response
+    /// without its id.
+    async fn coalesced(&self, key: &str, request: Value) -> Result<Value, ProviderError> {
+        let follower = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(key) {
+                Some(sender) => Some(sender.subscribe()),
+                None => {
+                    in_flight.insert(key.to_string(), broadcast::channel(1).0);
+                    None
+                }
+            }
+        };
+        if let Some(mut receiver) = follower {
+            if let Ok(Some(response)) = receiver.recv().await {
+                return Ok(response);
+            }
+            // The shared request failed or was cancelled; try on our own to get our own result.
+            return self.inner.send(request).await;
+        }
+
+        let mut leader = Leader {
+            in_flight: &self.in_flight,
+            key,
+            response: None,
+        };
+        let result = self.inner.send(request).await;
+        leader.response = result.as_ref().ok().cloned();
+        result
+    }
+}
+
+/// Owns a request in flight. Dropping it, whether the request completed or the leader was
+/// cancelled, unregisters the request and hands its response, if any, to the followers.
+struct Leader<'a> {
+    in_flight: &'a Mutex<HashMap<String, broadcast::Sender<Option<Value>>>>,
+    key: &'a str,
+    response: Option<Value>,
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        let sender = self.in_flight.lock().unwrap().remove(self.key);
+        if let Some(sender) = sender {
+            let _ = sender.send(self.response.take());
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for CacheTransport<T> {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError> {
+        let method = match request.get("method").and_then(Value::as_str) {
+            Some(method) if !is_write_method(method) => method.to_string(),
+            _ => return self.inner.send(request).await,
+        };
+
+        let id = request["id"].clone();
+        let key = format!("{method}:{}", request["params"]);
+        let cacheable = is_cacheable_request(&method, &request["params"]);
+        if cacheable {
+            if let Some(result) = self.lookup(&key).await {
+                return Ok(json!({"jsonrpc": "2.0", "id": id, "result": result}));
+            }
+        }
+
+        let mut response = self.coalesced(&key, request).await?;
+        if cacheable && response.get("error").is_none() {
+            if let Some(result) = response.get("result") {
+                if is_cacheable_result(&method, result) {
+                    self.store(&key, result).await;
+                }
+            }
+        }
+        if let Some(response) = response.as_object_mut() {
+            response.insert("id".to_string(), id);
+        }
+        Ok(response)
+    }
+}
+
+fn is_cacheable_request(method: &str, params: &Value) -> bool {
+    if IMMUTABLE_METHODS.contains(&method) || method == "starknet_getTransactionReceipt" {
+        return true;
+    }
+    if !BLOCK_SCOPED_METHODS.contains(&method) {
+        return false;
+    }
+    let params: Vec<&Value> = match params {
+        Value::Array(params) => params.iter().collect(),
+        Value::Object(params) => params.values().collect(),
+        _ => Vec::new(),
+    };
+    params
+        .into_iter()
+        .any(|param| param.get("block_hash").is_some())
+}
+
+fn is_cacheable_result(method: &str, result: &Value) -> bool {
+    match method {
+        "starknet_getTransactionReceipt" => result["finality_status"] == "ACCEPTED_ON_L1",
+        _ => !result.is_null(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Answers every call with its own params and counts the calls.
+    fn counting() -> (MemoryTransport, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let transport = MemoryTransport::with_handler(move |request| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let result = match request["method"].as_str() {
+                Some("starknet_getTransactionReceipt") => {
+                    json!({"finality_status": request["params"][1]})
+                }
+                _ => request["params"].clone(),
+            };
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        });
+        (transport, calls)
+    }
+
+    fn call(id: u64, method: &str, params: Value) -> Value {
+        json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+    }
+
+    #[tokio::test]
+    async fn caches_only_immutable_reads() {
+        let (transport, calls) = counting();
+        let cache = CacheTransport::new(transport, 16);
+
+        let by_hash = json!([{"block_hash": "0x1"}]);
+        let by_tag = json!(["latest"]);
+        for id in 0..3 {
+            let response = cache
+                .send(call(id, "starknet_getBlockWithTxs", by_hash.clone()))
+                .await
+                .unwrap();
+            assert_eq!(response["id"], id);
+            cache
+                .send(call(id, "starknet_getBlockWithTxs", by_tag.clone()))
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn caches_receipts_once_accepted_on_l1() {
+        let (transport, calls) = counting();
+        let cache = CacheTransport::new(transport, 16);
+        for _ in 0..2 {
+            cache
+                .send(call(
+                    1,
+                    "starknet_getTransactionReceipt",
+                    json!(["0x1", "ACCEPTED_ON_L2"]),
+                ))
+                .await
+                .unwrap();
+            cache
+                .send(call(
+                    1,
+                    "starknet_getTransactionReceipt",
+                    json!(["0x2", "ACCEPTED_ON_L1"]),
+                ))
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn persists_to_disk() {
+        let directory = tempfile::tempdir().unwrap();
+        let request = call(1, "starknet_getClass", json!(["latest", "0x5"]));
+
+        let (transport, calls) = counting();
+        let cache = CacheTransport::new(transport, 16).with_directory(directory.path());
+        cache.send(request.clone()).await.unwrap();
+
+        let (transport, restarted_calls) = counting();
+        let cache = CacheTransport::new(transport, 16).with_directory(directory.path());
+        let response = cache.send(request).await.unwrap();
+        assert_eq!(response["result"], json!(["latest", "0x5"]));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(restarted_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn coalesces_concurrent_requests() {
+        let (transport, mut server) = MemoryTransport::channel();
+        let cache = Arc::new(CacheTransport::new(transport, 16));
+
+        let requests: Vec<_> = (0..3)
+            .map(|id| {
+                let cache = cache.clone();
+                tokio::spawn(async move {
+                    cache
+                        .send(call(id, "starknet_blockNumber", json!([])))
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect();
+        let incoming = server.next_request().await.unwrap();
+        // Let the other callers join the request in flight before answering.
+        tokio::task::yield_now().await;
+        incoming.respond_result(json!(95812));
+
+        for (id, request) in requests.into_iter().enumerate() {
+            let response = request.await.unwrap();
+            assert_eq!(response["id"], id);
+            assert_eq!(response["result"], 95812);
+        }
+        drop(cache);
+        assert!(server.next_request().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn followers_finish_when_the_leader_is_cancelled() {
+        let (transport, mut server) = MemoryTransport::channel();
+        let cache = Arc::new(CacheTransport::new(transport, 16));
+        let send = |id| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                cache
+                    .send(call(id, "starknet_blockNumber", json!([])))
+                    .await
+            })
+        };
+
+        let leader = send(0);
+        let abandoned = server.next_request().await.unwrap();
+        let follower = send(1);
+        tokio::task::yield_now().await;
+        leader.abort();
+        assert!(leader.await.unwrap_err().is_cancelled());
+
+        // The follower retries on its own instead of waiting for the cancelled request.
+        let retried = tokio::time::timeout(Duration::from_secs(5), server.next_request());
+        retried.await.unwrap().unwrap().respond_result(json!(95812));
+        assert_eq!(follower.await.unwrap().unwrap()["result"], 95812);
+        drop(abandoned);
+
+        // Later callers do not join the cancelled request either.
+        let later = send(2);
+        server
+            .next_request()
+            .await
+            .unwrap()
+            .respond_result(json!(95813));
+        assert_eq!(later.await.unwrap().unwrap()["result"], 95813);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::rpc::{parse_response, ProviderError};
+use crate::transport::Transport;
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Sends each request to the first healthy endpoint, failing over to the next one on transport
+/// errors.
+///
+/// An endpoint that fails is skipped for a cooldown. Before routing a request, once every health
+/// interval, [`FallbackTransport::check_health`] compares `starknet_blockNumber` across
+/// endpoints and also skips those lagging behind the highest by more than the allowed lag until
+/// the next check. When every endpoint is skipped, all are tried in order. A JSON-RPC error is
+/// returned without failing over, since another endpoint would reject the same request.
+pub struct FallbackTransport<T> {
+    endpoints: Vec<T>,
+    health: Mutex<Vec<Health>>,
+    cooldown: Duration,
+    max_lag: u64,
+    health_interval: Duration,
+    /// When the last health check started, `None` before the first one.
+    last_check: Mutex<Option<Instant>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Health {
+    failed_until: Option<Instant>,
+    lagging: bool,
+}
+
+impl Health {
+    fn is_usable(&self, now: Instant) -> bool {
+        !self.lagging && self.failed_until.is_none_or(|until| until <= now)
+    }
+}
+
+impl<T: Transport> FallbackTransport<T> {
+    /// Tries `endpoints` in the given order.
+    pub fn new(endpoints: Vec<T>) -> Self {
+        assert!(!endpoints.is_empty(), "at least one endpoint is required");
+        Self {
+            health: Mutex::new(vec![Health::default(); endpoints.len()]),
+            endpoints,
+            cooldown: Duration::from_secs(30),
+            max_lag: 2,
+            health_interval: Duration::from_secs(30),
+            last_check: Mutex::new(None),
+        }
+    }
+
+    /// How long a failed endpoint is skipped.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// How many blocks an endpoint may trail the highest one before it is skipped.
+    pub fn with_max_lag(mut self, max_lag: u64) -> Self {
+        self.max_lag = max_lag;
+        self
+    }
+
+    /// How often endpoints are checked for lag, see [`FallbackTransport::check_health`].
+    pub fn with_health_interval(mut self, health_interval: Duration) -> Self {
+        self.health_interval = health_interval;
+        self
+    }
+
+    pub fn endpoints(&self) -> &[T] {
+        &self.endpoints
+    }
+
+    /// Queries the block number of every endpoint and marks those that fail or lag behind.
+    /// Returns the block number each endpoint reported.
+    pub async fn check_health(&self) -> Vec<Option<u64>> {
+        let request =
+            json!({"jsonrpc": "2.0", "id": 0, "method": "starknet_blockNumber", "params": []});
+        let block_numbers: Vec<Option<u64>> =
+            join_all(self.endpoints.iter().map(|endpoint| async {
+                let response = endpoint.send(request.clone()).await.ok()?;
+                parse_response::<u64>(response).ok()
+            }))
+            .await;
+
+        let highest = block_numbers.iter().flatten().max().copied();
+        let now = Instant::now();
+        let mut health = self.health.lock().unwrap();
+        for (health, block_number) in health.iter_mut().zip(&block_numbers) {
+            match (block_number, highest) {
+                (Some(block_number), Some(highest)) => {
+                    health.lagging = highest - block_number > self.max_lag;
+                    health.failed_until = None;
+                }
+                _ => health.failed_until = Some(now + self.cooldown),
+            }
+        }
+        block_numbers
+    }
+
+    /// Whether a health check is due, claiming it for the caller if so.
+    fn health_check_due(&self) -> bool {
+        let now = Instant::now();
+        let mut last_check = self.last_check.lock().unwrap();
+        if last_check.is_some_and(|last| now < last + self.health_interval) {
+            return false;
+        }
+        *last_check = Some(now);
+        true
+    }
+
+    /// Endpoint indices in the order to try them: usable ones first.
+    fn order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let health = self.health.lock().unwrap();
+        let (mut usable, skipped): (Vec<usize>, Vec<usize>) =
+            (0..self.endpoints.len()).partition(|index| health[*index].is_usable(now));
+        usable.extend(skipped);
+        usable
+    }
+
+    fn mark_failed(&self, index: usize) {
+        self.health.lock().unwrap()[index].failed_until = Some(Instant::now() + self.cooldown);
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for FallbackTransport<T> {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError> {
+        if self.health_check_due() {
+            self.check_health().await;
+        }
+        let mut last_error = None;
+        for index in self.order() {
+            match self.endpoints[index].send(request.clone()).await {
+                Err(ProviderError::Transport(err)) => {
+                    self.mark_failed(index);
+                    last_error = Some(ProviderError::Transport(err));
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    /// Answers `starknet_blockNumber` with `block_number` and anything else with `name`.
+    fn endpoint(name: &'static str, block_number: u64) -> MemoryTransport {
+        MemoryTransport::with_handler(move |request| {
+            let result = match request["method"].as_str() {
+                Some("starknet_blockNumber") => json!(block_number),
+                _ => json!(name),
+            };
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        })
+    }
+
+    fn down() -> MemoryTransport {
+        MemoryTransport::channel().0
+    }
+
+    async fn answer(transport: &FallbackTransport<MemoryTransport>) -> Value {
+        let call = json!({"jsonrpc": "2.0", "id": 1, "method": "starknet_chainId", "params": []});
+        transport.send(call).await.unwrap()["result"].clone()
+    }
+
+    #[tokio::test]
+    async fn fails_over_and_skips_failed_endpoints() {
+        let transport = FallbackTransport::new(vec![down(), endpoint("b", 10), endpoint("c", 10)]);
+        assert_eq!(answer(&transport).await, "b");
+        assert_eq!(transport.order(), vec![1, 2, 0]);
+    }
+
+    #[tokio::test]
+    async fn skips_lagging_endpoints() {
+        let transport =
+            FallbackTransport::new(vec![endpoint("a", 90), endpoint("b", 100)]).with_max_lag(5);
+        assert_eq!(answer(&transport).await, "b");
+        assert_eq!(transport.check_health().await, vec![Some(90), Some(100)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rechecks_health_every_interval() {
+        let block_number = Arc::new(AtomicU64::new(100));
+        let lagging = MemoryTransport::with_handler({
+            let block_number = block_number.clone();
+            move |request| {
+                let result = match request["method"].as_str() {
+                    Some("starknet_blockNumber") => json!(block_number.load(Ordering::SeqCst)),
+                    _ => json!("a"),
+                };
+                json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+            }
+        });
+        let transport = FallbackTransport::new(vec![lagging, endpoint("b", 100)])
+            .with_health_interval(Duration::from_secs(60));
+        assert_eq!(answer(&transport).await, "a");
+
+        block_number.store(90, Ordering::SeqCst);
+        assert_eq!(answer(&transport).await, "a");
+        tokio::time::advance(Duration::from_secs(61)).await;
+        assert_eq!(answer(&transport).await, "b");
+    }
+
+    #[tokio::test]
+    async fn returns_the_last_error_when_all_fail() {
+        let transport = FallbackTransport::new(vec![down(), down()]);
+        let call = json!({"jsonrpc": "2.0", "id": 1, "method": "starknet_chainId", "params": []});
+        assert!(matches!(
+            transport.send(call).await,
+            Err(ProviderError::Transport(_))
+        ));
+    }
+}
@@ -0,0 +1,32 @@
+//! [`Transport`](crate::transport::Transport) wrappers that add behaviour around any transport.
+
+mod cache;
+mod fallback;
+mod quorum;
+mod rate_limit;
+mod retry;
+
+pub use cache::CacheTransport;
+pub use fallback::FallbackTransport;
+pub use quorum::QuorumTransport;
+pub use rate_limit::{RateLimitMetrics, RateLimitTransport};
+pub use retry::{RetryPolicy, RetryTransport};
+
+use serde_json::Value;
+
+/// The methods called by a request body, a single call or a batch array.
+pub(crate) fn methods(request: &Value) -> Vec<&str> {
+    match request {
+        Value::Array(calls) => calls.iter().filter_map(method).collect(),
+        call => method(call).into_iter().collect(),
+    }
+}
+
+fn method(call: &Value) -> Option<&str> {
+    call.get("method")?.as_str()
+}
+
+/// Whether `method` changes chain state, i.e. is one of the `starknet_add*Transaction` methods.
+pub(crate) fn is_write_method(method: &str) -> bool {
+    method.starts_with("starknet_add")
+}
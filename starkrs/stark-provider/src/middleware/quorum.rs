@@ -0,0 +1,165 @@
+use crate::rpc::ProviderError;
+use crate::transport::{Transport, TransportError};
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde_json::{json, Value};
+
+/// Sends each request to every endpoint and returns a response only once `required` of them
+/// returned the same one, so that a single misbehaving node cannot feed us wrong data.
+///
+/// Responses are compared on their `result` or `error`. Endpoints that fail count as
+/// disagreeing, and the request fails as soon as the quorum can no longer be reached.
+pub struct QuorumTransport<T> {
+    endpoints: Vec<T>,
+    required: usize,
+}
+
+impl<T: Transport> QuorumTransport<T> {
+    pub fn new(endpoints: Vec<T>, required: usize) -> Self {
+        assert!(
+            required > 0 && required <= endpoints.len(),
+            "quorum must be between 1 and the number of endpoints"
+        );
+        Self {
+            endpoints,
+            required,
+        }
+    }
+
+    pub fn endpoints(&self) -> &[T] {
+        &self.endpoints
+    }
+
+    pub fn required(&self) -> usize {
+        self.required
+    }
+}
+
+/// The part of a response endpoints have to agree on. Batch responses may come back in any
+/// order, so their entries are compared by `id`.
+fn outcome(response: &Value) -> Value {
+    match response {
+        Value::Array(responses) => {
+            let mut outcomes: Vec<(String, Value)> = responses
+                .iter()
+                .map(|response| (response["id"].to_string(), outcome(response)))
+                .collect();
+            outcomes.sort_by(|(a, _), (b, _)| a.cmp(b));
+            outcomes
+                .into_iter()
+                .map(|(id, outcome)| json!([id, outcome]))
+                .collect()
+        }
+        response => Value::Array(vec![
+            response.get("result").cloned().unwrap_or(Value::Null),
+            response.get("error").cloned().unwrap_or(Value::Null),
+        ]),
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for QuorumTransport<T> {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError> {
+        let mut pending: FuturesUnordered<_> = self
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.send(request.clone()))
+            .collect();
+
+        // Distinct outcomes with the first response carrying each and how many agree on it.
+        let mut tallies: Vec<(Value, Value, usize)> = Vec::new();
+        let mut remaining = self.endpoints.len();
+        while let Some(result) = pending.next().await {
+            remaining -= 1;
+            if let Ok(response) = result {
+                let key = outcome(&response);
+                match tallies.iter_mut().find(|(outcome, ..)| *outcome == key) {
+                    Some((_, _, count)) => *count += 1,
+                    None => tallies.push((key, response, 1)),
+                }
+                if let Some((_, response, _)) =
+                    tallies.iter().find(|(.., count)| *count >= self.required)
+                {
+                    return Ok(response.clone());
+                }
+            }
+
+            let best = tallies.iter().map(|(.., count)| *count).max().unwrap_or(0);
+            if best + remaining < self.required {
+                break;
+            }
+        }
+
+        Err(TransportError::NoQuorum {
+            required: self.required,
+            agreeing: tallies.iter().map(|(.., count)| *count).max().unwrap_or(0),
+        }
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use crate::rpc::JsonRpcClient;
+    use crate::transport::MemoryTransport;
+
+    fn endpoint(block_number: u64) -> MemoryTransport {
+        MemoryTransport::with_handler(
+            move |request| json!({"jsonrpc": "2.0", "id": request["id"], "result": block_number}),
+        )
+    }
+
+    fn down() -> MemoryTransport {
+        MemoryTransport::channel().0
+    }
+
+    #[tokio::test]
+    async fn returns_the_agreed_response() {
+        let client = JsonRpcClient::new(QuorumTransport::new(
+            vec![endpoint(100), endpoint(666), down(), endpoint(100)],
+            2,
+        ));
+        assert_eq!(client.stark_block_number().await.unwrap(), 100);
+    }
+
+    #[tokio::test]
+    async fn fails_without_quorum() {
+        let client = JsonRpcClient::new(QuorumTransport::new(
+            vec![endpoint(100), endpoint(666), down()],
+            2,
+        ));
+        assert!(matches!(
+            client.stark_block_number().await,
+            Err(ProviderError::Transport(TransportError::NoQuorum {
+                required: 2,
+                agreeing: 1
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn compares_batches_regardless_of_order() {
+        let batch = |reversed: bool| {
+            MemoryTransport::with_handler(move |request| {
+                let mut responses: Vec<Value> = request
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|call| json!({"jsonrpc": "2.0", "id": call["id"], "result": call["id"]}))
+                    .collect();
+                if reversed {
+                    responses.reverse();
+                }
+                Value::Array(responses)
+            })
+        };
+        let transport = QuorumTransport::new(vec![batch(false), batch(true)], 2);
+        let request = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "starknet_blockNumber", "params": []},
+            {"jsonrpc": "2.0", "id": 2, "method": "starknet_chainId", "params": []},
+        ]);
+        assert!(transport.send(request).await.is_ok());
+    }
+}
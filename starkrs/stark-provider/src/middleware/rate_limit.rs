@@ -0,0 +1,189 @@
+use super::methods;
+use crate::rpc::ProviderError;
+use crate::transport::Transport;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Counters of a [`RateLimitTransport`] since it was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitMetrics {
+    pub requests: u64,
+    /// Requests that had to wait for tokens.
+    pub throttled_requests: u64,
+    /// Total time requests spent waiting for tokens.
+    pub throttled_time: Duration,
+}
+
+/// Limits the request rate with a token bucket.
+///
+/// Every call costs its method's weight in tokens (1 unless set), and a batch costs the sum of
+/// its calls. The bucket holds up to `burst` tokens and refills at `requests_per_second`.
+/// Waiting callers are served in arrival order. A request costing more than `burst` waits for a
+/// full bucket and leaves it in debt.
+pub struct RateLimitTransport<T> {
+    inner: T,
+    rate: f64,
+    capacity: f64,
+    weights: HashMap<String, u32>,
+    bucket: Mutex<Bucket>,
+    requests: AtomicU64,
+    throttled_requests: AtomicU64,
+    throttled_nanos: AtomicU64,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl<T> RateLimitTransport<T> {
+    pub fn new(inner: T, requests_per_second: f64, burst: u32) -> Self {
+        assert!(requests_per_second > 0.0, "rate must be positive");
+        let capacity = f64::from(burst.max(1));
+        Self {
+            inner,
+            rate: requests_per_second,
+            capacity,
+            weights: HashMap::new(),
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                refilled_at: Instant::now(),
+            }),
+            requests: AtomicU64::new(0),
+            throttled_requests: AtomicU64::new(0),
+            throttled_nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets the tokens a call to `method` costs, e.g. more for `starknet_getEvents`.
+    pub fn with_method_weight(mut self, method: impl Into<String>, weight: u32) -> Self {
+        self.weights.insert(method.into(), weight);
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn metrics(&self) -> RateLimitMetrics {
+        RateLimitMetrics {
+            requests: self.requests.load(Ordering::Relaxed),
+            throttled_requests: self.throttled_requests.load(Ordering::Relaxed),
+            throttled_time: Duration::from_nanos(self.throttled_nanos.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn cost(&self, request: &Value) -> f64 {
+        methods(request)
+            .into_iter()
+            .map(|method| f64::from(self.weights.get(method).copied().unwrap_or(1)))
+            .sum::<f64>()
+            .max(1.0)
+    }
+
+    /// Waits until the bucket can pay `cost`, then pays it. The lock is held while waiting so
+    /// that later callers queue behind earlier ones; throttled time includes that queueing.
+    async fn acquire(&self, cost: f64) {
+        let started = Instant::now();
+        let mut bucket = self.bucket.lock().await;
+        let required = cost.min(self.capacity);
+
+        let now = Instant::now();
+        bucket.tokens = (bucket.tokens + (now - bucket.refilled_at).as_secs_f64() * self.rate)
+            .min(self.capacity);
+        bucket.refilled_at = now;
+        if bucket.tokens < required {
+            let wait = Duration::from_secs_f64((required - bucket.tokens) / self.rate);
+            tokio::time::sleep(wait).await;
+            bucket.tokens = required;
+            bucket.refilled_at = Instant::now();
+
+            let waited = started.elapsed();
+            self.throttled_requests.fetch_add(1, Ordering::Relaxed);
+            self.throttled_nanos
+                .fetch_add(waited.as_nanos() as u64, Ordering::Relaxed);
+        }
+        bucket.tokens -= cost;
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for RateLimitTransport<T> {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError> {
+        self.acquire(self.cost(&request)).await;
+        self.inner.send(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn echo() -> MemoryTransport {
+        MemoryTransport::with_handler(
+            |request| json!({"jsonrpc":"2.0","id":request["id"],"result":"0x1"}),
+        )
+    }
+
+    fn call(method: &str) -> Value {
+        json!({"jsonrpc":"2.0","id":1,"method":method,"params":[]})
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn throttles_past_the_burst() {
+        let transport = RateLimitTransport::new(echo(), 10.0, 2);
+        let started = Instant::now();
+        for _ in 0..4 {
+            transport.send(call("starknet_chainId")).await.unwrap();
+        }
+        assert_eq!(started.elapsed(), Duration::from_millis(200));
+
+        let metrics = transport.metrics();
+        assert_eq!(metrics.requests, 4);
+        assert_eq!(metrics.throttled_requests, 2);
+        assert_eq!(metrics.throttled_time, Duration::from_millis(200));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn weighs_methods_and_batches() {
+        let transport =
+            RateLimitTransport::new(echo(), 10.0, 5).with_method_weight("starknet_getEvents", 5);
+        let started = Instant::now();
+        transport.send(call("starknet_getEvents")).await.unwrap();
+        transport
+            .send(json!([call("starknet_chainId"), call("starknet_chainId")]))
+            .await
+            .unwrap();
+        assert_eq!(started.elapsed(), Duration::from_millis(200));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn serves_concurrent_callers_in_order() {
+        let transport = Arc::new(RateLimitTransport::new(echo(), 1.0, 1));
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut handles = Vec::new();
+        for i in 0..3 {
+            let transport = transport.clone();
+            let order = order.clone();
+            handles.push(tokio::spawn(async move {
+                transport.acquire(1.0).await;
+                order.lock().unwrap().push(i);
+            }));
+            tokio::task::yield_now().await;
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+        assert_eq!(transport.metrics().throttled_time, Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,305 @@
+use super::{is_write_method, methods};
+use crate::rpc::ProviderError;
+use crate::transport::{Transport, TransportError};
+use async_trait::async_trait;
+use rand::Rng;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often and how patiently to retry a failed request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; `0` disables retrying.
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Growth of the backoff after each retry.
+    pub multiplier: f64,
+    /// Random spread applied to each backoff, as a fraction of it: `0.25` waits between 75% and
+    /// 125% of the nominal delay.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.25,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn never() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// The delay before retry number `retry`, counting from zero.
+    fn backoff(&self, retry: u32) -> Duration {
+        let nominal = self.initial_backoff.as_secs_f64() * self.multiplier.powi(retry as i32);
+        let nominal = nominal.min(self.max_backoff.as_secs_f64());
+        let spread = if self.jitter > 0.0 {
+            rand::thread_rng().gen_range(-self.jitter..=self.jitter)
+        } else {
+            0.0
+        };
+        Duration::from_secs_f64((nominal * (1.0 + spread)).max(0.0))
+    }
+}
+
+/// Retries failed reads with exponential backoff.
+///
+/// Only transport failures are retried: dropped connections, timeouts, `429` and `5xx` statuses.
+/// A `Retry-After` sent by the endpoint replaces the computed backoff, capped at the policy's
+/// `max_backoff`. JSON-RPC errors are answers and are returned as they are.
+/// `starknet_add*Transaction` calls are never retried, and neither is a batch containing one.
+pub struct RetryTransport<T> {
+    inner: T,
+    policy: RetryPolicy,
+    method_policies: HashMap<String, RetryPolicy>,
+}
+
+impl<T> RetryTransport<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            policy: RetryPolicy::default(),
+            method_policies: HashMap::new(),
+        }
+    }
+
+    /// Sets the policy for methods without their own.
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets the policy for calls to `method`, e.g. `starknet_getEvents`.
+    pub fn with_method_policy(mut self, method: impl Into<String>, policy: RetryPolicy) -> Self {
+        self.method_policies.insert(method.into(), policy);
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// The policy for `request`: for a batch, the one allowing the fewest retries.
+    fn policy_for(&self, request: &Value) -> RetryPolicy {
+        let methods = methods(request);
+        if methods.iter().any(|method| is_write_method(method)) {
+            return RetryPolicy::never();
+        }
+        methods
+            .into_iter()
+            .map(|method| self.method_policies.get(method).unwrap_or(&self.policy))
+            .min_by_key(|policy| policy.max_retries)
+            .unwrap_or(&self.policy)
+            .clone()
+    }
+}
+
+fn is_retryable(err: &ProviderError) -> bool {
+    match err {
+        ProviderError::Transport(TransportError::Status { status, .. }) => {
+            *status == 408 || *status == 429 || *status >= 500
+        }
+        ProviderError::Transport(TransportError::InvalidConfig(_)) => false,
+        // Nodes that disagree keep disagreeing; asking them again only adds load.
+        ProviderError::Transport(TransportError::NoQuorum { .. }) => false,
+        ProviderError::Transport(_) => true,
+        ProviderError::Rpc(_) | ProviderError::Deserialize(_) | ProviderError::Submission(_) => {
+            false
+        }
+    }
+}
+
+fn retry_after(err: &ProviderError) -> Option<Duration> {
+    match err {
+        ProviderError::Transport(TransportError::Status { retry_after, .. }) => *retry_after,
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for RetryTransport<T> {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError> {
+        let policy = self.policy_for(&request);
+        let mut retry = 0;
+        loop {
+            match self.inner.send(request.clone()).await {
+                Err(err) if retry < policy.max_retries && is_retryable(&err) => {
+                    let delay = retry_after(&err)
+                        .map(|delay| delay.min(policy.max_backoff))
+                        .unwrap_or_else(|| policy.backoff(retry));
+                    retry += 1;
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::JsonRpcError;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+    use tokio::time::Instant;
+
+    /// Fails with the queued errors, then answers `0x1`.
+    struct Flaky {
+        errors: Mutex<Vec<ProviderError>>,
+        attempts: AtomicU32,
+    }
+
+    impl Flaky {
+        fn new(errors: Vec<ProviderError>) -> Self {
+            Self {
+                errors: Mutex::new(errors),
+                attempts: AtomicU32::new(0),
+            }
+        }
+
+        fn attempts(&self) -> u32 {
+            self.attempts.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl Transport for Flaky {
+        async fn send(&self, _request: Value) -> Result<Value, ProviderError> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            match self.errors.lock().unwrap().pop() {
+                Some(err) => Err(err),
+                None => Ok(json!({"jsonrpc":"2.0","id":1,"result":"0x1"})),
+            }
+        }
+    }
+
+    fn status(status: u16, retry_after: Option<Duration>) -> ProviderError {
+        ProviderError::Transport(TransportError::Status {
+            status,
+            retry_after,
+        })
+    }
+
+    fn call(method: &str) -> Value {
+        json!({"jsonrpc":"2.0","id":1,"method":method,"params":[]})
+    }
+
+    fn fast() -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transport_errors() {
+        let transport = RetryTransport::new(Flaky::new(vec![
+            status(503, None),
+            ProviderError::Transport(TransportError::Closed),
+            status(429, None),
+        ]))
+        .with_policy(fast());
+        assert!(transport.send(call("starknet_chainId")).await.is_ok());
+        assert_eq!(transport.inner().attempts(), 4);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let transport =
+            RetryTransport::new(Flaky::new((0..5).map(|_| status(503, None)).collect()))
+                .with_policy(fast());
+        assert!(transport.send(call("starknet_chainId")).await.is_err());
+        assert_eq!(transport.inner().attempts(), 4);
+    }
+
+    #[tokio::test]
+    async fn never_retries_writes_or_rpc_errors() {
+        let transport =
+            RetryTransport::new(Flaky::new(vec![status(503, None)])).with_policy(fast());
+        let batch = json!([
+            call("starknet_chainId"),
+            call("starknet_addInvokeTransaction")
+        ]);
+        assert!(transport.send(batch).await.is_err());
+        assert_eq!(transport.inner().attempts(), 1);
+
+        let transport = RetryTransport::new(Flaky::new(vec![ProviderError::Rpc(JsonRpcError {
+            code: 24,
+            message: "Block not found".to_string(),
+            data: None,
+        })]))
+        .with_policy(fast());
+        assert!(transport
+            .send(call("starknet_getBlockWithTxs"))
+            .await
+            .is_err());
+        assert_eq!(transport.inner().attempts(), 1);
+        let transport = RetryTransport::new(Flaky::new(vec![ProviderError::Transport(
+            TransportError::NoQuorum {
+                required: 2,
+                agreeing: 1,
+            },
+        )]))
+        .with_policy(fast());
+        assert!(transport.send(call("starknet_chainId")).await.is_err());
+        assert_eq!(transport.inner().attempts(), 1);
+    }
+
+    #[tokio::test]
+    async fn applies_method_policies() {
+        let transport = RetryTransport::new(Flaky::new(vec![status(503, None)]))
+            .with_policy(fast())
+            .with_method_policy("starknet_getEvents", RetryPolicy::never());
+        assert!(transport.send(call("starknet_getEvents")).await.is_err());
+        assert!(transport.send(call("starknet_getEvents")).await.is_ok());
+        assert_eq!(transport.inner().attempts(), 2);
+    }
+
+    #[tokio::test]
+    async fn honours_retry_after() {
+        let transport = RetryTransport::new(Flaky::new(vec![status(429, Some(Duration::ZERO))]))
+            .with_policy(RetryPolicy {
+                initial_backoff: Duration::from_secs(30),
+                ..RetryPolicy::default()
+            });
+        let started = Instant::now();
+        assert!(transport.send(call("starknet_chainId")).await.is_ok());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn caps_retry_after_at_max_backoff() {
+        let transport = RetryTransport::new(Flaky::new(vec![status(
+            429,
+            Some(Duration::from_secs(86_400)),
+        )]));
+        let started = Instant::now();
+        assert!(transport.send(call("starknet_chainId")).await.is_ok());
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(250));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(10), Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,359 @@
+use crate::provider::Provider;
+use crate::rpc::ProviderError;
+use stark_core::types::receipt::{
+    TransactionExecutionStatus, TransactionFinalityStatus, TransactionReceipt, TransactionStatus,
+};
+use stark_core::Felt;
+use std::fmt;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+/// `TXN_HASH_NOT_FOUND`, returned until the node has seen the transaction.
+pub const TXN_HASH_NOT_FOUND: i64 = 29;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The finality a [`PendingTransaction`] waits for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetFinality {
+    #[default]
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+#[derive(Debug)]
+pub enum PendingTransactionError {
+    Provider(ProviderError),
+    /// The sequencer refused the transaction; it was never included in a block.
+    Rejected {
+        transaction_hash: Felt,
+        failure_reason: Option<String>,
+    },
+    /// The transaction was included but its execution failed.
+    Reverted {
+        receipt: Box<TransactionReceipt>,
+        revert_reason: Option<String>,
+    },
+    /// The target finality was not reached within the configured timeout.
+    Timeout {
+        transaction_hash: Felt,
+        last_status: Option<TransactionStatus>,
+    },
+}
+
+impl fmt::Display for PendingTransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PendingTransactionError::Provider(err) => write!(f, "{err}"),
+            PendingTransactionError::Rejected {
+                transaction_hash,
+                failure_reason,
+            } => write!(
+                f,
+                "transaction {transaction_hash} was rejected: {}",
+                failure_reason.as_deref().unwrap_or("no reason given")
+            ),
+            PendingTransactionError::Reverted {
+                receipt,
+                revert_reason,
+            } => write!(
+                f,
+                "transaction {} reverted: {}",
+                receipt.transaction_hash,
+                revert_reason.as_deref().unwrap_or("no reason given")
+            ),
+            PendingTransactionError::Timeout {
+                transaction_hash, ..
+            } => write!(f, "timed out waiting for transaction {transaction_hash}"),
+        }
+    }
+}
+
+impl std::error::Error for PendingTransactionError {}
+
+impl From<ProviderError> for PendingTransactionError {
+    fn from(err: ProviderError) -> Self {
+        PendingTransactionError::Provider(err)
+    }
+}
+
+/// What to do after observing a transaction status.
+#[derive(Debug, PartialEq, Eq)]
+enum Step {
+    Wait,
+    Rejected,
+    Reverted,
+    Done,
+}
+
+fn next_step(status: &TransactionStatus, target: TargetFinality) -> Step {
+    match status.finality_status {
+        TransactionFinalityStatus::Received => Step::Wait,
+        TransactionFinalityStatus::Rejected => Step::Rejected,
+        TransactionFinalityStatus::AcceptedOnL2 | TransactionFinalityStatus::AcceptedOnL1 => {
+            if status.execution_status == Some(TransactionExecutionStatus::Reverted) {
+                Step::Reverted
+            } else if target == TargetFinality::AcceptedOnL1
+                && status.finality_status != TransactionFinalityStatus::AcceptedOnL1
+            {
+                Step::Wait
+            } else {
+                Step::Done
+            }
+        }
+    }
+}
+
+/// A submitted transaction. Awaiting it polls the node until the transaction reaches the target
+/// finality and resolves to its receipt.
+pub struct PendingTransaction<'a, P> {
+    provider: &'a P,
+    transaction_hash: Felt,
+    interval: Duration,
+    timeout: Duration,
+    finality: TargetFinality,
+}
+
+impl<'a, P: Provider> PendingTransaction<'a, P> {
+    pub fn new(provider: &'a P, transaction_hash: Felt) -> Self {
+        Self {
+            provider,
+            transaction_hash,
+            interval: DEFAULT_INTERVAL,
+            timeout: DEFAULT_TIMEOUT,
+            finality: TargetFinality::default(),
+        }
+    }
+
+    pub fn transaction_hash(&self) -> Felt {
+        self.transaction_hash
+    }
+
+    /// Sets the delay between two status polls.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn finality(mut self, finality: TargetFinality) -> Self {
+        self.finality = finality;
+        self
+    }
+
+    async fn wait(self) -> Result<TransactionReceipt, PendingTransactionError> {
+        let started = Instant::now();
+        let mut last_status = None;
+        loop {
+            match self
+                .provider
+                .get_transaction_status(self.transaction_hash)
+                .await
+            {
+                Ok(status) => {
+                    match next_step(&status, self.finality) {
+                        Step::Wait => {}
+                        Step::Rejected => {
+                            return Err(PendingTransactionError::Rejected {
+                                transaction_hash: self.transaction_hash,
+                                failure_reason: status.failure_reason,
+                            })
+                        }
+                        Step::Reverted => {
+                            let receipt = self.receipt().await?;
+                            return Err(PendingTransactionError::Reverted {
+                                revert_reason: receipt
+                                    .revert_reason
+                                    .clone()
+                                    .or(status.failure_reason),
+                                receipt: Box::new(receipt),
+                            });
+                        }
+                        Step::Done => return self.receipt().await,
+                    }
+                    last_status = Some(status);
+                }
+                Err(ProviderError::Rpc(err)) if err.code == TXN_HASH_NOT_FOUND => {}
+                Err(err) => return Err(err.into()),
+            }
+
+            if started.elapsed() + self.interval > self.timeout {
+                return Err(PendingTransactionError::Timeout {
+                    transaction_hash: self.transaction_hash,
+                    last_status,
+                });
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    async fn receipt(&self) -> Result<TransactionReceipt, PendingTransactionError> {
+        Ok(self
+            .provider
+            .get_transaction_receipt(self.transaction_hash)
+            .await?)
+    }
+}
+
+impl<'a, P: Provider> IntoFuture for PendingTransaction<'a, P> {
+    type Output = Result<TransactionReceipt, PendingTransactionError>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.wait())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{JsonRpcClient, JsonRpcError};
+    use crate::transport::MemoryTransport;
+    use serde_json::{json, Value};
+
+    /// Answers status polls with `statuses` in turn, repeating the last one, and receipt
+    /// requests with `receipt`.
+    fn node(
+        statuses: Vec<Result<Value, JsonRpcError>>,
+        receipt: Value,
+    ) -> JsonRpcClient<MemoryTransport> {
+        let mut polls = 0;
+        JsonRpcClient::new(MemoryTransport::with_responder(
+            move |request| match request["method"].as_str().unwrap() {
+                "starknet_getTransactionStatus" => {
+                    let status = statuses[polls.min(statuses.len() - 1)].clone();
+                    polls += 1;
+                    status
+                }
+                "starknet_getTransactionReceipt" => Ok(receipt.clone()),
+                method => panic!("unexpected {method}"),
+            },
+        ))
+    }
+
+    fn receipt(execution_status: &str, revert_reason: Option<&str>) -> Value {
+        json!({
+            "type": "INVOKE",
+            "transaction_hash": "0x1",
+            "actual_fee": {"amount": "0x3e8", "unit": "FRI"},
+            "execution_status": execution_status,
+            "finality_status": "ACCEPTED_ON_L2",
+            "block_hash": "0x2",
+            "block_number": 12,
+            "messages_sent": [],
+            "revert_reason": revert_reason,
+            "events": []
+        })
+    }
+
+    fn status(
+        finality_status: TransactionFinalityStatus,
+        execution_status: Option<TransactionExecutionStatus>,
+    ) -> TransactionStatus {
+        TransactionStatus {
+            finality_status,
+            execution_status,
+            failure_reason: None,
+        }
+    }
+
+    #[test]
+    fn waits_for_target_finality() {
+        let received = status(TransactionFinalityStatus::Received, None);
+        let on_l2 = status(
+            TransactionFinalityStatus::AcceptedOnL2,
+            Some(TransactionExecutionStatus::Succeeded),
+        );
+        let on_l1 = status(
+            TransactionFinalityStatus::AcceptedOnL1,
+            Some(TransactionExecutionStatus::Succeeded),
+        );
+
+        assert_eq!(
+            next_step(&received, TargetFinality::AcceptedOnL2),
+            Step::Wait
+        );
+        assert_eq!(next_step(&on_l2, TargetFinality::AcceptedOnL2), Step::Done);
+        assert_eq!(next_step(&on_l2, TargetFinality::AcceptedOnL1), Step::Wait);
+        assert_eq!(next_step(&on_l1, TargetFinality::AcceptedOnL1), Step::Done);
+    }
+
+    #[test]
+    fn surfaces_rejection_and_revert() {
+        let rejected = status(TransactionFinalityStatus::Rejected, None);
+        let reverted = status(
+            TransactionFinalityStatus::AcceptedOnL2,
+            Some(TransactionExecutionStatus::Reverted),
+        );
+
+        assert_eq!(
+            next_step(&rejected, TargetFinality::AcceptedOnL2),
+            Step::Rejected
+        );
+        assert_eq!(
+            next_step(&reverted, TargetFinality::AcceptedOnL1),
+            Step::Reverted
+        );
+    }
+
+    #[tokio::test]
+    async fn polls_until_accepted() {
+        let provider = node(
+            vec![
+                Err(JsonRpcError {
+                    code: 29,
+                    message: "Transaction hash not found".to_string(),
+                    data: None,
+                }),
+                Ok(json!({"finality_status": "RECEIVED"})),
+                Ok(json!({"finality_status": "ACCEPTED_ON_L2", "execution_status": "SUCCEEDED"})),
+            ],
+            receipt("SUCCEEDED", None),
+        );
+        let receipt = PendingTransaction::new(&provider, Felt::ONE)
+            .interval(Duration::from_millis(1))
+            .await
+            .unwrap();
+        assert_eq!(receipt.block_number, Some(12));
+    }
+
+    #[tokio::test]
+    async fn reports_reverted_and_timed_out_transactions() {
+        let provider = node(
+            vec![Ok(
+                json!({"finality_status": "ACCEPTED_ON_L2", "execution_status": "REVERTED"}),
+            )],
+            receipt("REVERTED", Some("Error in the called contract")),
+        );
+        let err = PendingTransaction::new(&provider, Felt::ONE)
+            .interval(Duration::from_millis(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PendingTransactionError::Reverted { revert_reason: Some(reason), .. }
+                if reason == "Error in the called contract"
+        ));
+
+        let provider = node(
+            vec![Ok(json!({"finality_status": "RECEIVED"}))],
+            Value::Null,
+        );
+        let err = PendingTransaction::new(&provider, Felt::ONE)
+            .interval(Duration::from_millis(1))
+            .timeout(Duration::from_millis(20))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PendingTransactionError::Timeout { last_status: Some(status), .. }
+                if status.finality_status == TransactionFinalityStatus::Received
+        ));
+    }
+}
@@ -0,0 +1,291 @@
+use crate::events::EventsStream;
+use crate::rpc::ProviderError;
+use crate::watch::{BlockWatcher, EventWatcher};
+use async_trait::async_trait;
+use ethers::types::H256;
+use stark_core::types::block::{
+    BlockHashAndNumber, MaybePendingBlock, SyncStatus, TransactionWithReceipt,
+};
+use stark_core::types::contract::{CompiledClass, ContractClass};
+use stark_core::types::event::{EventFilter, EventsPage};
+use stark_core::types::fee::{FeeEstimate, SimulationFlag, SimulationFlagForEstimateFee};
+use stark_core::types::message::{MessageStatus, MsgFromL1};
+use stark_core::types::proof::{ContractStorageKeys, StorageProof};
+use stark_core::types::receipt::{TransactionReceipt, TransactionStatus};
+use stark_core::types::request::{BlockNumber, BlockTag, FunctionCall};
+use stark_core::types::state_update::MaybePendingStateUpdate;
+use stark_core::types::trace::{SimulatedTransaction, TransactionTrace, TransactionTraceWithHash};
+use stark_core::types::transaction::{
+    BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
+    BroadcastedInvokeTransaction, BroadcastedTransaction, DeclareTransactionResult,
+    DeployAccountTransactionResult, InvokeTransactionResult, Transaction,
+};
+use stark_core::Felt;
+
+/// The Starknet JSON-RPC read and write API.
+///
+/// [`JsonRpcClient`](crate::rpc::JsonRpcClient) implements it over any
+/// [`Transport`](crate::transport::Transport); mocks and alternative backends can implement it
+/// directly.
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// The version of the JSON-RPC spec the node implements, such as `0.8.0`.
+    async fn spec_version(&self) -> Result<String, ProviderError>;
+
+    async fn stark_block_number(&self) -> Result<u64, ProviderError>;
+
+    async fn stark_chain_id(&self) -> Result<Felt, ProviderError>;
+
+    async fn stark_blockhash_and_number(&self) -> Result<BlockHashAndNumber, ProviderError>;
+
+    async fn stark_getnonce(
+        &self,
+        address: Felt,
+        block_id: BlockNumber,
+    ) -> Result<Felt, ProviderError>;
+
+    async fn get_block_with_tx_hashes(
+        &self,
+        block_id: BlockNumber,
+    ) -> Result<MaybePendingBlock<Felt>, ProviderError>;
+
+    async fn get_block_with_txs(
+        &self,
+        block_id: BlockNumber,
+    ) -> Result<MaybePendingBlock<Transaction>, ProviderError>;
+
+    async fn get_block_with_receipts(
+        &self,
+        block_id: BlockNumber,
+    ) -> Result<MaybePendingBlock<TransactionWithReceipt>, ProviderError>;
+
+    async fn get_state_update(
+        &self,
+        block_id: BlockNumber,
+    ) -> Result<MaybePendingStateUpdate, ProviderError>;
+
+    async fn get_storage_at(
+        &self,
+        contract_address: Felt,
+        key: Felt,
+        block_id: BlockNumber,
+    ) -> Result<Felt, ProviderError>;
+
+    async fn get_transaction_by_hash(&self, hash: Felt) -> Result<Transaction, ProviderError>;
+
+    async fn get_transaction_by_blockid_and_index(
+        &self,
+        block_id: BlockNumber,
+        index: u64,
+    ) -> Result<Transaction, ProviderError>;
+
+    /// The transactions of the pending block.
+    async fn pending_transactions(&self) -> Result<Vec<Transaction>, ProviderError> {
+        Ok(self
+            .get_block_with_txs(BlockNumber::BlockTag(BlockTag::Pending))
+            .await?
+            .into_transactions())
+    }
+
+    async fn get_transaction_status(&self, hash: Felt) -> Result<TransactionStatus, ProviderError>;
+
+    async fn get_transaction_receipt(
+        &self,
+        hash: Felt,
+    ) -> Result<TransactionReceipt, ProviderError>;
+
+    async fn get_class(
+        &self,
+        block_id: BlockNumber,
+        class_hash: Felt,
+    ) -> Result<ContractClass, ProviderError>;
+
+    async fn get_class_hash_at(
+        &self,
+        block_id: BlockNumber,
+        contract_address: Felt,
+    ) -> Result<Felt, ProviderError>;
+
+    async fn get_class_at(
+        &self,
+        block_id: BlockNumber,
+        contract_address: Felt,
+    ) -> Result<ContractClass, ProviderError>;
+
+    /// The CASM a Sierra class compiles to, as executed by the sequencer.
+    async fn get_compiled_casm(&self, class_hash: Felt) -> Result<CompiledClass, ProviderError>;
+
+    async fn get_block_transaction_count(
+        &self,
+        block_id: BlockNumber,
+    ) -> Result<u64, ProviderError>;
+
+    async fn call(
+        &self,
+        request: FunctionCall,
+        block_id: BlockNumber,
+    ) -> Result<Vec<Felt>, ProviderError>;
+
+    /// Estimates the fee of `transactions`, executed in order on top of `block_id`, in one
+    /// request.
+    async fn estimate_fee(
+        &self,
+        transactions: &[BroadcastedTransaction],
+        simulation_flags: &[SimulationFlagForEstimateFee],
+        block_id: BlockNumber,
+    ) -> Result<Vec<FeeEstimate>, ProviderError>;
+
+    /// Executes `transactions` in order on top of `block_id` without submitting them, returning
+    /// the trace and fee estimate of each.
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockNumber,
+        transactions: &[BroadcastedTransaction],
+        simulation_flags: &[SimulationFlag],
+    ) -> Result<Vec<SimulatedTransaction>, ProviderError>;
+
+    /// Estimates the fee of the L2 transaction an L1 message triggers.
+    async fn estimate_message_fee(
+        &self,
+        message: MsgFromL1,
+        block_id: BlockNumber,
+    ) -> Result<FeeEstimate, ProviderError>;
+
+    /// The statuses of the L2 transactions handling the messages sent by an L1 transaction.
+    async fn get_messages_status(
+        &self,
+        l1_transaction_hash: H256,
+    ) -> Result<Vec<MessageStatus>, ProviderError>;
+
+    /// Merkle proofs of classes, contracts and contract storage against the global state root of
+    /// `block_id`.
+    async fn get_storage_proof(
+        &self,
+        block_id: BlockNumber,
+        class_hashes: &[Felt],
+        contract_addresses: &[Felt],
+        contracts_storage_keys: &[ContractStorageKeys],
+    ) -> Result<StorageProof, ProviderError>;
+
+    /// The call tree of an executed transaction. Print it with
+    /// [`TransactionTrace::printer`].
+    async fn trace_transaction(&self, hash: Felt) -> Result<TransactionTrace, ProviderError>;
+
+    /// The traces of every transaction in `block_id`, in block order.
+    async fn trace_block_transactions(
+        &self,
+        block_id: BlockNumber,
+    ) -> Result<Vec<TransactionTraceWithHash>, ProviderError>;
+
+    async fn syncing(&self) -> Result<SyncStatus, ProviderError>;
+
+    /// Returns one page of the events matching `filter`, starting at `continuation_token` if
+    /// given. Use [`Provider::events_stream`] to read all of them.
+    async fn get_events(
+        &self,
+        filter: EventFilter,
+        continuation_token: Option<String>,
+        chunk_size: u64,
+    ) -> Result<EventsPage, ProviderError>;
+
+    /// Submits a signed invoke transaction. Refusals by the node surface as
+    /// [`ProviderError::Submission`].
+    async fn add_invoke_transaction(
+        &self,
+        transaction: BroadcastedInvokeTransaction,
+    ) -> Result<InvokeTransactionResult, ProviderError>;
+
+    /// Submits a signed declare transaction.
+    async fn add_declare_transaction(
+        &self,
+        transaction: BroadcastedDeclareTransaction,
+    ) -> Result<DeclareTransactionResult, ProviderError>;
+
+    /// Submits a signed deploy account transaction.
+    async fn add_deploy_account_transaction(
+        &self,
+        transaction: BroadcastedDeployAccountTransaction,
+    ) -> Result<DeployAccountTransactionResult, ProviderError>;
+
+    /// Streams every event matching `filter`. See [`EventsStream`].
+    fn events_stream(&self, filter: EventFilter) -> EventsStream<'_, Self>
+    where
+        Self: Sized,
+    {
+        EventsStream::new(self, filter)
+    }
+
+    /// Streams new blocks by polling, reporting reorgs. See [`crate::watch`].
+    fn watch_blocks(&self) -> BlockWatcher<'_, Self>
+    where
+        Self: Sized,
+    {
+        BlockWatcher::new(self)
+    }
+
+    /// Streams the events matching `filter` by polling, reporting reorgs.
+    fn watch_events(&self, filter: EventFilter) -> EventWatcher<'_, Self>
+    where
+        Self: Sized,
+    {
+        EventWatcher::new(self, filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{JsonRpcClient, JsonRpcError};
+    use crate::transport::MemoryTransport;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn sends_spec_params() {
+        let (transport, mut server) = MemoryTransport::channel();
+        let provider = JsonRpcClient::new(transport);
+        let requests = tokio::spawn(async move {
+            let mut params = Vec::new();
+            while let Some(request) = server.next_request().await {
+                params.push((
+                    request.method().unwrap().to_string(),
+                    request.params().clone(),
+                ));
+                request.respond_error(JsonRpcError {
+                    code: 24,
+                    message: "Block not found".to_string(),
+                    data: None,
+                });
+            }
+            params
+        });
+
+        let pending = BlockNumber::BlockTag(BlockTag::Pending);
+        assert!(provider.get_state_update(pending).await.is_err());
+        let filter = EventFilter {
+            from_block: Some(BlockNumber::Number(1)),
+            to_block: None,
+            address: Some(Felt::TWO),
+            keys: vec![vec![Felt::ONE]],
+        };
+        let token = Some("1-0".to_string());
+        assert!(provider.get_events(filter, token, 10).await.is_err());
+        drop(provider);
+
+        assert_eq!(
+            requests.await.unwrap(),
+            vec![
+                ("starknet_getStateUpdate".to_string(), json!(["pending"])),
+                (
+                    "starknet_getEvents".to_string(),
+                    json!([{
+                        "from_block": {"block_number": 1},
+                        "address": "0x2",
+                        "keys": [["0x1"]],
+                        "chunk_size": 10,
+                        "continuation_token": "1-0",
+                    }])
+                ),
+            ]
+        );
+    }
+}
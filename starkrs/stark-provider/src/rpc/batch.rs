@@ -0,0 +1,425 @@
+use crate::rpc::{events_params, parse_response, JsonRpcClient, ProviderError};
+use crate::transport::Transport;
+use ethers::types::H256;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use stark_core::types::block::{
+    BlockHashAndNumber, MaybePendingBlock, SyncStatus, TransactionWithReceipt,
+};
+use stark_core::types::contract::{CompiledClass, ContractClass};
+use stark_core::types::event::{EventFilter, EventsPage};
+use stark_core::types::fee::{FeeEstimate, SimulationFlag, SimulationFlagForEstimateFee};
+use stark_core::types::message::{MessageStatus, MsgFromL1};
+use stark_core::types::proof::{ContractStorageKeys, StorageProof};
+use stark_core::types::receipt::{TransactionReceipt, TransactionStatus};
+use stark_core::types::request::{BlockNumber, FunctionCall};
+use stark_core::types::state_update::MaybePendingStateUpdate;
+use stark_core::types::trace::{SimulatedTransaction, TransactionTrace, TransactionTraceWithHash};
+use stark_core::types::transaction::{BroadcastedTransaction, Transaction};
+use stark_core::Felt;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Calls queued on a [`JsonRpcClient`] and sent together as one JSON-RPC array.
+///
+/// Each queued call returns a [`BatchCall`] handle that later takes its own result out of the
+/// [`BatchResponses`], so one failing call does not fail the others.
+pub struct Batch<'a, T> {
+    client: &'a JsonRpcClient<T>,
+    calls: Vec<Value>,
+}
+
+/// A handle to a queued call, resolving to `R`.
+#[derive(Debug)]
+pub struct BatchCall<R> {
+    index: usize,
+    _result: PhantomData<fn() -> R>,
+}
+
+impl<R> Clone for BatchCall<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for BatchCall<R> {}
+
+/// The responses of a sent [`Batch`], in call order.
+#[derive(Debug)]
+pub struct BatchResponses {
+    responses: Vec<Option<Value>>,
+}
+
+impl<'a, T: Transport> Batch<'a, T> {
+    pub(crate) fn new(client: &'a JsonRpcClient<T>) -> Self {
+        Self {
+            client,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Queues a call to any method.
+    pub fn add<P: Serialize, R: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: P,
+    ) -> BatchCall<R> {
+        self.calls.push(self.client.request_body(method, params));
+        BatchCall {
+            index: self.calls.len() - 1,
+            _result: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Sends every queued call in one request. Errors only if the batch as a whole failed; errors
+    /// of single calls are returned by [`BatchResponses::take`].
+    pub async fn send(self) -> Result<BatchResponses, ProviderError> {
+        if self.calls.is_empty() {
+            return Ok(BatchResponses {
+                responses: Vec::new(),
+            });
+        }
+
+        let ids: Vec<Value> = self.calls.iter().map(|call| call["id"].clone()).collect();
+        let mut body = self
+            .client
+            .transport()
+            .send(Value::Array(self.calls))
+            .await?;
+        // A node that rejects the batch outright answers with a single error object.
+        if let Some(error) = body.get_mut("error") {
+            return Err(ProviderError::Rpc(serde_json::from_value(error.take())?));
+        }
+        let responses: Vec<Value> = serde_json::from_value(body)?;
+
+        let mut by_id: HashMap<String, Value> = responses
+            .into_iter()
+            .map(|response| (response["id"].to_string(), response))
+            .collect();
+        Ok(BatchResponses {
+            responses: ids.iter().map(|id| by_id.remove(&id.to_string())).collect(),
+        })
+    }
+
+    pub fn spec_version(&mut self) -> BatchCall<String> {
+        self.add("starknet_specVersion", json!([]))
+    }
+
+    pub fn stark_block_number(&mut self) -> BatchCall<u64> {
+        self.add("starknet_blockNumber", json!([]))
+    }
+
+    pub fn stark_chain_id(&mut self) -> BatchCall<Felt> {
+        self.add("starknet_chainId", json!([]))
+    }
+
+    pub fn stark_blockhash_and_number(&mut self) -> BatchCall<BlockHashAndNumber> {
+        self.add("starknet_blockHashAndNumber", json!([]))
+    }
+
+    pub fn stark_getnonce(&mut self, address: Felt, block_id: BlockNumber) -> BatchCall<Felt> {
+        self.add("starknet_getNonce", [json!(block_id), json!(address)])
+    }
+
+    pub fn get_block_with_tx_hashes(
+        &mut self,
+        block_id: BlockNumber,
+    ) -> BatchCall<MaybePendingBlock<Felt>> {
+        self.add("starknet_getBlockWithTxHashes", [json!(block_id)])
+    }
+
+    pub fn get_block_with_txs(
+        &mut self,
+        block_id: BlockNumber,
+    ) -> BatchCall<MaybePendingBlock<Transaction>> {
+        self.add("starknet_getBlockWithTxs", [json!(block_id)])
+    }
+
+    pub fn get_block_with_receipts(
+        &mut self,
+        block_id: BlockNumber,
+    ) -> BatchCall<MaybePendingBlock<TransactionWithReceipt>> {
+        self.add("starknet_getBlockWithReceipts", [json!(block_id)])
+    }
+
+    pub fn get_state_update(
+        &mut self,
+        block_id: BlockNumber,
+    ) -> BatchCall<MaybePendingStateUpdate> {
+        self.add("starknet_getStateUpdate", [json!(block_id)])
+    }
+
+    pub fn get_storage_at(
+        &mut self,
+        contract_address: Felt,
+        key: Felt,
+        block_id: BlockNumber,
+    ) -> BatchCall<Felt> {
+        self.add(
+            "starknet_getStorageAt",
+            [json!(contract_address), json!(key), json!(block_id)],
+        )
+    }
+
+    pub fn get_transaction_by_hash(&mut self, hash: Felt) -> BatchCall<Transaction> {
+        self.add("starknet_getTransactionByHash", [json!(hash)])
+    }
+
+    pub fn get_transaction_by_blockid_and_index(
+        &mut self,
+        block_id: BlockNumber,
+        index: u64,
+    ) -> BatchCall<Transaction> {
+        self.add(
+            "starknet_getTransactionByBlockIdAndIndex",
+            [json!(block_id), json!(index)],
+        )
+    }
+
+    pub fn get_transaction_status(&mut self, hash: Felt) -> BatchCall<TransactionStatus> {
+        self.add("starknet_getTransactionStatus", [json!(hash)])
+    }
+
+    pub fn get_transaction_receipt(&mut self, hash: Felt) -> BatchCall<TransactionReceipt> {
+        self.add("starknet_getTransactionReceipt", [json!(hash)])
+    }
+
+    pub fn get_class(
+        &mut self,
+        block_id: BlockNumber,
+        class_hash: Felt,
+    ) -> BatchCall<ContractClass> {
+        self.add("starknet_getClass", [json!(block_id), json!(class_hash)])
+    }
+
+    pub fn get_class_hash_at(
+        &mut self,
+        block_id: BlockNumber,
+        contract_address: Felt,
+    ) -> BatchCall<Felt> {
+        self.add(
+            "starknet_getClassHashAt",
+            [json!(block_id), json!(contract_address)],
+        )
+    }
+
+    pub fn get_class_at(
+        &mut self,
+        block_id: BlockNumber,
+        contract_address: Felt,
+    ) -> BatchCall<ContractClass> {
+        self.add(
+            "starknet_getClassAt",
+            [json!(block_id), json!(contract_address)],
+        )
+    }
+
+    pub fn get_compiled_casm(&mut self, class_hash: Felt) -> BatchCall<CompiledClass> {
+        self.add("starknet_getCompiledCasm", [json!(class_hash)])
+    }
+
+    pub fn get_block_transaction_count(&mut self, block_id: BlockNumber) -> BatchCall<u64> {
+        self.add("starknet_getBlockTransactionCount", [json!(block_id)])
+    }
+
+    pub fn call(&mut self, request: FunctionCall, block_id: BlockNumber) -> BatchCall<Vec<Felt>> {
+        self.add("starknet_call", [json!(request), json!(block_id)])
+    }
+
+    pub fn estimate_fee(
+        &mut self,
+        transactions: &[BroadcastedTransaction],
+        simulation_flags: &[SimulationFlagForEstimateFee],
+        block_id: BlockNumber,
+    ) -> BatchCall<Vec<FeeEstimate>> {
+        self.add(
+            "starknet_estimateFee",
+            [
+                json!(transactions),
+                json!(simulation_flags),
+                json!(block_id),
+            ],
+        )
+    }
+
+    pub fn simulate_transactions(
+        &mut self,
+        block_id: BlockNumber,
+        transactions: &[BroadcastedTransaction],
+        simulation_flags: &[SimulationFlag],
+    ) -> BatchCall<Vec<SimulatedTransaction>> {
+        self.add(
+            "starknet_simulateTransactions",
+            [
+                json!(block_id),
+                json!(transactions),
+                json!(simulation_flags),
+            ],
+        )
+    }
+
+    pub fn estimate_message_fee(
+        &mut self,
+        message: MsgFromL1,
+        block_id: BlockNumber,
+    ) -> BatchCall<FeeEstimate> {
+        self.add(
+            "starknet_estimateMessageFee",
+            [json!(message), json!(block_id)],
+        )
+    }
+
+    pub fn get_messages_status(
+        &mut self,
+        l1_transaction_hash: H256,
+    ) -> BatchCall<Vec<MessageStatus>> {
+        self.add("starknet_getMessagesStatus", [json!(l1_transaction_hash)])
+    }
+
+    pub fn get_storage_proof(
+        &mut self,
+        block_id: BlockNumber,
+        class_hashes: &[Felt],
+        contract_addresses: &[Felt],
+        contracts_storage_keys: &[ContractStorageKeys],
+    ) -> BatchCall<StorageProof> {
+        self.add(
+            "starknet_getStorageProof",
+            [
+                json!(block_id),
+                json!(class_hashes),
+                json!(contract_addresses),
+                json!(contracts_storage_keys),
+            ],
+        )
+    }
+
+    pub fn trace_transaction(&mut self, hash: Felt) -> BatchCall<TransactionTrace> {
+        self.add("starknet_traceTransaction", [json!(hash)])
+    }
+
+    pub fn trace_block_transactions(
+        &mut self,
+        block_id: BlockNumber,
+    ) -> BatchCall<Vec<TransactionTraceWithHash>> {
+        self.add("starknet_traceBlockTransactions", [json!(block_id)])
+    }
+
+    pub fn syncing(&mut self) -> BatchCall<SyncStatus> {
+        self.add("starknet_syncing", json!([]))
+    }
+
+    pub fn get_events(
+        &mut self,
+        filter: EventFilter,
+        continuation_token: Option<String>,
+        chunk_size: u64,
+    ) -> BatchCall<EventsPage> {
+        self.add(
+            "starknet_getEvents",
+            events_params(filter, continuation_token, chunk_size),
+        )
+    }
+}
+
+impl BatchResponses {
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Takes the result of `call` out of the batch. Taking the same call twice, or a call the
+    /// node did not answer, is an error.
+    pub fn take<R: DeserializeOwned>(&mut self, call: BatchCall<R>) -> Result<R, ProviderError> {
+        let response = self
+            .responses
+            .get_mut(call.index)
+            .and_then(Option::take)
+            .ok_or_else(|| {
+                ProviderError::Deserialize(serde::de::Error::custom(format!(
+                    "no response for batch call {}",
+                    call.index
+                )))
+            })?;
+        parse_response(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use std::collections::HashSet;
+
+    #[tokio::test]
+    async fn matches_responses_by_id() {
+        let client = JsonRpcClient::new(MemoryTransport::with_handler(|request| {
+            let calls = request.as_array().unwrap();
+            let ids: HashSet<String> = calls.iter().map(|call| call["id"].to_string()).collect();
+            assert_eq!(ids.len(), calls.len());
+
+            // Answer in reverse order, failing the receipt lookup.
+            Value::Array(
+                calls
+                    .iter()
+                    .rev()
+                    .map(|call| match call["method"].as_str().unwrap() {
+                        "starknet_blockNumber" => {
+                            json!({"jsonrpc":"2.0","id":call["id"],"result":95812})
+                        }
+                        "starknet_chainId" => {
+                            json!({"jsonrpc":"2.0","id":call["id"],"result":"0x534e5f4d41494e"})
+                        }
+                        _ => json!({
+                            "jsonrpc":"2.0",
+                            "id":call["id"],
+                            "error":{"code":29,"message":"Transaction hash not found"}
+                        }),
+                    })
+                    .collect(),
+            )
+        }));
+
+        let mut batch = client.batch();
+        let block_number = batch.stark_block_number();
+        let receipt = batch.get_transaction_receipt(Felt::ONE);
+        let chain_id = batch.stark_chain_id();
+        let mut responses = batch.send().await.unwrap();
+
+        assert_eq!(responses.take(block_number).unwrap(), 95812);
+        assert_eq!(
+            responses.take(chain_id).unwrap(),
+            Felt::from_short_string("SN_MAIN").unwrap()
+        );
+        assert!(matches!(
+            responses.take(receipt),
+            Err(ProviderError::Rpc(err)) if err.code == 29
+        ));
+        assert!(responses.take(block_number).is_err());
+    }
+
+    #[tokio::test]
+    async fn surfaces_a_rejected_batch() {
+        let client = JsonRpcClient::new(MemoryTransport::with_handler(
+            |_| json!({"jsonrpc":"2.0","id":null,"error":{"code":-32600,"message":"Invalid Request"}}),
+        ));
+        let mut batch = client.batch();
+        batch.stark_block_number();
+        assert!(matches!(
+            batch.send().await,
+            Err(ProviderError::Rpc(err)) if err.code == -32600
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::transport::TransportError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The `error` object of a JSON-RPC response.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl fmt::Display for JsonRpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JSON-RPC error {}: {}", self.code, self.message)?;
+        if let Some(data) = &self.data {
+            write!(f, " ({data})")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ProviderError {
+    /// The request never produced a response body.
+    Transport(TransportError),
+    /// The node answered with a JSON-RPC error.
+    Rpc(JsonRpcError),
+    /// The node answered with a body that does not match the expected type.
+    Deserialize(serde_json::Error),
+    /// The node refused a submitted transaction.
+    Submission(SubmissionError),
+}
+
+/// Why the node refused a transaction passed to one of the `starknet_add*Transaction` methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmissionError {
+    /// 51: the class is already declared.
+    ClassAlreadyDeclared,
+    /// 52: the nonce does not match the account's next nonce.
+    InvalidTransactionNonce,
+    /// 53: the fee limit does not cover validation.
+    InsufficientResourcesForValidate,
+    /// 54: the account cannot pay the fee limit.
+    InsufficientAccountBalance,
+    /// 55: the account's `__validate__` failed.
+    ValidationFailure(String),
+    /// 56: the Sierra class does not compile.
+    CompilationFailed(String),
+    /// 57
+    ContractClassSizeIsTooLarge,
+    /// 58: the sender is not an account contract.
+    NonAccount,
+    /// 59: the same transaction is already known.
+    DuplicateTx,
+    /// 60: the declared compiled class hash does not match the compiled class.
+    CompiledClassHashMismatch,
+    /// 61
+    UnsupportedTxVersion,
+    /// 62
+    UnsupportedContractClassVersion,
+    /// 63
+    UnexpectedError(String),
+    /// Not sent: the transaction carries a query version and is only valid for estimation and
+    /// simulation.
+    QueryTransaction,
+}
+
+impl SubmissionError {
+    /// Maps a spec submission error code to its variant.
+    pub fn from_rpc(err: &JsonRpcError) -> Option<Self> {
+        let reason = || match &err.data {
+            Some(serde_json::Value::String(data)) => data.clone(),
+            Some(data) => data.to_string(),
+            None => err.message.clone(),
+        };
+        Some(match err.code {
+            51 => SubmissionError::ClassAlreadyDeclared,
+            52 => SubmissionError::InvalidTransactionNonce,
+            53 => SubmissionError::InsufficientResourcesForValidate,
+            54 => SubmissionError::InsufficientAccountBalance,
+            55 => SubmissionError::ValidationFailure(reason()),
+            56 => SubmissionError::CompilationFailed(reason()),
+            57 => SubmissionError::ContractClassSizeIsTooLarge,
+            58 => SubmissionError::NonAccount,
+            59 => SubmissionError::DuplicateTx,
+            60 => SubmissionError::CompiledClassHashMismatch,
+            61 => SubmissionError::UnsupportedTxVersion,
+            62 => SubmissionError::UnsupportedContractClassVersion,
+            63 => SubmissionError::UnexpectedError(reason()),
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for SubmissionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmissionError::ClassAlreadyDeclared => write!(f, "class already declared"),
+            SubmissionError::InvalidTransactionNonce => write!(f, "invalid transaction nonce"),
+            SubmissionError::InsufficientResourcesForValidate => {
+                write!(f, "max fee is smaller than the minimal transaction cost")
+            }
+            SubmissionError::InsufficientAccountBalance => {
+                write!(
+                    f,
+                    "account balance is smaller than the transaction's max fee"
+                )
+            }
+            SubmissionError::ValidationFailure(reason) => {
+                write!(f, "account validation failed: {reason}")
+            }
+            SubmissionError::CompilationFailed(reason) => {
+                write!(f, "compilation failed: {reason}")
+            }
+            SubmissionError::ContractClassSizeIsTooLarge => {
+                write!(f, "contract class size is too large")
+            }
+            SubmissionError::NonAccount => write!(f, "sender address is not an account contract"),
+            SubmissionError::DuplicateTx => {
+                write!(f, "a transaction with the same hash already exists")
+            }
+            SubmissionError::CompiledClassHashMismatch => {
+                write!(f, "compiled class hash does not match the compiled class")
+            }
+            SubmissionError::UnsupportedTxVersion => write!(f, "unsupported transaction version"),
+            SubmissionError::UnsupportedContractClassVersion => {
+                write!(f, "unsupported contract class version")
+            }
+            SubmissionError::UnexpectedError(reason) => write!(f, "unexpected error: {reason}"),
+            SubmissionError::QueryTransaction => {
+                write!(f, "query transactions can only be estimated or simulated")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubmissionError {}
+
+impl ProviderError {
+    /// Turns the spec's submission error codes into [`ProviderError::Submission`].
+    pub(crate) fn into_submission(self) -> Self {
+        match self {
+            ProviderError::Rpc(err) => match SubmissionError::from_rpc(&err) {
+                Some(submission) => ProviderError::Submission(submission),
+                None => ProviderError::Rpc(err),
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderError::Transport(err) => write!(f, "transport error: {err}"),
+            ProviderError::Rpc(err) => write!(f, "{err}"),
+            ProviderError::Deserialize(err) => write!(f, "invalid response: {err}"),
+            ProviderError::Submission(err) => write!(f, "transaction rejected: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+impl From<TransportError> for ProviderError {
+    fn from(err: TransportError) -> Self {
+        ProviderError::Transport(err)
+    }
+}
+
+impl From<reqwest::Error> for ProviderError {
+    fn from(err: reqwest::Error) -> Self {
+        ProviderError::Transport(TransportError::Http(err))
+    }
+}
+
+impl From<serde_json::Error> for ProviderError {
+    fn from(err: serde_json::Error) -> Self {
+        ProviderError::Deserialize(err)
+    }
+}
@@ -1,2 +1,6 @@
+mod batch;
+mod error;
 mod provider;
-pub use provider::*;
\ No newline at end of file
+pub use batch::*;
+pub use error::*;
+pub use provider::*;
@@ -0,0 +1,431 @@
+//! The WebSocket subscription API of RPC 0.8, exposed as [`Stream`]s on a
+//! [`JsonRpcClient`] over a [`WsTransport`].
+
+use crate::rpc::{parse_response, JsonRpcClient, ProviderError};
+use crate::transport::{subscription_key, Notification, WsTransport};
+use futures::stream::{self, Stream};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use stark_core::types::block::BlockHeader;
+use stark_core::types::event::EmittedEvent;
+use stark_core::types::request::BlockNumber;
+use stark_core::types::subscription::{NewTransactionStatus, ReorgData};
+use stark_core::types::transaction::Transaction;
+use stark_core::Felt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+const REORG_NOTIFICATION: &str = "starknet_subscriptionReorg";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubscriptionItem<T> {
+    Item(T),
+    /// The node reverted these blocks; items received from them are no longer valid.
+    Reorg(ReorgData),
+    /// The connection dropped and the subscription was created again. New heads and events
+    /// resume after the last block received, or at the first reverted block after a reorg, so
+    /// events of that block may be delivered twice; other subscriptions may have missed items.
+    Reconnected,
+}
+
+/// A live subscription. Dropping it unsubscribes in the background.
+pub struct Subscription<T> {
+    stream: Pin<Box<dyn Stream<Item = Result<SubscriptionItem<T>, ProviderError>> + Send>>,
+    transport: WsTransport,
+    id: Arc<Mutex<Option<String>>>,
+}
+
+impl<T> Subscription<T> {
+    /// The id the node currently knows the subscription by. It changes after a reconnect.
+    pub fn id(&self) -> Option<String> {
+        self.id.lock().unwrap().clone()
+    }
+
+    /// Ends the subscription, returning whether the node knew it.
+    pub async fn unsubscribe(self) -> Result<bool, ProviderError> {
+        let id = self.id.lock().unwrap().take();
+        match id {
+            Some(id) => unsubscribe(self.transport.clone(), id).await,
+            None => Ok(false),
+        }
+    }
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = Result<SubscriptionItem<T>, ProviderError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        let Some(id) = self.id.lock().unwrap().take() else {
+            return;
+        };
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(unsubscribe(self.transport.clone(), id));
+        }
+    }
+}
+
+async fn unsubscribe(transport: WsTransport, id: String) -> Result<bool, ProviderError> {
+    use crate::transport::Transport;
+
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "starknet_unsubscribe",
+        "params": {"subscription_id": id},
+    });
+    parse_response(transport.send(request).await?)
+}
+
+/// Sends a subscribe call, returning the subscription id and its notifications.
+async fn open(
+    transport: &WsTransport,
+    method: &str,
+    params: &Value,
+) -> Result<(String, mpsc::UnboundedReceiver<Notification>), ProviderError> {
+    let request = json!({"jsonrpc": "2.0", "id": 0, "method": method, "params": params});
+    let (response, notifications) = transport.subscribe(request).await?;
+    let id: Value = parse_response(response)?;
+    Ok((subscription_key(&id), notifications))
+}
+
+struct State {
+    transport: WsTransport,
+    method: &'static str,
+    params: Value,
+    /// How `params` follow the stream so that resubscribing resumes where it stopped.
+    resume: Resume,
+    /// `None` once the subscription could not be created again.
+    notifications: Option<mpsc::UnboundedReceiver<Notification>>,
+    id: Arc<Mutex<Option<String>>>,
+}
+
+impl State {
+    async fn next<T: DeserializeOwned>(
+        &mut self,
+    ) -> Option<Result<SubscriptionItem<T>, ProviderError>> {
+        let notification = self.notifications.as_mut()?.recv().await?;
+        let item = match notification {
+            Notification::Message { method, result } if method == REORG_NOTIFICATION => {
+                serde_json::from_value(result).map(|reorg: ReorgData| {
+                    self.resume.rewind(&mut self.params, &reorg);
+                    SubscriptionItem::Reorg(reorg)
+                })
+            }
+            Notification::Message { result, .. } => {
+                self.resume.advance(&mut self.params, &result);
+                serde_json::from_value(result).map(SubscriptionItem::Item)
+            }
+            Notification::Reconnected => {
+                return Some(
+                    match open(&self.transport, self.method, &self.params).await {
+                        Ok((id, notifications)) => {
+                            self.notifications = Some(notifications);
+                            *self.id.lock().unwrap() = Some(id);
+                            Ok(SubscriptionItem::Reconnected)
+                        }
+                        Err(err) => {
+                            self.notifications = None;
+                            *self.id.lock().unwrap() = None;
+                            Err(err)
+                        }
+                    },
+                );
+            }
+        };
+        Some(item.map_err(ProviderError::from))
+    }
+}
+
+async fn subscribe<T: DeserializeOwned + Send + 'static>(
+    transport: &WsTransport,
+    method: &'static str,
+    params: Map<String, Value>,
+    resume: Resume,
+) -> Result<Subscription<T>, ProviderError> {
+    let params = Value::Object(params);
+    let (id, notifications) = open(transport, method, &params).await?;
+    let id = Arc::new(Mutex::new(Some(id)));
+    let state = State {
+        transport: transport.clone(),
+        method,
+        params,
+        resume,
+        notifications: Some(notifications),
+        id: id.clone(),
+    };
+    let stream = stream::unfold(state, |mut state| async move {
+        let item = state.next().await?;
+        Some((item, state))
+    });
+    Ok(Subscription {
+        stream: Box::pin(stream),
+        transport: transport.clone(),
+        id,
+    })
+}
+
+/// Where a resubscription starts.
+#[derive(Debug, Clone, Copy)]
+enum Resume {
+    /// After the block of the last item, for subscriptions with one item per block.
+    AfterBlock,
+    /// At the block of the last item, which may carry more items.
+    AtBlock,
+    /// With the original params.
+    Never,
+}
+
+impl Resume {
+    fn advance(self, params: &mut Value, result: &Value) {
+        let next = match self {
+            Resume::AfterBlock => 1,
+            Resume::AtBlock => 0,
+            Resume::Never => return,
+        };
+        if let Some(block_number) = result["block_number"].as_u64() {
+            params["block_id"] = json!({"block_number": block_number + next});
+        }
+    }
+
+    /// Restarts from the first reverted block so that its replacement is delivered.
+    fn rewind(self, params: &mut Value, reorg: &ReorgData) {
+        if !matches!(self, Resume::Never) {
+            params["block_id"] = json!({"block_number": reorg.starting_block_number});
+        }
+    }
+}
+
+impl JsonRpcClient<WsTransport> {
+    /// Streams new block headers, starting from `block_id` if given.
+    pub async fn subscribe_new_heads(
+        &self,
+        block_id: Option<BlockNumber>,
+    ) -> Result<Subscription<BlockHeader>, ProviderError> {
+        let mut params = Map::new();
+        if let Some(block_id) = block_id {
+            params.insert("block_id".to_string(), json!(block_id));
+        }
+        subscribe(
+            self.transport(),
+            "starknet_subscribeNewHeads",
+            params,
+            Resume::AfterBlock,
+        )
+        .await
+    }
+
+    /// Streams events emitted by `from_address` matching `keys`, starting from `block_id` if
+    /// given. Each inner list of `keys` matches one key position; an empty list matches any key.
+    pub async fn subscribe_events(
+        &self,
+        from_address: Option<Felt>,
+        keys: Option<Vec<Vec<Felt>>>,
+        block_id: Option<BlockNumber>,
+    ) -> Result<Subscription<EmittedEvent>, ProviderError> {
+        let mut params = Map::new();
+        if let Some(from_address) = from_address {
+            params.insert("from_address".to_string(), json!(from_address));
+        }
+        if let Some(keys) = keys {
+            params.insert("keys".to_string(), json!(keys));
+        }
+        if let Some(block_id) = block_id {
+            params.insert("block_id".to_string(), json!(block_id));
+        }
+        subscribe(
+            self.transport(),
+            "starknet_subscribeEvents",
+            params,
+            Resume::AtBlock,
+        )
+        .await
+    }
+
+    /// Streams the status changes of a transaction.
+    pub async fn subscribe_transaction_status(
+        &self,
+        transaction_hash: Felt,
+    ) -> Result<Subscription<NewTransactionStatus>, ProviderError> {
+        let mut params = Map::new();
+        params.insert("transaction_hash".to_string(), json!(transaction_hash));
+        subscribe(
+            self.transport(),
+            "starknet_subscribeTransactionStatus",
+            params,
+            Resume::Never,
+        )
+        .await
+    }
+
+    /// Streams the hashes of transactions entering the pending block, optionally only those
+    /// sent by `sender_addresses`.
+    pub async fn subscribe_pending_transaction_hashes(
+        &self,
+        sender_addresses: Option<Vec<Felt>>,
+    ) -> Result<Subscription<Felt>, ProviderError> {
+        subscribe(
+            self.transport(),
+            "starknet_subscribePendingTransactions",
+            pending_transaction_params(false, sender_addresses),
+            Resume::Never,
+        )
+        .await
+    }
+
+    /// Like [`JsonRpcClient::subscribe_pending_transaction_hashes`], with the full transactions.
+    pub async fn subscribe_pending_transactions(
+        &self,
+        sender_addresses: Option<Vec<Felt>>,
+    ) -> Result<Subscription<Transaction>, ProviderError> {
+        subscribe(
+            self.transport(),
+            "starknet_subscribePendingTransactions",
+            pending_transaction_params(true, sender_addresses),
+            Resume::Never,
+        )
+        .await
+    }
+}
+
+fn pending_transaction_params(
+    transaction_details: bool,
+    sender_addresses: Option<Vec<Felt>>,
+) -> Map<String, Value> {
+    let mut params = Map::new();
+    params.insert(
+        "transaction_details".to_string(),
+        json!(transaction_details),
+    );
+    if let Some(sender_addresses) = sender_addresses {
+        params.insert("sender_address".to_string(), json!(sender_addresses));
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+    use url::Url;
+
+    fn header(block_number: u64) -> Value {
+        let price = json!({"price_in_fri": "0x1", "price_in_wei": "0x2"});
+        json!({
+            "block_hash": format!("{:#x}", 0x100 + block_number),
+            "parent_hash": format!("{:#x}", 0xff + block_number),
+            "block_number": block_number,
+            "new_root": "0x3",
+            "timestamp": 1_700_000_000u64 + block_number,
+            "sequencer_address": "0x4",
+            "l1_gas_price": price,
+            "l1_data_gas_price": price,
+            "l2_gas_price": price,
+            "l1_da_mode": "BLOB",
+            "starknet_version": "0.13.4",
+        })
+    }
+
+    fn notification(method: &str, subscription_id: &str, result: Value) -> Message {
+        Message::Text(
+            json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": {"subscription_id": subscription_id, "result": result},
+            })
+            .to_string(),
+        )
+    }
+
+    async fn next_call(
+        stream: &mut tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>,
+    ) -> Value {
+        match stream.next().await.unwrap().unwrap() {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            message => panic!("unexpected message {message:?}"),
+        }
+    }
+
+    fn reply(call: &Value, result: Value) -> Message {
+        Message::Text(json!({"jsonrpc": "2.0", "id": call["id"], "result": result}).to_string())
+    }
+
+    #[tokio::test]
+    async fn streams_heads_and_resubscribes_after_reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("ws://{}", listener.local_addr().unwrap())).unwrap();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut stream = tokio_tungstenite::accept_async(socket).await.unwrap();
+            let call = next_call(&mut stream).await;
+            assert_eq!(call["method"], "starknet_subscribeNewHeads");
+            assert_eq!(call["params"], json!({}));
+            stream.send(reply(&call, json!("s1"))).await.unwrap();
+            let heads = "starknet_subscriptionNewHeads";
+            stream
+                .send(notification(heads, "s1", header(10)))
+                .await
+                .unwrap();
+            let reorg = json!({
+                "starting_block_hash": "0x10a",
+                "starting_block_number": 10,
+                "ending_block_hash": "0x10a",
+                "ending_block_number": 10,
+            });
+            stream
+                .send(notification(REORG_NOTIFICATION, "s1", reorg))
+                .await
+                .unwrap();
+            stream.close(None).await.unwrap();
+
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut stream = tokio_tungstenite::accept_async(socket).await.unwrap();
+            let call = next_call(&mut stream).await;
+            // The reverted block 10 is delivered again from its replacement.
+            assert_eq!(call["params"], json!({"block_id": {"block_number": 10}}));
+            stream.send(reply(&call, json!("s2"))).await.unwrap();
+            let mut replacement = header(10);
+            replacement["block_hash"] = json!("0x20a");
+            stream
+                .send(notification(heads, "s2", replacement))
+                .await
+                .unwrap();
+
+            let call = next_call(&mut stream).await;
+            assert_eq!(call["method"], "starknet_unsubscribe");
+            assert_eq!(call["params"], json!({"subscription_id": "s2"}));
+            stream.send(reply(&call, json!(true))).await.unwrap();
+        });
+
+        let client = JsonRpcClient::new(WsTransport::connect(url).await.unwrap());
+        let mut heads = client.subscribe_new_heads(None).await.unwrap();
+        assert_eq!(heads.id().as_deref(), Some("s1"));
+
+        let mut items = Vec::new();
+        for _ in 0..4 {
+            items.push(heads.next().await.unwrap().unwrap());
+        }
+        assert!(matches!(&items[0], SubscriptionItem::Item(header) if header.block_number == 10));
+        assert!(
+            matches!(&items[1], SubscriptionItem::Reorg(reorg) if reorg.starting_block_number == 10)
+        );
+        assert_eq!(items[2], SubscriptionItem::Reconnected);
+        assert!(matches!(
+            &items[3],
+            SubscriptionItem::Item(header) if header.block_number == 10 && header.block_hash == Felt::from(0x20au64)
+        ));
+
+        assert_eq!(heads.id().as_deref(), Some("s2"));
+        assert!(heads.unsubscribe().await.unwrap());
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+use std::fmt;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite;
+
+#[derive(Debug)]
+pub enum TransportError {
+    Http(reqwest::Error),
+    /// The HTTP endpoint answered with an error status and no JSON-RPC body.
+    Status {
+        status: u16,
+        /// The `Retry-After` delay, if the endpoint sent one.
+        retry_after: Option<Duration>,
+    },
+    WebSocket(Box<tungstenite::Error>),
+    /// The connection went away before the response arrived.
+    Closed,
+    /// The transport could not be built from the given options.
+    InvalidConfig(String),
+    /// Fewer than `required` endpoints returned the same response.
+    NoQuorum { required: usize, agreeing: usize },
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::Http(err) => write!(f, "{err}"),
+            TransportError::Status { status, .. } => write!(f, "HTTP status {status}"),
+            TransportError::WebSocket(err) => write!(f, "websocket error: {err}"),
+            TransportError::Closed => write!(f, "connection closed"),
+            TransportError::InvalidConfig(message) => {
+                write!(f, "invalid transport configuration: {message}")
+            }
+            TransportError::NoQuorum { required, agreeing } => write!(
+                f,
+                "no quorum: {agreeing} endpoints agreed, {required} required"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(err: reqwest::Error) -> Self {
+        TransportError::Http(err)
+    }
+}
+
+impl From<tungstenite::Error> for TransportError {
+    fn from(err: tungstenite::Error) -> Self {
+        TransportError::WebSocket(Box::new(err))
+    }
+}
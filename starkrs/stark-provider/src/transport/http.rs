@@ -0,0 +1,272 @@
+use super::{Transport, TransportError};
+use crate::rpc::ProviderError;
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, RETRY_AFTER};
+use reqwest::{Client, Proxy};
+use serde_json::Value;
+use std::time::{Duration, SystemTime};
+use url::Url;
+
+/// Posts every request to a single HTTP endpoint.
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    url: Url,
+    client: Client,
+}
+
+impl HttpTransport {
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            client: Client::new(),
+        }
+    }
+
+    /// Starts an [`HttpTransportBuilder`] for endpoints that need headers, auth, timeouts or a
+    /// proxy.
+    pub fn builder(url: Url) -> HttpTransportBuilder {
+        HttpTransportBuilder {
+            url,
+            headers: Vec::new(),
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+        }
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError> {
+        let response = self
+            .client
+            .post(self.url.clone())
+            .json(&request)
+            .send()
+            .await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response.json().await?);
+        }
+
+        // Some nodes pair JSON-RPC errors with a 4xx status; keep those as regular responses.
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, SystemTime::now()));
+        let body = response.bytes().await?;
+        match serde_json::from_slice::<Value>(&body) {
+            Ok(body) if body.get("result").is_some() || body.get("error").is_some() => Ok(body),
+            _ => Err(TransportError::Status {
+                status: status.as_u16(),
+                retry_after,
+            }
+            .into()),
+        }
+    }
+}
+
+/// Parses a `Retry-After` value, either delay-seconds or an HTTP-date. A date in the past
+/// means no delay.
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+pub struct HttpTransportBuilder {
+    url: Url,
+    headers: Vec<(String, String)>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+}
+
+impl HttpTransportBuilder {
+    /// Adds a header sent with every request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends `key` in the `header` header, e.g. `x-api-key`.
+    pub fn api_key(self, header: impl Into<String>, key: impl Into<String>) -> Self {
+        self.header(header, key)
+    }
+
+    pub fn bearer_auth(self, token: impl AsRef<str>) -> Self {
+        self.header(AUTHORIZATION.as_str(), format!("Bearer {}", token.as_ref()))
+    }
+
+    /// Bounds the whole request, from connecting until the response body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes every request through the proxy at `url`.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    pub fn build(self) -> Result<HttpTransport, TransportError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                TransportError::InvalidConfig(format!("invalid header name {name}"))
+            })?;
+            let mut value = HeaderValue::from_str(&value).map_err(|_| {
+                TransportError::InvalidConfig(format!("invalid value for header {name}"))
+            })?;
+            value.set_sensitive(name == AUTHORIZATION);
+            headers.insert(name, value);
+        }
+
+        let mut client = Client::builder().default_headers(headers);
+        if let Some(timeout) = self.timeout {
+            client = client.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            client = client.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            client = client.proxy(Proxy::all(proxy)?);
+        }
+
+        Ok(HttpTransport {
+            url: self.url,
+            client: client.build()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Accepts one connection, answers it with `response` and returns the raw request.
+    async fn serve_once(listener: TcpListener, response: String) -> String {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let read = socket.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|line| {
+                        line.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if request.len() >= end + 4 + length {
+                    break;
+                }
+            }
+        }
+        socket.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    }
+
+    async fn local_server() -> (TcpListener, Url) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        (listener, url)
+    }
+
+    #[tokio::test]
+    async fn sends_configured_headers() {
+        let (listener, url) = local_server().await;
+        let body = r#"{"jsonrpc":"2.0","id":1,"result":7}"#;
+        let server = tokio::spawn(serve_once(
+            listener,
+            format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            ),
+        ));
+
+        let transport = HttpTransport::builder(url)
+            .api_key("x-api-key", "secret")
+            .header("x-client", "stark-rs")
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let response = transport
+            .send(serde_json::json!({"jsonrpc":"2.0","id":1,"method":"starknet_blockNumber","params":[]}))
+            .await
+            .unwrap();
+        assert_eq!(response["result"], 7);
+
+        let request = server.await.unwrap().to_ascii_lowercase();
+        assert!(request.contains("x-api-key: secret"));
+        assert!(request.contains("x-client: stark-rs"));
+    }
+
+    #[tokio::test]
+    async fn surfaces_rate_limit_status() {
+        let (listener, url) = local_server().await;
+        tokio::spawn(serve_once(
+            listener,
+            "HTTP/1.1 429 Too Many Requests\r\nretry-after: 3\r\ncontent-length: 0\r\n\r\n"
+                .to_string(),
+        ));
+
+        let err = HttpTransport::new(url)
+            .send(serde_json::json!({"jsonrpc":"2.0","id":1,"method":"starknet_blockNumber","params":[]}))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ProviderError::Transport(TransportError::Status {
+                status: 429,
+                retry_after: Some(retry_after),
+            }) if retry_after == Duration::from_secs(3)
+        ));
+    }
+
+    #[test]
+    fn parses_both_retry_after_forms() {
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:29:30 GMT", now),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        let result = HttpTransport::builder(Url::parse("http://localhost:5050").unwrap())
+            .header("bad header", "value")
+            .build();
+        assert!(matches!(result, Err(TransportError::InvalidConfig(_))));
+    }
+}
@@ -0,0 +1,179 @@
+use super::{Transport, TransportError};
+use crate::rpc::{JsonRpcError, ProviderError};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
+
+/// Hands requests to an in-process [`MemoryServer`] over a channel, for tests and local
+/// stand-in nodes.
+#[derive(Clone)]
+pub struct MemoryTransport {
+    requests: mpsc::UnboundedSender<IncomingRequest>,
+}
+
+/// The receiving end of a [`MemoryTransport`].
+pub struct MemoryServer {
+    requests: mpsc::UnboundedReceiver<IncomingRequest>,
+}
+
+/// A request waiting for the server's response.
+pub struct IncomingRequest {
+    body: Value,
+    response: oneshot::Sender<Value>,
+}
+
+impl MemoryTransport {
+    pub fn channel() -> (MemoryTransport, MemoryServer) {
+        let (requests, receiver) = mpsc::unbounded_channel();
+        (
+            MemoryTransport { requests },
+            MemoryServer { requests: receiver },
+        )
+    }
+
+    /// Creates a transport answered by `handler` on a spawned task.
+    pub fn with_handler<F>(handler: F) -> MemoryTransport
+    where
+        F: FnMut(&Value) -> Value + Send + 'static,
+    {
+        let (transport, server) = Self::channel();
+        tokio::spawn(server.serve(handler));
+        transport
+    }
+
+    /// Creates a transport answered by `responder` on a spawned task, wrapping what it returns
+    /// in a response envelope with the request's id.
+    pub fn with_responder<F>(mut responder: F) -> MemoryTransport
+    where
+        F: FnMut(&Value) -> Result<Value, JsonRpcError> + Send + 'static,
+    {
+        Self::with_handler(move |request| match responder(request) {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": request["id"], "result": result}),
+            Err(error) => json!({"jsonrpc": "2.0", "id": request["id"], "error": error}),
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError> {
+        let (response, receiver) = oneshot::channel();
+        self.requests
+            .send(IncomingRequest {
+                body: request,
+                response,
+            })
+            .map_err(|_| TransportError::Closed)?;
+        Ok(receiver.await.map_err(|_| TransportError::Closed)?)
+    }
+}
+
+impl MemoryServer {
+    /// Waits for the next request, or `None` once every transport is dropped.
+    pub async fn next_request(&mut self) -> Option<IncomingRequest> {
+        self.requests.recv().await
+    }
+
+    /// Answers every request with `handler` until every transport is dropped.
+    pub async fn serve<F>(mut self, mut handler: F)
+    where
+        F: FnMut(&Value) -> Value,
+    {
+        while let Some(request) = self.next_request().await {
+            let response = handler(request.body());
+            request.respond(response);
+        }
+    }
+}
+
+impl IncomingRequest {
+    /// The raw request body: a single call or a batch array.
+    pub fn body(&self) -> &Value {
+        &self.body
+    }
+
+    pub fn method(&self) -> Option<&str> {
+        self.body.get("method")?.as_str()
+    }
+
+    pub fn params(&self) -> &Value {
+        &self.body["params"]
+    }
+
+    /// Sends `body` back verbatim.
+    pub fn respond(self, body: Value) {
+        let _ = self.response.send(body);
+    }
+
+    /// Answers a single call with a successful response carrying its id.
+    pub fn respond_result(self, result: Value) {
+        let body = json!({"jsonrpc": "2.0", "id": self.body["id"], "result": result});
+        self.respond(body);
+    }
+
+    /// Answers a single call with a JSON-RPC error carrying its id.
+    pub fn respond_error(self, error: JsonRpcError) {
+        let body = json!({"jsonrpc": "2.0", "id": self.body["id"], "error": error});
+        self.respond(body);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use crate::rpc::JsonRpcClient;
+
+    #[tokio::test]
+    async fn serves_a_client() {
+        let (transport, mut server) = MemoryTransport::channel();
+        let client = JsonRpcClient::new(transport);
+
+        let request = tokio::spawn(async move { client.stark_block_number().await });
+        let incoming = server.next_request().await.unwrap();
+        assert_eq!(incoming.method(), Some("starknet_blockNumber"));
+        incoming.respond_result(json!(95812));
+        assert_eq!(request.await.unwrap().unwrap(), 95812);
+    }
+
+    #[tokio::test]
+    async fn responder_results_and_errors_reach_the_caller() {
+        let client = JsonRpcClient::new(MemoryTransport::with_responder(|request| {
+            match request["method"].as_str().unwrap() {
+                "starknet_blockNumber" => Ok(json!(95812)),
+                _ => Err(JsonRpcError {
+                    code: 24,
+                    message: "Block not found".to_string(),
+                    data: None,
+                }),
+            }
+        }));
+        assert_eq!(client.stark_block_number().await.unwrap(), 95812);
+        let err = client.stark_chain_id().await.unwrap_err();
+        assert!(matches!(err, ProviderError::Rpc(err) if err.code == 24));
+    }
+
+    #[tokio::test]
+    async fn handler_errors_reach_the_caller() {
+        let client = JsonRpcClient::new(MemoryTransport::with_handler(|request| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": {"code": 24, "message": "Block not found"}
+            })
+        }));
+        let err = client.stark_chain_id().await.unwrap_err();
+        assert!(matches!(err, ProviderError::Rpc(err) if err.code == 24));
+    }
+
+    #[tokio::test]
+    async fn dropped_server_closes_the_transport() {
+        let (transport, server) = MemoryTransport::channel();
+        drop(server);
+        let err = transport.send(json!({})).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ProviderError::Transport(TransportError::Closed)
+        ));
+    }
+}
@@ -0,0 +1,21 @@
+mod error;
+mod http;
+mod memory;
+mod ws;
+
+pub use error::TransportError;
+pub use http::{HttpTransport, HttpTransportBuilder};
+pub use memory::{IncomingRequest, MemoryServer, MemoryTransport};
+pub use ws::WsTransport;
+pub(crate) use ws::{subscription_key, Notification};
+
+use crate::rpc::ProviderError;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Carries raw JSON-RPC request bodies, single calls or batch arrays, to a node and returns the
+/// raw response bodies.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError>;
+}
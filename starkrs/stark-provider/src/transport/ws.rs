@@ -0,0 +1,343 @@
+use super::{Transport, TransportError};
+use crate::rpc::ProviderError;
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+type Stream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Multiplexes requests over a single WebSocket connection.
+///
+/// Request ids are rewritten to connection-unique values on the way out and restored on the way
+/// back, so concurrent callers may use the same ids. Clones share the connection.
+///
+/// A dropped connection is re-established with backoff. Requests in flight, and requests made
+/// while reconnecting, fail with [`TransportError::Closed`]. Subscriptions are told so that they
+/// can subscribe again.
+#[derive(Clone)]
+pub struct WsTransport {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    requests: mpsc::UnboundedSender<Request>,
+    next_id: AtomicU64,
+}
+
+struct Request {
+    body: Value,
+    /// Rewritten id to the caller's id, for every call in `body`.
+    ids: HashMap<u64, Value>,
+    response: oneshot::Sender<Result<Value, TransportError>>,
+    /// Receives the notifications of the subscription this request creates.
+    subscription: Option<mpsc::UnboundedSender<Notification>>,
+}
+
+/// A message for a subscription.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Notification {
+    /// A notification for the subscription: its `method` and `params.result`.
+    Message { method: String, result: Value },
+    /// The connection was re-established; the subscription no longer exists on the node.
+    Reconnected,
+}
+
+impl WsTransport {
+    pub async fn connect(url: Url) -> Result<Self, TransportError> {
+        let (stream, _) = tokio_tungstenite::connect_async(url.as_str()).await?;
+        let (requests, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run(url, stream, receiver));
+        Ok(Self {
+            inner: Arc::new(Inner {
+                requests,
+                next_id: AtomicU64::new(1),
+            }),
+        })
+    }
+
+    /// Sends a subscribe call and routes the notifications of the subscription it creates to the
+    /// returned receiver, starting before the response is delivered.
+    pub(crate) async fn subscribe(
+        &self,
+        request: Value,
+    ) -> Result<(Value, mpsc::UnboundedReceiver<Notification>), ProviderError> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let response = self.request(request, Some(sender)).await?;
+        Ok((response, receiver))
+    }
+
+    async fn request(
+        &self,
+        mut request: Value,
+        subscription: Option<mpsc::UnboundedSender<Notification>>,
+    ) -> Result<Value, ProviderError> {
+        let calls: Vec<&mut Value> = match &mut request {
+            Value::Array(calls) => calls.iter_mut().collect(),
+            call => vec![call],
+        };
+        let mut ids = HashMap::new();
+        for call in calls {
+            if let Some(call) = call.as_object_mut() {
+                let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+                let original = call.insert("id".to_string(), id.into());
+                ids.insert(id, original.unwrap_or(Value::Null));
+            }
+        }
+        if ids.is_empty() {
+            return Err(ProviderError::Deserialize(serde::de::Error::custom(
+                "request body holds no JSON-RPC call",
+            )));
+        }
+
+        let (response, receiver) = oneshot::channel();
+        self.inner
+            .requests
+            .send(Request {
+                body: request,
+                ids,
+                response,
+                subscription,
+            })
+            .map_err(|_| TransportError::Closed)?;
+        Ok(receiver.await.map_err(|_| TransportError::Closed)??)
+    }
+}
+
+#[async_trait]
+impl Transport for WsTransport {
+    async fn send(&self, request: Value) -> Result<Value, ProviderError> {
+        self.request(request, None).await
+    }
+}
+
+/// Subscription ids are strings since RPC 0.8 and integers before.
+pub(crate) fn subscription_key(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+#[derive(Default)]
+struct Routes {
+    /// Keyed by the lowest rewritten id of each request.
+    waiting: HashMap<u64, Request>,
+    subscriptions: HashMap<String, mpsc::UnboundedSender<Notification>>,
+}
+
+/// Owns the connection: writes queued requests, routes responses and notifications, and
+/// reconnects when the connection drops. Ends once every [`WsTransport`] clone is dropped.
+async fn run(url: Url, mut stream: Stream, mut requests: mpsc::UnboundedReceiver<Request>) {
+    let mut routes = Routes::default();
+    loop {
+        if !serve(&mut stream, &mut requests, &mut routes).await {
+            return;
+        }
+        for (_, request) in routes.waiting.drain() {
+            let _ = request.response.send(Err(TransportError::Closed));
+        }
+
+        let Some(reconnected) = reconnect(&url, &mut requests).await else {
+            return;
+        };
+        stream = reconnected;
+        for (_, subscription) in routes.subscriptions.drain() {
+            let _ = subscription.send(Notification::Reconnected);
+        }
+    }
+}
+
+/// Serves the connection until it drops, returning `true`, or until every transport is gone,
+/// returning `false`.
+async fn serve(
+    stream: &mut Stream,
+    requests: &mut mpsc::UnboundedReceiver<Request>,
+    routes: &mut Routes,
+) -> bool {
+    loop {
+        tokio::select! {
+            request = requests.recv() => {
+                let Some(request) = request else { return false };
+                let key = *request.ids.keys().min().unwrap();
+                match stream.send(Message::Text(request.body.to_string())).await {
+                    Ok(()) => {
+                        routes.waiting.insert(key, request);
+                    }
+                    Err(err) => {
+                        let _ = request.response.send(Err(err.into()));
+                        return true;
+                    }
+                }
+            }
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => dispatch(routes, text.as_bytes()),
+                Some(Ok(Message::Binary(data))) => dispatch(routes, &data),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return true,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+}
+
+/// Connects again with exponential backoff, failing requests made in the meantime. Returns
+/// `None` once every transport is gone.
+async fn reconnect(url: &Url, requests: &mut mpsc::UnboundedReceiver<Request>) -> Option<Stream> {
+    let mut delay = Duration::ZERO;
+    loop {
+        let sleep = tokio::time::sleep(delay);
+        tokio::pin!(sleep);
+        loop {
+            tokio::select! {
+                _ = &mut sleep => break,
+                request = requests.recv() => {
+                    let request = request?;
+                    let _ = request.response.send(Err(TransportError::Closed));
+                }
+            }
+        }
+        if let Ok((stream, _)) = tokio_tungstenite::connect_async(url.as_str()).await {
+            return Some(stream);
+        }
+        delay = (delay * 2)
+            .max(Duration::from_millis(250))
+            .min(MAX_RECONNECT_DELAY);
+    }
+}
+
+fn dispatch(routes: &mut Routes, message: &[u8]) {
+    let Ok(mut response) = serde_json::from_slice::<Value>(message) else {
+        return;
+    };
+    if response.get("method").is_some() && response.get("id").is_none() {
+        return notify(routes, response);
+    }
+
+    let key = match &response {
+        Value::Array(responses) => responses
+            .iter()
+            .filter_map(response_id)
+            .find(|id| routes.waiting.contains_key(id)),
+        response => response_id(response),
+    };
+    let Some(request) = key.and_then(|key| routes.waiting.remove(&key)) else {
+        return;
+    };
+
+    if let (Some(subscription), Some(id)) = (request.subscription, response.get("result")) {
+        routes
+            .subscriptions
+            .insert(subscription_key(id), subscription);
+    }
+    let responses: Vec<&mut Value> = match &mut response {
+        Value::Array(responses) => responses.iter_mut().collect(),
+        response => vec![response],
+    };
+    for response in responses {
+        let original = response_id(response).and_then(|id| request.ids.get(&id).cloned());
+        if let Some(original) = original {
+            response["id"] = original;
+        }
+    }
+    let _ = request.response.send(Ok(response));
+}
+
+fn notify(routes: &mut Routes, mut notification: Value) {
+    let key = subscription_key(&notification["params"]["subscription_id"]);
+    let Some(subscription) = routes.subscriptions.get(&key) else {
+        return;
+    };
+    let message = Notification::Message {
+        method: notification["method"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        result: notification["params"]["result"].take(),
+    };
+    // The subscriber is gone; stop routing to it.
+    if subscription.send(message).is_err() {
+        routes.subscriptions.remove(&key);
+    }
+}
+
+fn response_id(response: &Value) -> Option<u64> {
+    response.get("id")?.as_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    /// Answers every call with its own id as the result, replying in reverse arrival order once
+    /// `batch` messages have arrived.
+    async fn echo_server(batch: usize) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("ws://{}", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut stream = tokio_tungstenite::accept_async(socket).await.unwrap();
+            let mut received = Vec::new();
+            while let Some(Ok(Message::Text(text))) = stream.next().await {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                received.push(match request {
+                    Value::Array(calls) => Value::Array(
+                        calls
+                            .iter()
+                            .map(
+                                |call| json!({"jsonrpc":"2.0","id":call["id"],"result":call["id"]}),
+                            )
+                            .collect(),
+                    ),
+                    call => json!({"jsonrpc":"2.0","id":call["id"],"result":call["id"]}),
+                });
+                if received.len() == batch {
+                    for response in received.drain(..).rev() {
+                        stream
+                            .send(Message::Text(response.to_string()))
+                            .await
+                            .unwrap();
+                    }
+                }
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn routes_out_of_order_responses() {
+        let transport = WsTransport::connect(echo_server(2).await).await.unwrap();
+        let call = json!({"jsonrpc":"2.0","id":1,"method":"starknet_blockNumber","params":[]});
+
+        let (first, second) = tokio::join!(transport.send(call.clone()), transport.send(call));
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_eq!(first["id"], 1);
+        assert_eq!(second["id"], 1);
+        assert_ne!(first["result"], second["result"]);
+    }
+
+    #[tokio::test]
+    async fn restores_ids_in_batches() {
+        let transport = WsTransport::connect(echo_server(1).await).await.unwrap();
+        let response = transport
+            .send(json!([
+                {"jsonrpc":"2.0","id":"a","method":"starknet_blockNumber","params":[]},
+                {"jsonrpc":"2.0","id":"b","method":"starknet_chainId","params":[]}
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(response[0]["id"], "a");
+        assert_eq!(response[1]["id"], "b");
+    }
+}
@@ -0,0 +1,418 @@
+//! Block and event streams built on polling, for nodes without WebSocket support.
+//!
+//! The watchers keep the hashes of the most recent blocks and compare each new block's parent
+//! hash against them. On a mismatch the replaced blocks are reported as
+//! [`ChainUpdate::Reverted`], newest first, before the blocks of the new chain.
+
+use crate::provider::Provider;
+use crate::rpc::ProviderError;
+use futures::stream::{self, BoxStream, Stream};
+use stark_core::types::block::{Block, BlockHashAndNumber, MaybePendingBlock};
+use stark_core::types::event::{EmittedEvent, EventFilter};
+use stark_core::types::request::{BlockNumber, BlockTag};
+use stark_core::Felt;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// `BLOCK_NOT_FOUND`, returned while the next block has not been produced yet.
+const BLOCK_NOT_FOUND: i64 = 24;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChainUpdate<T> {
+    New(T),
+    /// A block that is no longer part of the chain; everything received from it must be rolled
+    /// back.
+    Reverted(BlockHashAndNumber),
+}
+
+/// Follows the canonical chain one change at a time.
+struct Chain<'a, P> {
+    provider: &'a P,
+    interval: Duration,
+    depth: usize,
+    /// The most recent blocks of the chain, oldest first.
+    window: VecDeque<BlockHashAndNumber>,
+    /// The next block to fetch, or `None` to start from the latest block.
+    next: Option<u64>,
+    /// Set after an error so that the next poll waits before retrying.
+    failed: bool,
+}
+
+impl<'a, P: Provider> Chain<'a, P> {
+    fn new(provider: &'a P, interval: Duration, depth: usize) -> Self {
+        Self {
+            provider,
+            interval,
+            depth: depth.max(1),
+            window: VecDeque::new(),
+            next: None,
+            failed: false,
+        }
+    }
+
+    /// Waits for the next block, or for the current tip to be reverted. A reorg deeper than the
+    /// window is only reported for the blocks still in the window.
+    async fn next(&mut self) -> Result<ChainUpdate<Block<Felt>>, ProviderError> {
+        if std::mem::take(&mut self.failed) {
+            tokio::time::sleep(self.interval).await;
+        }
+        let result = self.poll().await;
+        self.failed = result.is_err();
+        result
+    }
+
+    async fn poll(&mut self) -> Result<ChainUpdate<Block<Felt>>, ProviderError> {
+        loop {
+            let block_id = match self.next {
+                Some(number) => BlockNumber::Number(number),
+                None => BlockNumber::BlockTag(BlockTag::Latest),
+            };
+            let block = match self.provider.get_block_with_tx_hashes(block_id).await {
+                Ok(MaybePendingBlock::Block(block)) => block,
+                Ok(MaybePendingBlock::Pending(_)) => {
+                    return Err(ProviderError::Deserialize(serde::de::Error::custom(
+                        "expected an accepted block, got the pending block",
+                    )))
+                }
+                Err(ProviderError::Rpc(err)) if err.code == BLOCK_NOT_FOUND => {
+                    tokio::time::sleep(self.interval).await;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            let header = &block.header;
+            if let Some(tip) = self.window.back() {
+                if tip.block_hash != header.parent_hash {
+                    return Ok(ChainUpdate::Reverted(self.unwind()));
+                }
+            }
+            self.window.push_back(BlockHashAndNumber {
+                block_hash: header.block_hash,
+                block_number: header.block_number,
+            });
+            if self.window.len() > self.depth {
+                self.window.pop_front();
+            }
+            self.next = Some(header.block_number + 1);
+            return Ok(ChainUpdate::New(block));
+        }
+    }
+
+    /// Drops the tip so that its height is fetched again.
+    fn unwind(&mut self) -> BlockHashAndNumber {
+        let tip = self.window.pop_back().expect("unwind with an empty window");
+        self.next = Some(tip.block_number);
+        tip
+    }
+}
+
+/// A stream of the chain's blocks as returned by `starknet_getBlockWithTxHashes`, starting at the
+/// latest block. Errors are yielded and polling resumes on the next poll.
+pub struct BlockWatcher<'a, P> {
+    provider: &'a P,
+    interval: Duration,
+    depth: usize,
+    stream: Option<BoxStream<'a, Result<ChainUpdate<Block<Felt>>, ProviderError>>>,
+}
+
+impl<'a, P: Provider> BlockWatcher<'a, P> {
+    pub fn new(provider: &'a P) -> Self {
+        Self {
+            provider,
+            interval: DEFAULT_INTERVAL,
+            depth: DEFAULT_DEPTH,
+            stream: None,
+        }
+    }
+
+    /// Sets the delay between polls while waiting for a new block.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets how many recent blocks are remembered, bounding the reorg depth that is detected.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+impl<'a, P: Provider> Stream for BlockWatcher<'a, P> {
+    type Item = Result<ChainUpdate<Block<Felt>>, ProviderError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let (provider, interval, depth) = (self.provider, self.interval, self.depth);
+        let stream = self.stream.get_or_insert_with(|| {
+            let chain = Chain::new(provider, interval, depth);
+            Box::pin(stream::unfold(chain, |mut chain| async move {
+                let update = chain.next().await;
+                Some((update, chain))
+            }))
+        });
+        stream.as_mut().poll_next(cx)
+    }
+}
+
+/// A stream of the events matching a filter, read from the receipts of each new block starting
+/// at the latest one. The filter's block bounds are ignored. A reverted block is reported whether
+/// or not it had matching events.
+pub struct EventWatcher<'a, P> {
+    provider: &'a P,
+    filter: EventFilter,
+    interval: Duration,
+    depth: usize,
+    stream: Option<BoxStream<'a, Result<ChainUpdate<EmittedEvent>, ProviderError>>>,
+}
+
+impl<'a, P: Provider> EventWatcher<'a, P> {
+    pub fn new(provider: &'a P, filter: EventFilter) -> Self {
+        Self {
+            provider,
+            filter,
+            interval: DEFAULT_INTERVAL,
+            depth: DEFAULT_DEPTH,
+            stream: None,
+        }
+    }
+
+    /// Sets the delay between polls while waiting for a new block.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets how many recent blocks are remembered, bounding the reorg depth that is detected.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+struct EventState<'a, P> {
+    chain: Chain<'a, P>,
+    filter: EventFilter,
+    queue: VecDeque<EmittedEvent>,
+}
+
+impl<'a, P: Provider> EventState<'a, P> {
+    async fn next(&mut self) -> Result<ChainUpdate<EmittedEvent>, ProviderError> {
+        loop {
+            if let Some(event) = self.queue.pop_front() {
+                return Ok(ChainUpdate::New(event));
+            }
+            let block = match self.chain.next().await? {
+                ChainUpdate::New(block) => block,
+                ChainUpdate::Reverted(block) => return Ok(ChainUpdate::Reverted(block)),
+            };
+            let receipts = futures::future::try_join_all(
+                block
+                    .transactions
+                    .iter()
+                    .map(|hash| self.chain.provider.get_transaction_receipt(*hash)),
+            )
+            .await;
+            let receipts = match receipts {
+                Ok(receipts) => receipts,
+                Err(err) => {
+                    // Fetch the block again so that its events are not skipped.
+                    self.chain.unwind();
+                    return Err(err);
+                }
+            };
+            for receipt in receipts {
+                for event in receipt.events {
+                    if self.filter.matches(event.from_address, &event.keys) {
+                        self.queue.push_back(EmittedEvent {
+                            from_address: event.from_address,
+                            keys: event.keys,
+                            data: event.data,
+                            block_hash: Some(block.header.block_hash),
+                            block_number: Some(block.header.block_number),
+                            transaction_hash: receipt.transaction_hash,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a, P: Provider> Stream for EventWatcher<'a, P> {
+    type Item = Result<ChainUpdate<EmittedEvent>, ProviderError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let (provider, interval, depth) = (self.provider, self.interval, self.depth);
+        let filter = self.filter.clone();
+        let stream = self.stream.get_or_insert_with(|| {
+            let state = EventState {
+                chain: Chain::new(provider, interval, depth),
+                filter,
+                queue: VecDeque::new(),
+            };
+            Box::pin(stream::unfold(state, |mut state| async move {
+                let update = state.next().await;
+                Some((update, state))
+            }))
+        });
+        stream.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::JsonRpcClient;
+    use crate::transport::MemoryTransport;
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    /// A chain of `(hash, parent hash, transaction hashes)`, indexed by block number.
+    type Blocks = Arc<Mutex<Vec<(u64, u64, Vec<u64>)>>>;
+
+    fn node(blocks: Blocks) -> JsonRpcClient<MemoryTransport> {
+        JsonRpcClient::new(MemoryTransport::with_handler(move |request| {
+            let blocks = blocks.lock().unwrap();
+            let params = &request["params"];
+            let result = match request["method"].as_str().unwrap() {
+                "starknet_getBlockWithTxHashes" => {
+                    let number = match params[0]["block_number"].as_u64() {
+                        Some(number) => number as usize,
+                        None => blocks.len() - 1,
+                    };
+                    blocks.get(number).map(|(hash, parent, transactions)| {
+                        let price = json!({"price_in_fri": "0x1", "price_in_wei": "0x1"});
+                        json!({
+                            "status": "ACCEPTED_ON_L2",
+                            "block_hash": Felt::from(*hash),
+                            "parent_hash": Felt::from(*parent),
+                            "block_number": number,
+                            "new_root": "0x1",
+                            "timestamp": 1_700_000_000 + number,
+                            "sequencer_address": "0x1",
+                            "l1_gas_price": price,
+                            "l1_data_gas_price": price,
+                            "l1_da_mode": "BLOB",
+                            "starknet_version": "0.13.2",
+                            "transactions": transactions
+                                .iter()
+                                .map(|hash| Felt::from(*hash))
+                                .collect::<Vec<_>>(),
+                        })
+                    })
+                }
+                "starknet_getTransactionReceipt" => Some(json!({
+                    "type": "INVOKE",
+                    "transaction_hash": params[0],
+                    "actual_fee": {"amount": "0x1", "unit": "FRI"},
+                    "execution_status": "SUCCEEDED",
+                    "finality_status": "ACCEPTED_ON_L2",
+                    "messages_sent": [],
+                    "events": [
+                        {"from_address": "0x5", "keys": ["0x9", params[0]], "data": ["0x1"]},
+                        {"from_address": "0x6", "keys": ["0x9"], "data": []},
+                    ],
+                })),
+                method => panic!("unexpected method {method}"),
+            };
+            match result {
+                Some(result) => json!({"jsonrpc": "2.0", "id": request["id"], "result": result}),
+                None => json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "error": {"code": BLOCK_NOT_FOUND, "message": "Block not found"},
+                }),
+            }
+        }))
+    }
+
+    fn block(hash: u64, block_number: u64) -> BlockHashAndNumber {
+        BlockHashAndNumber {
+            block_hash: Felt::from(hash),
+            block_number,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reports_reverted_blocks_before_the_new_chain() {
+        let blocks: Blocks = Arc::new(Mutex::new(vec![(0xa0, 0, vec![]), (0xa1, 0xa0, vec![])]));
+        let client = node(blocks.clone());
+        let mut watcher = client.watch_blocks();
+
+        let hash = |update: ChainUpdate<Block<Felt>>| match update {
+            ChainUpdate::New(block) => block.header.block_hash,
+            update => panic!("unexpected {update:?}"),
+        };
+        assert_eq!(
+            hash(watcher.next().await.unwrap().unwrap()),
+            Felt::from(0xa1u64)
+        );
+
+        // Block 1 is replaced while block 2 is produced.
+        *blocks.lock().unwrap() = vec![
+            (0xa0, 0, vec![]),
+            (0xb1, 0xa0, vec![]),
+            (0xb2, 0xb1, vec![]),
+        ];
+        assert_eq!(
+            watcher.next().await.unwrap().unwrap(),
+            ChainUpdate::Reverted(block(0xa1, 1))
+        );
+        assert_eq!(
+            hash(watcher.next().await.unwrap().unwrap()),
+            Felt::from(0xb1u64)
+        );
+        assert_eq!(
+            hash(watcher.next().await.unwrap().unwrap()),
+            Felt::from(0xb2u64)
+        );
+
+        // Waits for block 3.
+        let waiting = tokio::time::timeout(Duration::from_secs(10), watcher.next()).await;
+        assert!(waiting.is_err());
+        blocks.lock().unwrap().push((0xb3, 0xb2, vec![]));
+        assert_eq!(
+            hash(watcher.next().await.unwrap().unwrap()),
+            Felt::from(0xb3u64)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn yields_matching_events_and_reverts() {
+        let blocks: Blocks = Arc::new(Mutex::new(vec![(0xa0, 0, vec![0x11, 0x12])]));
+        let client = node(blocks.clone());
+        let filter = EventFilter {
+            address: Some(Felt::from(5u64)),
+            keys: vec![vec![], vec![Felt::from(0x12u64), Felt::from(0x21u64)]],
+            ..Default::default()
+        };
+        let mut watcher = client.watch_events(filter);
+
+        let event = match watcher.next().await.unwrap().unwrap() {
+            ChainUpdate::New(event) => event,
+            update => panic!("unexpected {update:?}"),
+        };
+        assert_eq!(event.transaction_hash, Felt::from(0x12u64));
+        assert_eq!(event.block_hash, Some(Felt::from(0xa0u64)));
+        assert_eq!(event.block_number, Some(0));
+
+        *blocks.lock().unwrap() = vec![(0xb0, 0, vec![]), (0xb1, 0xb0, vec![0x21])];
+        assert_eq!(
+            watcher.next().await.unwrap().unwrap(),
+            ChainUpdate::Reverted(block(0xa0, 0))
+        );
+        match watcher.next().await.unwrap().unwrap() {
+            ChainUpdate::New(event) => {
+                assert_eq!(event.transaction_hash, Felt::from(0x21u64));
+                assert_eq!(event.block_number, Some(1));
+            }
+            update => panic!("unexpected {update:?}"),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// The messaging half of StarknetCore, with a hook standing in for the state updates that prove
+/// messages from L2.
+contract MockStarknetMessaging {
+    event LogMessageToL2(
+        address indexed fromAddress,
+        uint256 indexed toAddress,
+        uint256 indexed selector,
+        uint256[] payload,
+        uint256 nonce,
+        uint256 fee
+    );
+    event ConsumedMessageToL1(uint256 indexed fromAddress, address indexed toAddress, uint256[] payload);
+
+    mapping(bytes32 => uint256) public l1ToL2Messages;
+    mapping(bytes32 => uint256) public l2ToL1Messages;
+    uint256 public l1ToL2MessageNonce;
+
+    function sendMessageToL2(uint256 toAddress, uint256 selector, uint256[] calldata payload)
+        external
+        payable
+        returns (bytes32, uint256)
+    {
+        require(msg.value > 0, "L1_MSG_FEE_MUST_BE_GREATER_THAN_0");
+        uint256 nonce = l1ToL2MessageNonce++;
+        emit LogMessageToL2(msg.sender, toAddress, selector, payload, nonce, msg.value);
+        bytes32 msgHash = keccak256(
+            abi.encodePacked(uint256(uint160(msg.sender)), toAddress, nonce, selector, payload.length, payload)
+        );
+        l1ToL2Messages[msgHash] = msg.value + 1;
+        return (msgHash, nonce);
+    }
+
+    function mockSendMessageFromL2(uint256 fromAddress, uint256 toAddress, uint256[] calldata payload) external {
+        bytes32 msgHash = keccak256(abi.encodePacked(fromAddress, toAddress, payload.length, payload));
+        l2ToL1Messages[msgHash] += 1;
+    }
+
+    function consumeMessageFromL2(uint256 fromAddress, uint256[] calldata payload) external returns (bytes32) {
+        bytes32 msgHash = keccak256(
+            abi.encodePacked(fromAddress, uint256(uint160(msg.sender)), payload.length, payload)
+        );
+        require(l2ToL1Messages[msgHash] > 0, "INVALID_MESSAGE_TO_CONSUME");
+        emit ConsumedMessageToL1(fromAddress, msg.sender, payload);
+        l2ToL1Messages[msgHash] -= 1;
+        return msgHash;
+    }
+}
@@ -0,0 +1,623 @@
+use super::error::CliError;
+use super::keystore::KeystoreArgs;
+use super::write::{FeeArgs, WaitArgs};
+use clap::{Args, Subcommand};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use stark_accounts::deployment::OZ_ACCOUNT_CLASS_HASH;
+use stark_accounts::{AccountDeployment, LocalWallet, SingleOwnerAccount};
+use stark_core::types::receipt::{TransactionExecutionStatus, TransactionFinalityStatus};
+use stark_core::types::request::{BlockNumber, BlockTag, FunctionCall};
+use stark_core::utils::hash::{calculate_contract_address, get_selector_from_name};
+use stark_core::Felt;
+use stark_provider::pending::{PendingTransactionError, TXN_HASH_NOT_FOUND};
+use stark_provider::rpc::ProviderError;
+use stark_provider::Provider;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const DESCRIPTOR_VERSION: u64 = 1;
+
+/// What `starkrs` needs to know about an account, stored as JSON next to its keystore.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDescriptor {
+    pub version: u64,
+    pub address: Felt,
+    pub class_hash: Felt,
+    pub public_key: Felt,
+    /// The deployment salt; unknown for fetched accounts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<Felt>,
+    pub deployment: Deployment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Deployment {
+    Undeployed,
+    /// The deploy transaction was sent but no receipt has settled it yet.
+    Pending {
+        transaction_hash: Felt,
+    },
+    Deployed {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        transaction_hash: Option<Felt>,
+    },
+}
+
+impl AccountDescriptor {
+    pub fn load(path: &Path) -> Result<Self, CliError> {
+        let descriptor: Self = super::write::read_json(path)?;
+        if descriptor.version != DESCRIPTOR_VERSION {
+            return Err(CliError::Config(format!(
+                "{}: unsupported descriptor version {}",
+                path.display(),
+                descriptor.version
+            )));
+        }
+        Ok(descriptor)
+    }
+
+    pub fn save(&self, path: &Path, overwrite: bool) -> Result<(), CliError> {
+        if !overwrite && path.exists() {
+            return Err(CliError::Config(format!(
+                "{} already exists",
+                path.display()
+            )));
+        }
+        let mut contents = serde_json::to_string_pretty(self)?;
+        contents.push('\n');
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Resolves a pending deployment from the node's view of its transaction, saving the
+    /// descriptor when it changes.
+    async fn settle<P: Provider>(&mut self, provider: &P, path: &Path) -> Result<(), CliError> {
+        let Deployment::Pending { transaction_hash } = self.deployment else {
+            return Ok(());
+        };
+        let status = match provider.get_transaction_status(transaction_hash).await {
+            Ok(status) => status,
+            Err(ProviderError::Rpc(err)) if err.code == TXN_HASH_NOT_FOUND => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        self.deployment = match (status.finality_status, status.execution_status) {
+            (TransactionFinalityStatus::Rejected, _)
+            | (_, Some(TransactionExecutionStatus::Reverted)) => Deployment::Undeployed,
+            (TransactionFinalityStatus::Received, _) | (_, None) => return Ok(()),
+            (_, Some(TransactionExecutionStatus::Succeeded)) => Deployment::Deployed {
+                transaction_hash: Some(transaction_hash),
+            },
+        };
+        self.save(path, true)
+    }
+}
+
+/// The account a command is sent from.
+#[derive(Debug, Clone, Args)]
+pub struct AccountArgs {
+    /// The account descriptor written by `account init` or `account fetch`.
+    #[arg(long, env = "STARKRS_ACCOUNT")]
+    pub account: PathBuf,
+
+    #[command(flatten)]
+    pub keystore: KeystoreArgs,
+}
+
+impl AccountArgs {
+    pub async fn load<P: Provider>(
+        &self,
+        provider: Arc<P>,
+    ) -> Result<SingleOwnerAccount<P, LocalWallet>, CliError> {
+        let mut descriptor = AccountDescriptor::load(&self.account)?;
+        descriptor.settle(provider.as_ref(), &self.account).await?;
+        match descriptor.deployment {
+            Deployment::Deployed { .. } => {}
+            Deployment::Undeployed => {
+                return Err(CliError::Config(format!(
+                    "account {} is not deployed yet; run `starkrs account deploy` first",
+                    descriptor.address
+                )))
+            }
+            Deployment::Pending { transaction_hash } => {
+                return Err(CliError::Config(format!(
+                    "the deployment of account {} in {transaction_hash} is not accepted yet",
+                    descriptor.address
+                )))
+            }
+        }
+        let signer = self.keystore.signer()?;
+        check_key(&descriptor, &signer)?;
+        let chain_id = provider.stark_chain_id().await?;
+        Ok(SingleOwnerAccount::new(
+            provider,
+            signer,
+            descriptor.address,
+            chain_id,
+        ))
+    }
+}
+
+fn check_key(descriptor: &AccountDescriptor, signer: &LocalWallet) -> Result<(), CliError> {
+    if signer.public_key() == descriptor.public_key {
+        Ok(())
+    } else {
+        Err(CliError::Config(format!(
+            "the keystore does not hold the key of account {}",
+            descriptor.address
+        )))
+    }
+}
+
+/// Commands that create, deploy and import accounts.
+#[derive(Debug, Clone, Subcommand)]
+pub enum AccountCommand {
+    /// Writes the descriptor of a new OpenZeppelin account controlled by a keystore's key.
+    Init(InitArgs),
+    /// Deploys an initialized account. Fund its address with the fee token first.
+    Deploy {
+        /// The descriptor written by `account init`.
+        descriptor: PathBuf,
+        #[command(flatten)]
+        keystore: KeystoreArgs,
+        #[command(flatten)]
+        fee: FeeArgs,
+        #[command(flatten)]
+        wait: WaitArgs,
+        /// Send a new deploy transaction even though one is pending, for when the node dropped
+        /// the previous one.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Writes the descriptor of an account that is already deployed.
+    Fetch {
+        address: Felt,
+        /// Where to write the descriptor.
+        #[arg(long)]
+        output: PathBuf,
+        /// Replace an existing descriptor.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct InitArgs {
+    /// Where to write the descriptor.
+    pub output: PathBuf,
+    #[command(flatten)]
+    pub keystore: KeystoreArgs,
+    /// The account class to deploy.
+    #[arg(long, default_value = OZ_ACCOUNT_CLASS_HASH)]
+    pub class_hash: Felt,
+    /// The deployment salt; random when not given.
+    #[arg(long)]
+    pub salt: Option<Felt>,
+    /// Replace an existing descriptor.
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Creates a descriptor without talking to a node.
+pub fn init(args: InitArgs) -> Result<Value, CliError> {
+    let public_key = args.keystore.signer()?.public_key();
+    let salt = args.salt.unwrap_or_else(super::keystore::random_key);
+    let descriptor = AccountDescriptor {
+        version: DESCRIPTOR_VERSION,
+        address: calculate_contract_address(salt, args.class_hash, &[public_key], Felt::ZERO),
+        class_hash: args.class_hash,
+        public_key,
+        salt: Some(salt),
+        deployment: Deployment::Undeployed,
+    };
+    descriptor.save(&args.output, args.force)?;
+    Ok(json!({"address": descriptor.address, "descriptor": args.output}))
+}
+
+pub async fn execute<P: Provider>(
+    provider: Arc<P>,
+    command: AccountCommand,
+) -> Result<Value, CliError> {
+    match command {
+        AccountCommand::Init(args) => init(args),
+        AccountCommand::Deploy {
+            descriptor: path,
+            keystore,
+            fee,
+            wait,
+            force,
+        } => {
+            let mut descriptor = AccountDescriptor::load(&path)?;
+            descriptor.settle(provider.as_ref(), &path).await?;
+            let salt = match (&descriptor.deployment, descriptor.salt) {
+                (Deployment::Deployed { .. }, _) => {
+                    return Err(CliError::Config(format!(
+                        "account {} is already deployed",
+                        descriptor.address
+                    )))
+                }
+                (Deployment::Pending { transaction_hash }, _) if !force => {
+                    return Err(CliError::Config(format!(
+                        "account {} is already being deployed in {transaction_hash}; \
+                         pass --force to deploy it again if the node dropped that transaction",
+                        descriptor.address
+                    )))
+                }
+                (_, Some(salt)) => salt,
+                (_, None) => {
+                    return Err(CliError::Config(format!(
+                        "{}: an undeployed account needs a salt",
+                        path.display()
+                    )))
+                }
+            };
+            let signer = keystore.signer()?;
+            check_key(&descriptor, &signer)?;
+            let chain_id = provider.stark_chain_id().await?;
+            let mut deployment = AccountDeployment::new(
+                provider.as_ref(),
+                &signer,
+                chain_id,
+                descriptor.class_hash,
+                salt,
+                vec![descriptor.public_key],
+            )
+            .fee_token(fee.fee_token()?);
+            if let Some(max_fee) = fee.max_fee {
+                deployment = deployment.max_fee(max_fee);
+            }
+            let pending = deployment.send().await?;
+            let transaction_hash = pending.transaction_hash();
+            descriptor.deployment = Deployment::Pending { transaction_hash };
+            // The transaction is out; failing here would hide its hash from the caller.
+            if let Err(err) = descriptor.save(&path, true) {
+                eprintln!("warning: could not update {}: {err}", path.display());
+            }
+            let result = json!({
+                "transaction_hash": transaction_hash,
+                "address": descriptor.address,
+            });
+            if wait.no_wait {
+                return Ok(result);
+            }
+            // Only a receipt settles the deployment; on any other error the transaction may
+            // still be accepted, so the descriptor stays pending.
+            let (result, deployment) = match wait.finish(pending, result).await {
+                Ok(result) => (
+                    Ok(result),
+                    Deployment::Deployed {
+                        transaction_hash: Some(transaction_hash),
+                    },
+                ),
+                Err(
+                    err @ CliError::Pending(
+                        PendingTransactionError::Rejected { .. }
+                        | PendingTransactionError::Reverted { .. },
+                    ),
+                ) => (Err(err), Deployment::Undeployed),
+                Err(err) => return Err(err),
+            };
+            descriptor.deployment = deployment;
+            match (result, descriptor.save(&path, true)) {
+                (result, Ok(())) => result,
+                (Ok(_), Err(err)) => Err(err),
+                (Err(err), Err(save_err)) => {
+                    eprintln!("warning: could not update {}: {save_err}", path.display());
+                    Err(err)
+                }
+            }
+        }
+        AccountCommand::Fetch {
+            address,
+            output,
+            force,
+        } => {
+            let block_id = BlockNumber::BlockTag(BlockTag::Pending);
+            let class_hash = provider
+                .get_class_hash_at(block_id.clone(), address)
+                .await?;
+            let descriptor = AccountDescriptor {
+                version: DESCRIPTOR_VERSION,
+                address,
+                class_hash,
+                public_key: public_key(provider.as_ref(), address, block_id).await?,
+                salt: None,
+                deployment: Deployment::Deployed {
+                    transaction_hash: None,
+                },
+            };
+            descriptor.save(&output, force)?;
+            Ok(json!({"address": address, "class_hash": class_hash, "descriptor": output}))
+        }
+    }
+}
+
+/// Reads an account's public key through the getter of current accounts, falling back to the
+/// Cairo 0 name.
+async fn public_key<P: Provider>(
+    provider: &P,
+    address: Felt,
+    block_id: BlockNumber,
+) -> Result<Felt, CliError> {
+    let mut last_error = None;
+    for getter in ["get_public_key", "getPublicKey"] {
+        let request = FunctionCall {
+            contract_address: address,
+            entry_point_selector: get_selector_from_name(getter),
+            calldata: vec![],
+        };
+        match provider.call(request, block_id.clone()).await {
+            Ok(result) if !result.is_empty() => return Ok(result[0]),
+            Ok(_) => {}
+            Err(err) => last_error = Some(err),
+        }
+    }
+    Err(match last_error {
+        Some(err) => err.into(),
+        None => CliError::Config(format!("account {address} returned no public key")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::keystore::{self, PasswordArgs};
+    use super::*;
+    use stark_provider::rpc::{JsonRpcClient, JsonRpcError};
+    use stark_provider::transport::MemoryTransport;
+
+    fn keystore_args(dir: &Path) -> KeystoreArgs {
+        let path = dir.join("signer.json");
+        if !path.exists() {
+            keystore::create(&path, Felt::from(0x1234u64), "hunter2").unwrap();
+        }
+        KeystoreArgs {
+            keystore: path,
+            password: PasswordArgs {
+                keystore_password: Some("hunter2".to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn descriptors_serialize_their_deployment_status() {
+        let descriptor = AccountDescriptor {
+            version: 1,
+            address: Felt::from(0xau64),
+            class_hash: Felt::from(0xbu64),
+            public_key: Felt::from(0xcu64),
+            salt: None,
+            deployment: Deployment::Deployed {
+                transaction_hash: Some(Felt::from(0xdu64)),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&descriptor).unwrap(),
+            json!({
+                "version": 1,
+                "address": "0xa",
+                "class_hash": "0xb",
+                "public_key": "0xc",
+                "deployment": {"status": "deployed", "transaction_hash": "0xd"}
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn init_then_deploy_marks_the_account_deployed_once_accepted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("account.json");
+        let public_key = LocalWallet::from_private_key(Felt::from(0x1234u64)).public_key();
+        let initialized = init(InitArgs {
+            output: path.clone(),
+            keystore: keystore_args(dir.path()),
+            class_hash: Felt::from_hex(OZ_ACCOUNT_CLASS_HASH).unwrap(),
+            salt: Some(Felt::from(0x99u64)),
+            force: false,
+        })
+        .unwrap();
+        let descriptor = AccountDescriptor::load(&path).unwrap();
+        assert_eq!(descriptor.public_key, public_key);
+        assert_eq!(descriptor.deployment, Deployment::Undeployed);
+        assert_eq!(initialized["address"], json!(descriptor.address));
+
+        let provider = Arc::new(JsonRpcClient::new(MemoryTransport::with_responder(
+            move |request| {
+                let result = match request["method"].as_str().unwrap() {
+                    "starknet_chainId" => json!("0x534e5f5345504f4c4941"),
+                    "starknet_addDeployAccountTransaction" => {
+                        let tx = &request["params"][0];
+                        assert_eq!(tx["contract_address_salt"], "0x99");
+                        assert_eq!(tx["max_fee"], "0x3e8");
+                        json!({"transaction_hash": "0xabc", "contract_address": "0x1"})
+                    }
+                    "starknet_getTransactionStatus" => {
+                        assert_eq!(request["params"][0], "0xabc");
+                        json!({"finality_status": "ACCEPTED_ON_L2", "execution_status": "SUCCEEDED"})
+                    }
+                    method => panic!("unexpected {method}"),
+                };
+                Ok(result)
+            },
+        )));
+        let deploy = || AccountCommand::Deploy {
+            descriptor: path.clone(),
+            keystore: keystore_args(dir.path()),
+            fee: FeeArgs {
+                fee_token: super::super::write::FeeTokenArg::Eth,
+                max_fee: Some(Felt::from(1000u64)),
+            },
+            wait: WaitArgs { no_wait: true },
+            force: false,
+        };
+        let result = execute(provider.clone(), deploy()).await.unwrap();
+        assert_eq!(result["transaction_hash"], "0xabc");
+        assert_eq!(
+            AccountDescriptor::load(&path).unwrap().deployment,
+            Deployment::Pending {
+                transaction_hash: Felt::from(0xabcu64)
+            }
+        );
+
+        // The next command sees the accepted transaction and does not deploy again.
+        let err = execute(provider, deploy()).await.unwrap_err();
+        assert!(err.to_string().contains("already deployed"), "{err}");
+        assert_eq!(
+            AccountDescriptor::load(&path).unwrap().deployment,
+            Deployment::Deployed {
+                transaction_hash: Some(Felt::from(0xabcu64))
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn a_failed_wait_keeps_the_deployment_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("account.json");
+        init(InitArgs {
+            output: path.clone(),
+            keystore: keystore_args(dir.path()),
+            class_hash: Felt::from_hex(OZ_ACCOUNT_CLASS_HASH).unwrap(),
+            salt: Some(Felt::from(0x99u64)),
+            force: false,
+        })
+        .unwrap();
+        let provider = Arc::new(JsonRpcClient::new(MemoryTransport::with_responder(
+            move |request| match request["method"].as_str().unwrap() {
+                "starknet_chainId" => Ok(json!("0x534e5f5345504f4c4941")),
+                "starknet_addDeployAccountTransaction" => {
+                    Ok(json!({"transaction_hash": "0xabc", "contract_address": "0x1"}))
+                }
+                "starknet_getTransactionStatus" => Err(JsonRpcError {
+                    code: -32603,
+                    message: "Internal error".to_string(),
+                    data: None,
+                }),
+                method => panic!("unexpected {method}"),
+            },
+        )));
+        let err = execute(
+            provider,
+            AccountCommand::Deploy {
+                descriptor: path.clone(),
+                keystore: keystore_args(dir.path()),
+                fee: FeeArgs {
+                    fee_token: super::super::write::FeeTokenArg::Eth,
+                    max_fee: Some(Felt::from(1000u64)),
+                },
+                wait: WaitArgs { no_wait: false },
+                force: false,
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                CliError::Pending(PendingTransactionError::Provider(ProviderError::Rpc(ref rpc)))
+                    if rpc.code == -32603
+            ),
+            "{err}"
+        );
+        assert_eq!(
+            AccountDescriptor::load(&path).unwrap().deployment,
+            Deployment::Pending {
+                transaction_hash: Felt::from(0xabcu64)
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn force_redeploys_an_account_whose_transaction_was_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("account.json");
+        init(InitArgs {
+            output: path.clone(),
+            keystore: keystore_args(dir.path()),
+            class_hash: Felt::from_hex(OZ_ACCOUNT_CLASS_HASH).unwrap(),
+            salt: Some(Felt::from(0x99u64)),
+            force: false,
+        })
+        .unwrap();
+        let mut descriptor = AccountDescriptor::load(&path).unwrap();
+        descriptor.deployment = Deployment::Pending {
+            transaction_hash: Felt::from(0xabcu64),
+        };
+        descriptor.save(&path, true).unwrap();
+
+        let provider = Arc::new(JsonRpcClient::new(MemoryTransport::with_responder(
+            move |request| match request["method"].as_str().unwrap() {
+                "starknet_chainId" => Ok(json!("0x534e5f5345504f4c4941")),
+                "starknet_getTransactionStatus" => Err(JsonRpcError {
+                    code: TXN_HASH_NOT_FOUND,
+                    message: "Transaction hash not found".to_string(),
+                    data: None,
+                }),
+                "starknet_addDeployAccountTransaction" => {
+                    assert_eq!(request["params"][0]["contract_address_salt"], "0x99");
+                    Ok(json!({"transaction_hash": "0xdef", "contract_address": "0x1"}))
+                }
+                method => panic!("unexpected {method}"),
+            },
+        )));
+        let deploy = |force| AccountCommand::Deploy {
+            descriptor: path.clone(),
+            keystore: keystore_args(dir.path()),
+            fee: FeeArgs {
+                fee_token: super::super::write::FeeTokenArg::Eth,
+                max_fee: Some(Felt::from(1000u64)),
+            },
+            wait: WaitArgs { no_wait: true },
+            force,
+        };
+        let err = execute(provider.clone(), deploy(false)).await.unwrap_err();
+        assert!(err.to_string().contains("--force"), "{err}");
+
+        let result = execute(provider, deploy(true)).await.unwrap();
+        assert_eq!(result["transaction_hash"], "0xdef");
+        assert_eq!(
+            AccountDescriptor::load(&path).unwrap().deployment,
+            Deployment::Pending {
+                transaction_hash: Felt::from(0xdefu64)
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_falls_back_to_the_cairo_0_getter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("account.json");
+        let provider = Arc::new(JsonRpcClient::new(MemoryTransport::with_responder(
+            move |request| match request["method"].as_str().unwrap() {
+                "starknet_getClassHashAt" => Ok(json!("0x5")),
+                "starknet_call" => {
+                    let selector = &request["params"][0]["entry_point_selector"];
+                    if *selector == json!(get_selector_from_name("getPublicKey")) {
+                        Ok(json!(["0x7"]))
+                    } else {
+                        Err(JsonRpcError {
+                            code: 21,
+                            message: "Entry point not found".to_string(),
+                            data: None,
+                        })
+                    }
+                }
+                method => panic!("unexpected {method}"),
+            },
+        )));
+        execute(
+            provider,
+            AccountCommand::Fetch {
+                address: Felt::from(0xau64),
+                output: path.clone(),
+                force: false,
+            },
+        )
+        .await
+        .unwrap();
+        let descriptor = AccountDescriptor::load(&path).unwrap();
+        assert_eq!(descriptor.class_hash, Felt::from(0x5u64));
+        assert_eq!(descriptor.public_key, Felt::from(0x7u64));
+        assert_eq!(descriptor.salt, None);
+    }
+}
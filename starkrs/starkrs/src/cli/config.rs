@@ -0,0 +1,172 @@
+use super::error::CliError;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// The profile used when none is named and the config sets no `default_profile`.
+const DEFAULT_PROFILE: &str = "default";
+
+/// The contents of the config file, `~/.starkrs/config.toml` unless `--config` says otherwise:
+///
+/// ```toml
+/// default_profile = "sepolia"
+///
+/// [profiles.sepolia]
+/// rpc_url = "https://starknet-sepolia.public.blastapi.io/rpc/v0_7"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// A named set of connection settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub rpc_url: Url,
+}
+
+impl Config {
+    /// Reads the config at `path`; a missing file is an empty config.
+    pub fn load(path: &Path) -> Result<Config, CliError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|err| CliError::Config(format!("{}: {err}", path.display()))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// `~/.starkrs/config.toml`, or `None` without a home directory.
+    pub fn default_path() -> Option<PathBuf> {
+        let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+        Some(PathBuf::from(home).join(".starkrs").join("config.toml"))
+    }
+}
+
+/// Where to find the node. The first of `--rpc-url`, `STARKRS_RPC_URL` and the selected profile
+/// wins.
+#[derive(Debug, Clone, Args)]
+pub struct ConnectionArgs {
+    /// The node's JSON-RPC endpoint, over http(s) or ws(s).
+    #[arg(long, env = "STARKRS_RPC_URL", global = true)]
+    pub rpc_url: Option<Url>,
+
+    /// The config profile to take the endpoint from.
+    #[arg(long, env = "STARKRS_PROFILE", global = true)]
+    pub profile: Option<String>,
+
+    /// The config file [default: ~/.starkrs/config.toml].
+    #[arg(long, env = "STARKRS_CONFIG", global = true)]
+    pub config: Option<PathBuf>,
+}
+
+impl ConnectionArgs {
+    pub fn rpc_url(&self) -> Result<Url, CliError> {
+        if let Some(url) = &self.rpc_url {
+            return Ok(url.clone());
+        }
+        let path = self
+            .config
+            .clone()
+            .or_else(Config::default_path)
+            .ok_or_else(|| {
+                CliError::Config("no RPC URL: pass --rpc-url or set STARKRS_RPC_URL".to_string())
+            })?;
+        let config = Config::load(&path)?;
+        let name = self
+            .profile
+            .as_deref()
+            .or(config.default_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE);
+        match config.profiles.get(name) {
+            Some(profile) => Ok(profile.rpc_url.clone()),
+            None if self.profile.is_some() || config.default_profile.is_some() => Err(
+                CliError::Config(format!("no profile `{name}` in {}", path.display())),
+            ),
+            None => Err(CliError::Config(format!(
+                "no RPC URL: pass --rpc-url, set STARKRS_RPC_URL or add a profile to {}",
+                path.display()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_config(contents: &str) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), contents).unwrap();
+        file
+    }
+
+    fn connection(config: &Path) -> ConnectionArgs {
+        ConnectionArgs {
+            rpc_url: None,
+            profile: None,
+            config: Some(config.to_path_buf()),
+        }
+    }
+
+    const CONFIG: &str = r#"
+default_profile = "sepolia"
+
+[profiles.sepolia]
+rpc_url = "http://sepolia.example/rpc"
+
+[profiles.mainnet]
+rpc_url = "https://mainnet.example/rpc"
+"#;
+
+    #[test]
+    fn flag_wins_over_profiles() {
+        let file = write_config(CONFIG);
+        let mut args = connection(file.path());
+        args.rpc_url = Some(Url::parse("http://localhost:5050").unwrap());
+        args.profile = Some("mainnet".to_string());
+        assert_eq!(args.rpc_url().unwrap().as_str(), "http://localhost:5050/");
+    }
+
+    #[test]
+    fn selects_named_then_default_profile() {
+        let file = write_config(CONFIG);
+        let mut args = connection(file.path());
+        assert_eq!(
+            args.rpc_url().unwrap().as_str(),
+            "http://sepolia.example/rpc"
+        );
+        args.profile = Some("mainnet".to_string());
+        assert_eq!(
+            args.rpc_url().unwrap().as_str(),
+            "https://mainnet.example/rpc"
+        );
+        args.profile = Some("devnet".to_string());
+        assert!(matches!(
+            args.rpc_url(),
+            Err(CliError::Config(message)) if message.contains("no profile `devnet`")
+        ));
+    }
+
+    #[test]
+    fn missing_config_asks_for_a_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = connection(&dir.path().join("config.toml"));
+        assert!(matches!(
+            args.rpc_url(),
+            Err(CliError::Config(message)) if message.starts_with("no RPC URL")
+        ));
+    }
+
+    #[test]
+    fn malformed_config_names_the_file() {
+        let file = write_config("[profiles.sepolia]\nrpc_url = 5\n");
+        let err = connection(file.path()).rpc_url().unwrap_err();
+        assert!(err.to_string().contains(&file.path().display().to_string()));
+    }
+}
@@ -0,0 +1,95 @@
+use eth_keystore::KeystoreError;
+use stark_accounts::{AccountError, LocalWallet, Signer};
+use stark_core::types::contract::ClassHashError;
+use stark_core::types::felt::FeltError;
+use stark_provider::pending::PendingTransactionError;
+use stark_provider::rpc::ProviderError;
+use std::fmt;
+
+/// The error of an account operation signed by a keystore signer.
+pub type WalletError = AccountError<<LocalWallet as Signer>::Error>;
+
+/// Everything a command can fail with.
+#[derive(Debug)]
+pub enum CliError {
+    /// No usable RPC URL, or an unreadable config file.
+    Config(String),
+    /// A malformed argument that clap could not check on its own.
+    InvalidArgument(String),
+    Felt(FeltError),
+    Provider(ProviderError),
+    Account(WalletError),
+    /// A submitted transaction was rejected, reverted or not accepted in time.
+    Pending(PendingTransactionError),
+    Keystore(KeystoreError),
+    ClassHash(ClassHashError),
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Config(message) => write!(f, "{message}"),
+            CliError::InvalidArgument(message) => write!(f, "invalid argument: {message}"),
+            CliError::Felt(err) => write!(f, "invalid field element: {err}"),
+            CliError::Provider(err) => write!(f, "{err}"),
+            CliError::Account(err) => write!(f, "{err}"),
+            CliError::Pending(err) => write!(f, "{err}"),
+            CliError::Keystore(err) => write!(f, "keystore: {err}"),
+            CliError::ClassHash(err) => write!(f, "cannot hash class: {err}"),
+            CliError::Io(err) => write!(f, "{err}"),
+            CliError::Json(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<FeltError> for CliError {
+    fn from(err: FeltError) -> Self {
+        CliError::Felt(err)
+    }
+}
+
+impl From<ProviderError> for CliError {
+    fn from(err: ProviderError) -> Self {
+        CliError::Provider(err)
+    }
+}
+
+impl From<WalletError> for CliError {
+    fn from(err: WalletError) -> Self {
+        CliError::Account(err)
+    }
+}
+
+impl From<PendingTransactionError> for CliError {
+    fn from(err: PendingTransactionError) -> Self {
+        CliError::Pending(err)
+    }
+}
+
+impl From<KeystoreError> for CliError {
+    fn from(err: KeystoreError) -> Self {
+        CliError::Keystore(err)
+    }
+}
+
+impl From<ClassHashError> for CliError {
+    fn from(err: ClassHashError) -> Self {
+        CliError::ClassHash(err)
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(err: std::io::Error) -> Self {
+        CliError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(err: serde_json::Error) -> Self {
+        CliError::Json(err)
+    }
+}
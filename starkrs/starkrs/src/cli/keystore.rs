@@ -0,0 +1,138 @@
+use super::error::CliError;
+use clap::Args;
+use rand::RngCore;
+use stark_accounts::LocalWallet;
+use stark_core::Felt;
+use std::path::{Path, PathBuf};
+
+/// A password-protected key file in the Ethereum keystore format (scrypt and AES-128-CTR).
+#[derive(Debug, Clone, Args)]
+pub struct KeystoreArgs {
+    /// The encrypted key file of the account's signer.
+    #[arg(long, env = "STARKRS_KEYSTORE")]
+    pub keystore: PathBuf,
+
+    #[command(flatten)]
+    pub password: PasswordArgs,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct PasswordArgs {
+    /// The keystore password; prompted for when not given.
+    #[arg(long, env = "STARKRS_KEYSTORE_PASSWORD", hide_env_values = true)]
+    pub keystore_password: Option<String>,
+}
+
+impl PasswordArgs {
+    pub fn password(&self, prompt: &str) -> Result<String, CliError> {
+        match &self.keystore_password {
+            Some(password) => Ok(password.clone()),
+            None => Ok(rpassword::prompt_password(prompt)?),
+        }
+    }
+
+    /// The password of a new keystore. A prompted password is asked for twice so that a typo
+    /// cannot lock the key away.
+    pub fn new_password(&self) -> Result<String, CliError> {
+        match &self.keystore_password {
+            Some(password) => Ok(password.clone()),
+            None => confirm_password(|prompt| rpassword::prompt_password(prompt)),
+        }
+    }
+}
+
+fn confirm_password(
+    mut prompt: impl FnMut(&str) -> std::io::Result<String>,
+) -> Result<String, CliError> {
+    let password = prompt("New keystore password: ")?;
+    if prompt("Repeat the password: ")? != password {
+        return Err(CliError::InvalidArgument(
+            "the passwords do not match".to_string(),
+        ));
+    }
+    Ok(password)
+}
+
+impl KeystoreArgs {
+    pub fn signer(&self) -> Result<LocalWallet, CliError> {
+        let password = self.password.password("Keystore password: ")?;
+        load(&self.keystore, &password)
+    }
+}
+
+/// A random private key, below the curve order.
+pub fn random_key() -> Felt {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes[0] &= 0x07;
+    Felt::from_bytes_be(&bytes).expect("251 bits fit in a field element")
+}
+
+/// Encrypts `private_key` into a new keystore at `path`.
+pub fn create(path: &Path, private_key: Felt, password: &str) -> Result<(), CliError> {
+    if path.exists() {
+        return Err(CliError::Config(format!(
+            "{} already exists",
+            path.display()
+        )));
+    }
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| CliError::Config(format!("invalid keystore path {}", path.display())))?;
+    std::fs::create_dir_all(dir)?;
+    eth_keystore::encrypt_key(
+        dir,
+        &mut rand::thread_rng(),
+        private_key.to_bytes_be(),
+        password,
+        Some(name),
+    )?;
+    Ok(())
+}
+
+pub fn load(path: &Path, password: &str) -> Result<LocalWallet, CliError> {
+    let bytes = eth_keystore::decrypt_key(path, password)?;
+    Ok(LocalWallet::from_private_key(Felt::from_bytes_be(&bytes)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_round_trip_through_the_keystore() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys").join("signer.json");
+        let key = random_key();
+        create(&path, key, "hunter2").unwrap();
+        assert!(matches!(
+            create(&path, key, "hunter2"),
+            Err(CliError::Config(message)) if message.ends_with("already exists")
+        ));
+
+        let wallet = load(&path, "hunter2").unwrap();
+        assert_eq!(
+            wallet.public_key(),
+            LocalWallet::from_private_key(key).public_key()
+        );
+        assert!(matches!(load(&path, "hunter3"), Err(CliError::Keystore(_))));
+    }
+
+    #[test]
+    fn new_passwords_must_be_typed_twice() {
+        let mut answers = vec!["hunter2", "hunter2"].into_iter();
+        let password = confirm_password(|_| Ok(answers.next().unwrap().to_string())).unwrap();
+        assert_eq!(password, "hunter2");
+
+        let mut answers = vec!["hunter2", "hunter3"].into_iter();
+        assert!(matches!(
+            confirm_password(|_| Ok(answers.next().unwrap().to_string())),
+            Err(CliError::InvalidArgument(_))
+        ));
+    }
+}
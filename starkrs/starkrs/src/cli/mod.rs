@@ -0,0 +1,215 @@
+//! The `starkrs` command-line tool.
+
+mod account;
+mod config;
+mod error;
+mod keystore;
+mod output;
+mod read;
+mod signer;
+mod util;
+mod write;
+
+use account::AccountCommand;
+use config::ConnectionArgs;
+use error::CliError;
+use output::Format;
+use read::ReadCommand;
+use signer::SignerCommand;
+use util::UtilCommand;
+use write::WriteCommand;
+
+use clap::{Parser, Subcommand};
+use stark_provider::rpc::{JsonRpcClient, ProviderError};
+use stark_provider::transport::{HttpTransport, WsTransport};
+use std::sync::Arc;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "starkrs",
+    version,
+    about = "A command-line client for Starknet nodes"
+)]
+pub struct Cli {
+    #[command(flatten)]
+    pub connection: ConnectionArgs,
+
+    /// Print JSON instead of tables.
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    #[command(flatten)]
+    Read(ReadCommand),
+    #[command(flatten)]
+    Write(WriteCommand),
+    /// Manage signing keys.
+    #[command(subcommand)]
+    Signer(SignerCommand),
+    /// Create, deploy and import accounts.
+    #[command(subcommand)]
+    Account(AccountCommand),
+    /// Hashes, encodings and unit conversions.
+    #[command(subcommand)]
+    Util(UtilCommand),
+}
+
+impl Cli {
+    pub fn format(&self) -> Format {
+        if self.json {
+            Format::Json
+        } else {
+            Format::Table
+        }
+    }
+}
+
+/// Runs the parsed command line and prints its result.
+pub async fn run(cli: Cli) -> Result<(), CliError> {
+    let format = cli.format();
+    // Key management, account initialization and utilities work offline.
+    let command = match cli.command {
+        Command::Signer(command) => return print(signer::execute(command)?, format),
+        Command::Util(command) => return print(util::execute(command)?, format),
+        Command::Account(AccountCommand::Init(args)) => return print(account::init(args)?, format),
+        command => command,
+    };
+    let url = cli.connection.rpc_url()?;
+    let value = match url.scheme() {
+        "http" | "https" => {
+            let provider = JsonRpcClient::new(HttpTransport::new(url));
+            dispatch(provider, command).await?
+        }
+        "ws" | "wss" => {
+            let transport = WsTransport::connect(url)
+                .await
+                .map_err(ProviderError::from)?;
+            dispatch(JsonRpcClient::new(transport), command).await?
+        }
+        scheme => {
+            return Err(CliError::Config(format!(
+                "unsupported RPC URL scheme `{scheme}`"
+            )))
+        }
+    };
+    print(value, format)
+}
+
+fn print(value: serde_json::Value, format: Format) -> Result<(), CliError> {
+    println!("{}", output::render(&value, format));
+    Ok(())
+}
+
+async fn dispatch<P: stark_provider::Provider>(
+    provider: P,
+    command: Command,
+) -> Result<serde_json::Value, CliError> {
+    let provider = Arc::new(provider);
+    match command {
+        Command::Read(command) => read::execute(provider.as_ref(), command).await,
+        Command::Write(command) => write::execute(provider, command).await,
+        Command::Account(command) => account::execute(provider, command).await,
+        Command::Signer(command) => signer::execute(command),
+        Command::Util(command) => util::execute(command),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stark_core::types::request::BlockNumber;
+
+    #[test]
+    fn parses_read_commands_with_global_flags() {
+        let cli = Cli::try_parse_from([
+            "starkrs",
+            "storage",
+            "0x49d3",
+            "5",
+            "--block",
+            "95812",
+            "--json",
+            "--rpc-url",
+            "http://localhost:5050",
+        ])
+        .unwrap();
+        assert_eq!(cli.format(), Format::Json);
+        assert_eq!(
+            cli.connection.rpc_url.unwrap().as_str(),
+            "http://localhost:5050/"
+        );
+        match cli.command {
+            Command::Read(ReadCommand::Storage {
+                address,
+                key,
+                block,
+            }) => {
+                assert_eq!(address, "0x49d3".parse().unwrap());
+                assert_eq!(key, 5u64.into());
+                assert!(matches!(block.id, BlockNumber::Number(95812)));
+            }
+            other => panic!("parsed {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_arguments() {
+        assert!(Cli::try_parse_from(["starkrs", "nonce", "0xzz"]).is_err());
+        assert!(Cli::try_parse_from(["starkrs", "block", "yesterday"]).is_err());
+        assert!(Cli::try_parse_from(["starkrs", "call", "0x1"]).is_err());
+    }
+
+    #[test]
+    fn call_takes_trailing_calldata() {
+        let cli = Cli::try_parse_from(["starkrs", "call", "0x1", "balanceOf", "0x2", "3"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Read(ReadCommand::Call { function, args, .. })
+                if function == "balanceOf" && args == vec![2u64.into(), 3u64.into()]
+        ));
+    }
+
+    #[test]
+    fn parses_account_and_write_commands() {
+        let cli = Cli::try_parse_from([
+            "starkrs",
+            "invoke",
+            "0x1",
+            "transfer",
+            "0x2",
+            "--account",
+            "account.json",
+            "--keystore",
+            "signer.json",
+            "--fee-token",
+            "strk",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Write(WriteCommand::Invoke { account, .. })
+                if account.keystore.keystore.to_str() == Some("signer.json")
+        ));
+
+        let cli = Cli::try_parse_from([
+            "starkrs",
+            "account",
+            "init",
+            "account.json",
+            "--keystore",
+            "signer.json",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Account(AccountCommand::Init(args))
+                if args.class_hash == stark_accounts::deployment::OZ_ACCOUNT_CLASS_HASH.parse().unwrap()
+        ));
+        assert!(Cli::try_parse_from(["starkrs", "declare", "class.json"]).is_err());
+    }
+}
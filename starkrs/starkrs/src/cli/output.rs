@@ -0,0 +1,180 @@
+use serde_json::{Map, Value};
+
+/// How command results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Pretty-printed JSON in the shapes of the JSON-RPC spec.
+    Json,
+    /// Aligned columns for people.
+    Table,
+}
+
+/// Renders a command result.
+///
+/// As a table, an object becomes `field  value` rows with nested fields flattened to dotted
+/// paths, a list of objects becomes one row per entry under a header, and anything else is
+/// printed one value per line.
+pub fn render(value: &Value, format: Format) -> String {
+    match format {
+        Format::Json => serde_json::to_string_pretty(value).expect("a Value always serializes"),
+        Format::Table => match value {
+            Value::Object(object) => fields(object),
+            Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object) => {
+                rows(items)
+            }
+            Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join("\n"),
+            scalar => cell(scalar),
+        },
+    }
+}
+
+fn fields(object: &Map<String, Value>) -> String {
+    let mut entries = Vec::new();
+    flatten("", object, &mut entries);
+    let width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    for (key, values) in entries {
+        let mut values = values.into_iter();
+        let first = values.next().unwrap_or_default();
+        lines.push(format!("{key:width$}  {first}").trim_end().to_string());
+        lines.extend(values.map(|value| format!("{:width$}  {value}", "")));
+    }
+    lines.join("\n")
+}
+
+/// Collects one entry per leaf; lists of plain values keep one value per line.
+fn flatten(prefix: &str, object: &Map<String, Value>, entries: &mut Vec<(String, Vec<String>)>) {
+    for (key, value) in object {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Value::Object(inner) => flatten(&path, inner, entries),
+            Value::Array(items) if items.iter().any(Value::is_object) => {
+                for (index, item) in items.iter().enumerate() {
+                    let path = format!("{path}[{index}]");
+                    match item {
+                        Value::Object(inner) => flatten(&path, inner, entries),
+                        other => entries.push((path, vec![cell(other)])),
+                    }
+                }
+            }
+            Value::Array(items) if items.is_empty() => entries.push((path, vec!["[]".into()])),
+            Value::Array(items) => entries.push((path, items.iter().map(cell).collect())),
+            scalar => entries.push((path, vec![cell(scalar)])),
+        }
+    }
+}
+
+fn rows(items: &[Value]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for item in items {
+        for key in item.as_object().into_iter().flat_map(Map::keys) {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+    let cells: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            columns
+                .iter()
+                .map(|column| item.get(column).map(cell).unwrap_or_else(|| "-".into()))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            cells
+                .iter()
+                .map(|row| row[index].len())
+                .chain([column.len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |row: Vec<String>| {
+        row.iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let header = line(columns.iter().map(|column| column.to_uppercase()).collect());
+    std::iter::once(header)
+        .chain(cells.into_iter().map(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".into(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn objects_become_aligned_fields() {
+        let block = json!({
+            "block_number": 95812,
+            "status": "ACCEPTED_ON_L1",
+            "l1_gas_price": {"price_in_wei": "0x3b9aca00"},
+            "transactions": ["0x1", "0x2"],
+            "events": [],
+        });
+        assert_eq!(
+            render(&block, Format::Table),
+            [
+                "block_number               95812",
+                "events                     []",
+                "l1_gas_price.price_in_wei  0x3b9aca00",
+                "status                     ACCEPTED_ON_L1",
+                "transactions               0x1",
+                "                           0x2",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn lists_of_objects_become_rows() {
+        let events = json!([
+            {"from_address": "0x49d3", "keys": ["0x99", "0x1"], "block_number": 7},
+            {"from_address": "0x1", "keys": [], "block_number": null},
+        ]);
+        assert_eq!(
+            render(&events, Format::Table),
+            [
+                "BLOCK_NUMBER  FROM_ADDRESS  KEYS",
+                "7             0x49d3        0x99, 0x1",
+                "-             0x1",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn scalars_print_bare() {
+        assert_eq!(
+            render(&json!("0x534e5f4d41494e"), Format::Table),
+            "0x534e5f4d41494e"
+        );
+        assert_eq!(render(&json!(["0x1", "0x2"]), Format::Table), "0x1\n0x2");
+        assert_eq!(render(&json!({"a": 1}), Format::Json), "{\n  \"a\": 1\n}");
+    }
+}
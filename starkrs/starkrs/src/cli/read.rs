@@ -0,0 +1,313 @@
+use super::error::CliError;
+use clap::Subcommand;
+use futures::{StreamExt, TryStreamExt};
+use serde_json::Value;
+use stark_core::types::event::EventFilter;
+use stark_core::types::request::{BlockNumber, BlockTag, FunctionCall};
+use stark_core::utils::hash::get_selector_from_name;
+use stark_core::Felt;
+use stark_provider::Provider;
+
+/// Commands that only read chain state.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ReadCommand {
+    /// The number of the latest block.
+    BlockNumber,
+    /// A block: `latest`, `pending`, a number or a hash.
+    Block {
+        #[arg(value_parser = parse_block_id, default_value = "latest")]
+        id: BlockNumber,
+        /// Include the full transactions instead of their hashes.
+        #[arg(long)]
+        full: bool,
+    },
+    /// A transaction by hash.
+    Tx { hash: Felt },
+    /// The receipt of a transaction.
+    Receipt { hash: Felt },
+    /// The nonce of an account.
+    Nonce {
+        address: Felt,
+        #[command(flatten)]
+        block: BlockArg,
+    },
+    /// The value of a storage slot.
+    Storage {
+        address: Felt,
+        key: Felt,
+        #[command(flatten)]
+        block: BlockArg,
+    },
+    /// The class hash of a deployed contract.
+    ClassHashAt {
+        address: Felt,
+        #[command(flatten)]
+        block: BlockArg,
+    },
+    /// Calls a view function and prints what it returns.
+    Call {
+        address: Felt,
+        /// The function name, or its selector as hex.
+        function: String,
+        /// The calldata, as decimal or hex field elements.
+        args: Vec<Felt>,
+        #[command(flatten)]
+        block: BlockArg,
+    },
+    /// Events matching a filter, oldest first.
+    Events {
+        #[arg(long, value_parser = parse_block_id)]
+        from_block: Option<BlockNumber>,
+        #[arg(long, value_parser = parse_block_id)]
+        to_block: Option<BlockNumber>,
+        /// Only events emitted by this contract.
+        #[arg(long)]
+        address: Option<Felt>,
+        /// The accepted values of one key position, comma separated; repeat for the following
+        /// positions. An empty value accepts any key.
+        #[arg(long = "keys")]
+        keys: Vec<String>,
+        /// Stop after this many events.
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+        /// How many events to request per page.
+        #[arg(long, default_value_t = 100)]
+        chunk_size: u64,
+    },
+    /// The chain id, such as `SN_MAIN` encoded as a field element.
+    ChainId,
+    /// The node's sync progress, or `false` when it is synced.
+    Syncing,
+}
+
+/// The block to read state at.
+#[derive(Debug, Clone, clap::Args)]
+pub struct BlockArg {
+    /// `latest`, `pending`, a block number or a block hash.
+    #[arg(long = "block", value_parser = parse_block_id, default_value = "latest")]
+    pub id: BlockNumber,
+}
+
+/// Parses a block id the way the commands accept it: a tag, a decimal number or a hex hash.
+pub fn parse_block_id(value: &str) -> Result<BlockNumber, String> {
+    match value {
+        "latest" => Ok(BlockNumber::BlockTag(BlockTag::Latest)),
+        "pending" => Ok(BlockNumber::BlockTag(BlockTag::Pending)),
+        hash if hash.starts_with("0x") => Felt::from_hex(hash)
+            .map(|hash| BlockNumber::Hash(hash.to_string()))
+            .map_err(|err| format!("invalid block hash: {err}")),
+        number => number.parse().map(BlockNumber::Number).map_err(|_| {
+            format!("expected `latest`, `pending`, a block number or a hash, got `{number}`")
+        }),
+    }
+}
+
+/// Runs `command` against `provider` and returns its result as JSON.
+pub async fn execute<P: Provider>(provider: &P, command: ReadCommand) -> Result<Value, CliError> {
+    let value = match command {
+        ReadCommand::BlockNumber => serde_json::to_value(provider.stark_block_number().await?)?,
+        ReadCommand::Block { id, full: false } => {
+            serde_json::to_value(provider.get_block_with_tx_hashes(id).await?)?
+        }
+        ReadCommand::Block { id, full: true } => {
+            serde_json::to_value(provider.get_block_with_txs(id).await?)?
+        }
+        ReadCommand::Tx { hash } => {
+            serde_json::to_value(provider.get_transaction_by_hash(hash).await?)?
+        }
+        ReadCommand::Receipt { hash } => {
+            serde_json::to_value(provider.get_transaction_receipt(hash).await?)?
+        }
+        ReadCommand::Nonce { address, block } => {
+            serde_json::to_value(provider.stark_getnonce(address, block.id).await?)?
+        }
+        ReadCommand::Storage {
+            address,
+            key,
+            block,
+        } => serde_json::to_value(provider.get_storage_at(address, key, block.id).await?)?,
+        ReadCommand::ClassHashAt { address, block } => {
+            serde_json::to_value(provider.get_class_hash_at(block.id, address).await?)?
+        }
+        ReadCommand::Call {
+            address,
+            function,
+            args,
+            block,
+        } => {
+            let request = FunctionCall {
+                contract_address: address,
+                entry_point_selector: selector(&function)?,
+                calldata: args,
+            };
+            serde_json::to_value(provider.call(request, block.id).await?)?
+        }
+        ReadCommand::Events {
+            from_block,
+            to_block,
+            address,
+            keys,
+            limit,
+            chunk_size,
+        } => {
+            let filter = EventFilter {
+                from_block,
+                to_block,
+                address,
+                keys: keys
+                    .iter()
+                    .map(|position| parse_keys(position))
+                    .collect::<Result<_, _>>()?,
+            };
+            let events: Vec<_> = provider
+                .events_stream(filter)
+                .chunk_size(chunk_size)
+                .take(limit)
+                .try_collect()
+                .await?;
+            serde_json::to_value(events)?
+        }
+        ReadCommand::ChainId => serde_json::to_value(provider.stark_chain_id().await?)?,
+        ReadCommand::Syncing => serde_json::to_value(provider.syncing().await?)?,
+    };
+    Ok(value)
+}
+
+/// Resolves a function name or a hex selector.
+pub fn selector(function: &str) -> Result<Felt, CliError> {
+    if function.starts_with("0x") {
+        Ok(Felt::from_hex(function)?)
+    } else {
+        Ok(get_selector_from_name(function))
+    }
+}
+
+fn parse_keys(position: &str) -> Result<Vec<Felt>, CliError> {
+    position
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(|key| key.parse().map_err(CliError::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use stark_provider::rpc::JsonRpcClient;
+    use stark_provider::transport::MemoryTransport;
+    use std::sync::{Arc, Mutex};
+
+    /// A client whose node answers each method with the listed result, and the requests it got.
+    fn node(
+        results: Vec<(&'static str, Value)>,
+    ) -> (JsonRpcClient<MemoryTransport>, Arc<Mutex<Vec<Value>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        let transport = MemoryTransport::with_handler(move |request| {
+            seen.lock().unwrap().push(request.clone());
+            let method = request["method"].as_str().unwrap();
+            let result = results
+                .iter()
+                .find(|(name, _)| *name == method)
+                .map(|(_, result)| result.clone())
+                .unwrap_or_else(|| panic!("unexpected {method}"));
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+        });
+        (JsonRpcClient::new(transport), requests)
+    }
+
+    #[test]
+    fn parses_block_ids() {
+        assert!(matches!(
+            parse_block_id("pending"),
+            Ok(BlockNumber::BlockTag(BlockTag::Pending))
+        ));
+        assert!(matches!(
+            parse_block_id("95812"),
+            Ok(BlockNumber::Number(95812))
+        ));
+        assert!(matches!(
+            parse_block_id("0x00abc"),
+            Ok(BlockNumber::Hash(hash)) if hash == "0xabc"
+        ));
+        assert!(parse_block_id("yesterday").is_err());
+    }
+
+    #[tokio::test]
+    async fn call_encodes_the_selector_and_calldata() {
+        let (provider, requests) = node(vec![("starknet_call", json!(["0x2a", "0x0"]))]);
+        let command = ReadCommand::Call {
+            address: Felt::from(0x49d3u64),
+            function: "balanceOf".to_string(),
+            args: vec![Felt::from(0x7u64)],
+            block: BlockArg {
+                id: BlockNumber::Number(10),
+            },
+        };
+        let result = execute(&provider, command).await.unwrap();
+        assert_eq!(result, json!(["0x2a", "0x0"]));
+        assert_eq!(
+            requests.lock().unwrap()[0]["params"],
+            json!([
+                {
+                    "contract_address": "0x49d3",
+                    "entry_point_selector": get_selector_from_name("balanceOf").to_string(),
+                    "calldata": ["0x7"],
+                },
+                {"block_number": 10},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn events_follow_pages_up_to_the_limit() {
+        let event = |n: u64| {
+            json!({
+                "from_address": "0x49d3",
+                "keys": ["0x99"],
+                "data": [format!("{n:#x}")],
+                "block_number": n,
+                "block_hash": "0x1",
+                "transaction_hash": "0x2",
+            })
+        };
+        let (provider, requests) = node(vec![
+            ("starknet_blockNumber", json!(5)),
+            (
+                "starknet_getEvents",
+                json!({"events": [event(1), event(2)], "continuation_token": "next"}),
+            ),
+        ]);
+        let command = ReadCommand::Events {
+            from_block: Some(BlockNumber::Number(1)),
+            to_block: None,
+            address: None,
+            keys: vec!["0x99, 0x98".to_string(), String::new()],
+            limit: 3,
+            chunk_size: 2,
+        };
+        let result = execute(&provider, command).await.unwrap();
+        assert_eq!(result, json!([event(1), event(2), event(1)]));
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[1]["params"][0],
+            json!({
+                "from_block": {"block_number": 1},
+                "to_block": {"block_number": 5},
+                "keys": [["0x99", "0x98"], []],
+                "chunk_size": 2,
+            })
+        );
+        assert_eq!(requests[2]["params"][0]["continuation_token"], "next");
+    }
+
+    #[tokio::test]
+    async fn syncing_reads_false_when_synced() {
+        let (provider, _) = node(vec![("starknet_syncing", json!(false))]);
+        let result = execute(&provider, ReadCommand::Syncing).await.unwrap();
+        assert_eq!(result, json!(false));
+    }
+}
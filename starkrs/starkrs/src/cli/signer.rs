@@ -0,0 +1,100 @@
+use super::error::CliError;
+use super::keystore::{self, PasswordArgs};
+use clap::Subcommand;
+use serde_json::{json, Value};
+use stark_accounts::AccountError;
+use stark_core::Felt;
+use std::path::PathBuf;
+
+/// Commands that manage signing keys; none of them talk to a node.
+#[derive(Debug, Clone, Subcommand)]
+pub enum SignerCommand {
+    /// Generates a random key and stores it in a new encrypted keystore.
+    Keygen {
+        /// Where to write the keystore.
+        keystore: PathBuf,
+        #[command(flatten)]
+        password: PasswordArgs,
+    },
+    /// Prints the public key of a keystore.
+    Inspect {
+        keystore: PathBuf,
+        #[command(flatten)]
+        password: PasswordArgs,
+    },
+    /// Signs a hash with the key in a keystore.
+    Sign {
+        keystore: PathBuf,
+        hash: Felt,
+        #[command(flatten)]
+        password: PasswordArgs,
+    },
+}
+
+pub fn execute(command: SignerCommand) -> Result<Value, CliError> {
+    match command {
+        SignerCommand::Keygen { keystore, password } => {
+            let password = password.new_password()?;
+            let private_key = keystore::random_key();
+            keystore::create(&keystore, private_key, &password)?;
+            let public_key = keystore::load(&keystore, &password)?.public_key();
+            Ok(json!({"keystore": keystore, "public_key": public_key}))
+        }
+        SignerCommand::Inspect { keystore, password } => {
+            let password = password.password("Keystore password: ")?;
+            let wallet = keystore::load(&keystore, &password)?;
+            Ok(json!({"public_key": wallet.public_key()}))
+        }
+        SignerCommand::Sign {
+            keystore,
+            hash,
+            password,
+        } => {
+            let password = password.password("Keystore password: ")?;
+            let signature = keystore::load(&keystore, &password)?
+                .sign(&hash)
+                .map_err(AccountError::Signing)?;
+            Ok(json!({"r": signature.r, "s": signature.s}))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stark_accounts::signer::{verify_signature, Signature};
+
+    #[test]
+    fn signs_with_a_generated_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signer.json");
+        let password = PasswordArgs {
+            keystore_password: Some("hunter2".to_string()),
+        };
+        let generated = execute(SignerCommand::Keygen {
+            keystore: path.clone(),
+            password: password.clone(),
+        })
+        .unwrap();
+        let inspected = execute(SignerCommand::Inspect {
+            keystore: path.clone(),
+            password: password.clone(),
+        })
+        .unwrap();
+        assert_eq!(generated["public_key"], inspected["public_key"]);
+
+        let hash = Felt::from(0x2abu64);
+        let signature = execute(SignerCommand::Sign {
+            keystore: path,
+            hash,
+            password,
+        })
+        .unwrap();
+        let public_key: Felt = serde_json::from_value(inspected["public_key"].clone()).unwrap();
+        let signature = Signature {
+            r: serde_json::from_value(signature["r"].clone()).unwrap(),
+            s: serde_json::from_value(signature["s"].clone()).unwrap(),
+        };
+        assert!(verify_signature(&public_key, &hash, &signature));
+    }
+}
@@ -0,0 +1,294 @@
+use super::error::CliError;
+use super::write::{read_json, read_sierra_class};
+use clap::Subcommand;
+use ethers::types::U256;
+use ethers::utils::{format_units, parse_units};
+use serde_json::{json, Value};
+use stark_core::types::contract::CompiledClass;
+use stark_core::types::transaction::{BroadcastedDeclareTransaction, BroadcastedTransaction};
+use stark_core::utils::hash::{
+    calculate_contract_address, get_selector_from_name, get_storage_var_address, pedersen_hash,
+    poseidon_hash_many, starknet_keccak,
+};
+use stark_core::Felt;
+use std::path::PathBuf;
+
+/// ETH and STRK both have 18 decimals, counted in wei and fri.
+const FEE_TOKEN_DECIMALS: u32 = 18;
+
+/// Offline helpers for hashes, encodings and units.
+#[derive(Debug, Clone, Subcommand)]
+pub enum UtilCommand {
+    /// The selector of an entry point or event name.
+    Selector { name: String },
+    /// The Keccak-256 of a UTF-8 string truncated to 250 bits.
+    StarknetKeccak { data: String },
+    /// The Pedersen hash of two field elements.
+    Pedersen { x: Felt, y: Felt },
+    /// The Poseidon hash of a list of field elements, as Cairo's `poseidon_hash_span`.
+    Poseidon {
+        #[arg(required = true)]
+        values: Vec<Felt>,
+    },
+    /// Decodes a field element as a Cairo short string.
+    ToShortString { value: Felt },
+    /// Encodes up to 31 ASCII characters as a field element.
+    FromShortString { value: String },
+    /// Prints a decimal or hex number as a field element in both bases.
+    ToFelt { value: Felt },
+    /// Splits a 256-bit number into the low and high 128-bit felts of a Cairo `u256`.
+    SplitU256 {
+        #[arg(value_parser = parse_u256)]
+        value: U256,
+    },
+    /// The address of a storage variable, at the given keys for mappings.
+    StorageAddress { name: String, keys: Vec<Felt> },
+    /// The address a contract is deployed at.
+    ContractAddress {
+        class_hash: Felt,
+        /// The constructor calldata.
+        args: Vec<Felt>,
+        #[arg(long)]
+        salt: Felt,
+        /// The deploying contract; zero for account deployments and non-unique UDC deployments.
+        #[arg(long, default_value = "0")]
+        deployer: Felt,
+    },
+    /// The hash of a Sierra class or of a compiled (CASM) class.
+    ClassHash { file: PathBuf },
+    /// The hash of a broadcasted transaction in JSON-RPC format.
+    TxHash {
+        file: PathBuf,
+        /// The chain id, as a felt or a short string such as `SN_SEPOLIA`.
+        #[arg(long, value_parser = parse_chain_id)]
+        chain_id: Felt,
+    },
+    /// Converts an amount of ETH to wei.
+    ToWei { amount: String },
+    /// Converts an amount of wei to ETH.
+    FromWei {
+        #[arg(value_parser = parse_u256)]
+        amount: U256,
+    },
+    /// Converts an amount of STRK to fri.
+    ToFri { amount: String },
+    /// Converts an amount of fri to STRK.
+    FromFri {
+        #[arg(value_parser = parse_u256)]
+        amount: U256,
+    },
+}
+
+pub fn execute(command: UtilCommand) -> Result<Value, CliError> {
+    let value = match command {
+        UtilCommand::Selector { name } => json!(get_selector_from_name(&name)),
+        UtilCommand::StarknetKeccak { data } => json!(starknet_keccak(data.as_bytes())),
+        UtilCommand::Pedersen { x, y } => json!(pedersen_hash(&x, &y)),
+        UtilCommand::Poseidon { values } => json!(poseidon_hash_many(&values)),
+        UtilCommand::ToShortString { value } => json!(value.to_short_string()?),
+        UtilCommand::FromShortString { value } => json!(Felt::from_short_string(&value)?),
+        UtilCommand::ToFelt { value } => json!({"hex": value, "dec": value.to_dec_string()}),
+        UtilCommand::SplitU256 { value } => {
+            let low = value.low_u128();
+            let high = (value >> 128).low_u128();
+            json!({"low": Felt::from(low), "high": Felt::from(high)})
+        }
+        UtilCommand::StorageAddress { name, keys } => json!(get_storage_var_address(&name, &keys)),
+        UtilCommand::ContractAddress {
+            class_hash,
+            args,
+            salt,
+            deployer,
+        } => json!(calculate_contract_address(
+            salt, class_hash, &args, deployer
+        )),
+        UtilCommand::ClassHash { file } => {
+            let class: Value = read_json(&file)?;
+            if class.get("sierra_program").is_some() {
+                json!(read_sierra_class(&file)?.class_hash())
+            } else if class.get("bytecode").is_some() {
+                json!(read_json::<CompiledClass>(&file)?.class_hash()?)
+            } else {
+                return Err(CliError::InvalidArgument(format!(
+                    "{} is neither a Sierra nor a compiled class",
+                    file.display()
+                )));
+            }
+        }
+        UtilCommand::TxHash { file, chain_id } => match read_json(&file)? {
+            BroadcastedTransaction::Invoke(tx) => json!(tx.transaction_hash(chain_id)),
+            BroadcastedTransaction::DeployAccount(tx) => json!({
+                "transaction_hash": tx.transaction_hash(chain_id),
+                "contract_address": tx.contract_address(),
+            }),
+            BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V3(tx)) => json!({
+                "transaction_hash": tx.transaction_hash(chain_id),
+                "class_hash": tx.class_hash(),
+            }),
+            BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V1(_)) => {
+                return Err(CliError::InvalidArgument(
+                    "hashing Cairo 0 declarations is not supported".to_string(),
+                ))
+            }
+        },
+        UtilCommand::ToWei { amount } | UtilCommand::ToFri { amount } => {
+            // `parse_units` returns negative amounts as two's complement once converted.
+            if amount.trim_start().starts_with('-') {
+                return Err(CliError::InvalidArgument(format!(
+                    "{amount}: amounts cannot be negative"
+                )));
+            }
+            let units = parse_units(&amount, FEE_TOKEN_DECIMALS)
+                .map_err(|err| CliError::InvalidArgument(format!("{amount}: {err}")))?;
+            json!(U256::from(units).to_string())
+        }
+        UtilCommand::FromWei { amount } | UtilCommand::FromFri { amount } => {
+            json!(format_amount(amount))
+        }
+    };
+    Ok(value)
+}
+
+/// Formats a base-unit amount with 18 decimals, without trailing zeros.
+fn format_amount(amount: U256) -> String {
+    let formatted = format_units(amount, FEE_TOKEN_DECIMALS).expect("18 decimals are valid");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn parse_u256(value: &str) -> Result<U256, String> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(|err| err.to_string()),
+        None => U256::from_dec_str(value).map_err(|err| err.to_string()),
+    };
+    parsed.map_err(|err| format!("invalid 256-bit number: {err}"))
+}
+
+fn parse_chain_id(value: &str) -> Result<Felt, String> {
+    value
+        .parse()
+        .or_else(|_| Felt::from_short_string(value))
+        .map_err(|err| format!("invalid chain id: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(command: UtilCommand) -> Value {
+        execute(command).unwrap()
+    }
+
+    #[test]
+    fn encodes_and_splits_values() {
+        assert_eq!(
+            run(UtilCommand::Selector {
+                name: "transfer".to_string()
+            }),
+            "0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"
+        );
+        assert_eq!(
+            run(UtilCommand::ToShortString {
+                value: Felt::from_hex("0x534e5f4d41494e").unwrap()
+            }),
+            "SN_MAIN"
+        );
+        assert_eq!(
+            run(UtilCommand::ToFelt {
+                value: Felt::from(255u64)
+            }),
+            json!({"hex": "0xff", "dec": "255"})
+        );
+        let value = (U256::from(3u64) << 128) + U256::from(7u64);
+        assert_eq!(
+            run(UtilCommand::SplitU256 { value }),
+            json!({"low": "0x7", "high": "0x3"})
+        );
+        assert_eq!(
+            parse_chain_id("SN_MAIN"),
+            parse_chain_id("0x534e5f4d41494e")
+        );
+    }
+
+    #[test]
+    fn converts_fee_token_units() {
+        assert_eq!(
+            run(UtilCommand::ToWei {
+                amount: "1.5".to_string()
+            }),
+            "1500000000000000000"
+        );
+        assert_eq!(
+            run(UtilCommand::FromFri {
+                amount: parse_u256("1500000000000000000").unwrap()
+            }),
+            "1.5"
+        );
+        assert_eq!(
+            run(UtilCommand::FromWei {
+                amount: U256::from(10u64).pow(18.into())
+            }),
+            "1"
+        );
+        assert!(matches!(
+            execute(UtilCommand::ToFri {
+                amount: "lots".to_string()
+            }),
+            Err(CliError::InvalidArgument(_))
+        ));
+        for amount in ["-1", "-0.5", "-0"] {
+            assert!(matches!(
+                execute(UtilCommand::ToWei {
+                    amount: amount.to_string()
+                }),
+                Err(CliError::InvalidArgument(message)) if message.contains("negative")
+            ));
+        }
+    }
+
+    #[test]
+    fn selects_default_entry_points() {
+        assert_eq!(
+            run(UtilCommand::Selector {
+                name: "__default__".to_string()
+            }),
+            "0x0"
+        );
+        assert_eq!(
+            run(UtilCommand::Selector {
+                name: "__l1_default__".to_string()
+            }),
+            "0x0"
+        );
+    }
+
+    #[test]
+    fn hashes_transaction_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tx.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "type": "INVOKE",
+                "version": "0x1",
+                "sender_address": "0x1234",
+                "calldata": ["0x1", "0x2"],
+                "max_fee": "0x3e8",
+                "signature": [],
+                "nonce": "0x5"
+            }"#,
+        )
+        .unwrap();
+        // The v1 invoke hash h("invoke", 1, sender, 0, h(calldata), max_fee, chain_id, nonce),
+        // computed from this field list rather than through the transaction types.
+        assert_eq!(
+            run(UtilCommand::TxHash {
+                file: path,
+                chain_id: Felt::from_short_string("SN_SEPOLIA").unwrap(),
+            }),
+            "0x449ae2a693aea419bfdad31dceea0cb0e12ba4c7e9d70c162711d241893ad9a"
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use super::account::AccountArgs;
+use super::error::CliError;
+use super::read::selector;
+use clap::{Args, Subcommand, ValueEnum};
+use serde_json::value::RawValue;
+use serde_json::{json, Value};
+use stark_accounts::{Call, ContractDeployment, FeeToken};
+use stark_core::types::contract::{CompiledClass, SierraClass};
+use stark_core::Felt;
+use stark_provider::pending::PendingTransaction;
+use stark_provider::Provider;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Commands that submit transactions from an account.
+#[derive(Debug, Clone, Subcommand)]
+pub enum WriteCommand {
+    /// Calls a contract function from the account.
+    Invoke {
+        address: Felt,
+        /// The function name, or its selector as hex.
+        function: String,
+        /// The calldata, as decimal or hex field elements.
+        args: Vec<Felt>,
+        #[command(flatten)]
+        account: AccountArgs,
+        #[command(flatten)]
+        fee: FeeArgs,
+        #[command(flatten)]
+        wait: WaitArgs,
+    },
+    /// Declares a Sierra class. Declarations always pay in STRK.
+    Declare {
+        /// The Sierra class, as produced by `scarb build`.
+        class: PathBuf,
+        /// The CASM the class compiles to, to derive the compiled class hash from.
+        #[arg(long, required_unless_present = "compiled_class_hash")]
+        casm: Option<PathBuf>,
+        /// The compiled class hash, when the CASM is not at hand.
+        #[arg(long, conflicts_with = "casm")]
+        compiled_class_hash: Option<Felt>,
+        #[command(flatten)]
+        account: AccountArgs,
+        #[command(flatten)]
+        wait: WaitArgs,
+    },
+    /// Deploys a contract through the Universal Deployer Contract.
+    Deploy {
+        class_hash: Felt,
+        /// The constructor calldata, as decimal or hex field elements.
+        args: Vec<Felt>,
+        /// The deployment salt; random when not given.
+        #[arg(long)]
+        salt: Option<Felt>,
+        /// Derive the address from the salt alone instead of mixing in the account address.
+        #[arg(long)]
+        not_unique: bool,
+        #[command(flatten)]
+        account: AccountArgs,
+        #[command(flatten)]
+        fee: FeeArgs,
+        #[command(flatten)]
+        wait: WaitArgs,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FeeTokenArg {
+    Eth,
+    Strk,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct FeeArgs {
+    /// The token to pay fees in: ETH through V1 transactions, STRK through V3 transactions.
+    #[arg(long, value_enum, default_value = "eth")]
+    pub fee_token: FeeTokenArg,
+
+    /// The maximum ETH fee in wei, instead of an estimate with a safety margin.
+    #[arg(long)]
+    pub max_fee: Option<Felt>,
+}
+
+impl FeeArgs {
+    pub fn fee_token(&self) -> Result<FeeToken, CliError> {
+        match (self.fee_token, self.max_fee) {
+            (FeeTokenArg::Eth, _) => Ok(FeeToken::Eth),
+            (FeeTokenArg::Strk, None) => Ok(FeeToken::Strk),
+            (FeeTokenArg::Strk, Some(_)) => Err(CliError::InvalidArgument(
+                "--max-fee only applies to ETH fees".to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct WaitArgs {
+    /// Return once the transaction is submitted instead of waiting for it to be accepted.
+    #[arg(long)]
+    pub no_wait: bool,
+}
+
+impl WaitArgs {
+    /// Waits for `pending` unless told not to, adding the outcome to `result`.
+    pub async fn finish<P: Provider>(
+        &self,
+        pending: PendingTransaction<'_, P>,
+        mut result: Value,
+    ) -> Result<Value, CliError> {
+        if self.no_wait {
+            return Ok(result);
+        }
+        eprintln!(
+            "Waiting for {} to be accepted...",
+            pending.transaction_hash()
+        );
+        let receipt = pending.await?;
+        result["finality_status"] = json!(receipt.finality_status);
+        result["block_number"] = json!(receipt.block_number);
+        result["actual_fee"] = json!(receipt.actual_fee);
+        Ok(result)
+    }
+}
+
+/// Runs `command` from the account it names and returns what it did as JSON.
+pub async fn execute<P: Provider>(
+    provider: Arc<P>,
+    command: WriteCommand,
+) -> Result<Value, CliError> {
+    match command {
+        WriteCommand::Invoke {
+            address,
+            function,
+            args,
+            account,
+            fee,
+            wait,
+        } => {
+            let account = account.load(provider).await?;
+            let call = Call {
+                to: address,
+                selector: selector(&function)?,
+                calldata: args,
+            };
+            let mut execution = account.execute(vec![call]).fee_token(fee.fee_token()?);
+            if let Some(max_fee) = fee.max_fee {
+                execution = execution.max_fee(max_fee);
+            }
+            let pending = execution.send().await?;
+            let result = json!({"transaction_hash": pending.transaction_hash()});
+            wait.finish(pending, result).await
+        }
+        WriteCommand::Declare {
+            class,
+            casm,
+            compiled_class_hash,
+            account,
+            wait,
+        } => {
+            let contract_class = read_sierra_class(&class)?;
+            let compiled_class_hash = match (compiled_class_hash, casm) {
+                (Some(hash), _) => hash,
+                (None, Some(casm)) => read_json::<CompiledClass>(&casm)?.class_hash()?,
+                (None, None) => unreachable!("clap requires --casm without --compiled-class-hash"),
+            };
+            let account = account.load(provider).await?;
+            let declaration = account.declare(contract_class, compiled_class_hash);
+            let class_hash = declaration.class_hash();
+            let pending = declaration.send().await?;
+            let result = json!({
+                "transaction_hash": pending.transaction_hash(),
+                "class_hash": class_hash,
+            });
+            wait.finish(pending, result).await
+        }
+        WriteCommand::Deploy {
+            class_hash,
+            args,
+            salt,
+            not_unique,
+            account,
+            fee,
+            wait,
+        } => {
+            let account = account.load(provider).await?;
+            let deployment = ContractDeployment {
+                class_hash,
+                salt: salt.unwrap_or_else(super::keystore::random_key),
+                unique: !not_unique,
+                constructor_calldata: args,
+            };
+            let mut execution = account
+                .execute(vec![deployment.call()])
+                .fee_token(fee.fee_token()?);
+            if let Some(max_fee) = fee.max_fee {
+                execution = execution.max_fee(max_fee);
+            }
+            let pending = execution.send().await?;
+            let result = json!({
+                "transaction_hash": pending.transaction_hash(),
+                "contract_address": deployment.address(account.address()),
+                "salt": deployment.salt,
+            });
+            wait.finish(pending, result).await
+        }
+    }
+}
+
+pub fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, CliError> {
+    let contents = std::fs::read_to_string(path)?;
+    serde_json::from_str(&contents)
+        .map_err(|err| CliError::Config(format!("{}: {err}", path.display())))
+}
+
+/// Reads a Sierra class from the JSON-RPC format or from a compiler artifact. An artifact's ABI
+/// is a JSON list rather than a string; it is declared as that list minified, in its original
+/// field order, since the class hash commits to the exact ABI string.
+pub fn read_sierra_class(path: &Path) -> Result<SierraClass, CliError> {
+    #[derive(serde::Deserialize)]
+    struct RawAbi {
+        abi: Option<Box<RawValue>>,
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    let invalid = |err: serde_json::Error| CliError::Config(format!("{}: {err}", path.display()));
+    let mut class: Value = serde_json::from_str(&contents).map_err(invalid)?;
+    let raw: RawAbi = serde_json::from_str(&contents).map_err(invalid)?;
+    if let Some(abi) = raw.abi.filter(|abi| !abi.get().starts_with('"')) {
+        class["abi"] = Value::String(minify(abi.get()));
+    }
+    serde_json::from_value(class).map_err(invalid)
+}
+
+/// Drops the whitespace between the tokens of a JSON document.
+fn minify(json: &str) -> String {
+    let mut minified = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c.is_whitespace() {
+            continue;
+        } else if c == '"' {
+            in_string = true;
+        }
+        minified.push(c);
+    }
+    minified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiler_artifacts_are_flattened() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contract.contract_class.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "sierra_program": ["0x1"],
+                "sierra_program_debug_info": {"type_names": []},
+                "contract_class_version": "0.1.0",
+                "entry_points_by_type": {"EXTERNAL": [], "L1_HANDLER": [], "CONSTRUCTOR": []},
+                "abi": [
+                    {"type": "function", "name": "say \"hi\" "}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let class = read_sierra_class(&path).unwrap();
+        assert_eq!(
+            class.abi.as_deref(),
+            Some(r#"[{"type":"function","name":"say \"hi\" "}]"#)
+        );
+    }
+
+    #[test]
+    fn max_fee_requires_eth() {
+        let fee = FeeArgs {
+            fee_token: FeeTokenArg::Strk,
+            max_fee: Some(Felt::ONE),
+        };
+        assert!(matches!(fee.fee_token(), Err(CliError::InvalidArgument(_))));
+    }
+}
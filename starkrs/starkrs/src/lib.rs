@@ -6,7 +6,6 @@ mod tests {
     use ethers::prelude::*;
     #[test]
     fn check_stark_core_utils_constant_zero() {
-        let a = ZERO;
         assert_eq!(ZERO,0);
     }
    
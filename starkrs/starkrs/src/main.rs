@@ -0,0 +1,12 @@
+mod cli;
+
+use clap::Parser;
+
+#[tokio::main]
+async fn main() {
+    let cli = cli::Cli::parse();
+    if let Err(err) = cli::run(cli).await {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}